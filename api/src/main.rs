@@ -1,5 +1,6 @@
 use database::prelude::Client;
 use discord::bot::Bot;
+use log::error;
 use rocket::{fairing::{Fairing, Info, Kind}, Request, Response, http::Header};
 use utils::logging;
 
@@ -36,8 +37,17 @@ async fn rocket() -> _ {
         String::from("discord"),
     ]);
     let db_client = Client::new().await;
-    let discord_bot = Bot::new().await;
     let env = environment::Env::new();
+
+    // one bot shares the api's pool, serving the routes over http while its
+    // gateway connection receives slash commands and other interactions
+    let (discord_bot, mut gateway) = Bot::connect(db_client.clone()).await;
+    rocket::tokio::spawn(async move {
+        if let Err(e) = gateway.start().await {
+            error!("{:?}", e);
+        }
+    });
+
     rocket::build()
         .manage(db_client)
         .manage(discord_bot)
@@ -50,14 +60,39 @@ async fn rocket() -> _ {
                 routes::guild::create_guild,
                 routes::guild::get_guild,
                 routes::guild::get_guild_users,
+                routes::guild::get_leaderboard,
+                routes::guild::get_points,
                 routes::guild::update_guild,
+                routes::guild::update_shame,
+                routes::guild::update_threads,
+                routes::guild::get_notification_channels,
+                routes::guild::update_notification_channel,
+                routes::guild::update_personality,
+                routes::guild::update_locale,
+                routes::guild::get_templates,
+                routes::guild::update_template,
+                routes::guild::preview_template,
                 routes::guild::delete_guild,
             ],
         )
         .mount(
             "/user",
-            routes![routes::user::create_user, routes::user::get_user],
+            routes![
+                routes::user::create_user,
+                routes::user::get_user,
+                routes::user::get_lists,
+                routes::user::get_tasks,
+                routes::user::get_templates,
+                routes::user::update_locale,
+                routes::user::get_stats,
+                routes::user::get_points,
+                routes::user::get_ledger,
+                routes::user::get_partnerships,
+                routes::user::get_delivery_preferences,
+                routes::user::update_delivery_preference,
+            ],
         )
+        .mount("/me", routes![routes::user::get_me])
         .mount(
             "/users",
             routes![routes::users::create_users, routes::users::associate_users],
@@ -67,12 +102,33 @@ async fn rocket() -> _ {
             routes![
                 routes::list::create_list,
                 routes::list::get_list,
+                routes::list::get_stats,
                 routes::list::delete_list,
+                routes::list::restore_list,
+                routes::list::archive_list,
+                routes::list::unarchive_list,
+                routes::list::get_members,
+                routes::list::update_member,
+                routes::list::delete_member,
                 routes::list::task::create_task,
                 routes::list::task::get_task,
+                routes::list::task::assign_task,
                 routes::list::task::get_tasks,
                 routes::list::task::delete_task,
+                routes::list::task::restore_task,
+                routes::list::task::archive_task,
+                routes::list::task::unarchive_task,
                 routes::list::task::update_task,
+                routes::list::task::get_events,
+                routes::list::task::get_items,
+                routes::list::task::create_item,
+                routes::list::task::reorder_items,
+                routes::list::task::update_item,
+                routes::list::task::delete_item,
+                routes::list::task::get_dependencies,
+                routes::list::task::get_blockers,
+                routes::list::task::create_dependency,
+                routes::list::task::delete_dependency,
             ],
         )
         .mount(
@@ -91,8 +147,33 @@ async fn rocket() -> _ {
                 routes::accountability::get_request,
                 routes::accountability::update_status,
                 routes::accountability::delete_request,
+                routes::accountability::comment,
+                routes::accountability::get_change,
+                routes::accountability::approve_change,
+                routes::accountability::reject_change,
+            ],
+        )
+        .mount(
+            "/partnership",
+            routes![
+                routes::partnership::create_partnership,
+                routes::partnership::get_partnership,
+                routes::partnership::update_status,
+                routes::partnership::delete_partnership,
+            ],
+        )
+        .mount("/tasks", routes![routes::tasks::bulk])
+        .mount(
+            "/template",
+            routes![
+                routes::template::create_template,
+                routes::template::get_template,
+                routes::template::clone_list,
+                routes::template::instantiate,
+                routes::template::delete_template,
             ],
         )
+        .mount("/search", routes![routes::search::search])
         .mount(
             "/discord",
             routes![
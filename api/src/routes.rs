@@ -1,7 +1,12 @@
 use std::fmt::Display;
 use std::str::FromStr;
 
-use database::prelude::{ApiKey, Client};
+use ::discord::locale::Locale;
+use chrono::Utc;
+use database::prelude::{
+    AccountabilityRequest, ApiKey, Client, Cursor, DueWindow, ListMember, ListRole, Priority, Task,
+    TaskFilter, TaskSort,
+};
 use log::error;
 use rocket::request::{FromRequest, Outcome};
 use rocket::serde::{Deserialize, Serialize};
@@ -76,6 +81,194 @@ where
     }
 }
 
+// only locales with a message catalog can be picked explicitly
+fn check_locale<T>(locale: Option<&str>) -> Result<(), GenericResponse<T>> {
+    match locale {
+        Some(tag) if Locale::from_tag(tag).is_none() => Err(GenericResponse {
+            status: 400,
+            data: vec![],
+            error: Some(GenericError {
+                message: format!("{} isn't a supported locale.", tag),
+            }),
+        }),
+        _ => Ok(()),
+    }
+}
+
+// cursors only come from the next_cursor of a previous page fetched with
+// the same filter, anything else is turned down with a 400
+fn parse_cursor<T>(
+    cursor: Option<&str>,
+    parse: impl Fn(&str) -> Option<Cursor>,
+) -> Result<Option<Cursor>, GenericResponse<T>> {
+    match cursor {
+        Some(cursor) => parse(cursor).map(Some).ok_or_else(|| GenericResponse {
+            status: 400,
+            data: vec![],
+            error: Some(GenericError {
+                message: String::from("The cursor isn't valid."),
+            }),
+        }),
+        None => Ok(None),
+    }
+}
+
+#[derive(FromForm)]
+pub struct TaskQuery {
+    priority: Option<String>,
+    tag: Option<String>,
+    checked: Option<bool>,
+    due_after: Option<i64>,
+    due_before: Option<i64>,
+    sort: Option<String>,
+    order: Option<String>,
+    due: Option<String>,
+    archived: Option<bool>,
+}
+
+impl TaskQuery {
+    // fails with a reason if a priority, sort, order or due window isn't
+    // known
+    fn filter(self) -> Result<TaskFilter, String> {
+        let priority = match self.priority.as_deref() {
+            Some(name) => Some(
+                Priority::from_name(name).ok_or_else(|| format!("{} isn't a priority.", name))?,
+            ),
+            None => None,
+        };
+        let sort = match self.sort.as_deref() {
+            Some(name) => {
+                TaskSort::from_name(name).ok_or_else(|| format!("Can't sort by {}.", name))?
+            }
+            None => TaskSort::default(),
+        };
+        let descending = match self.order.as_deref() {
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(order) => return Err(format!("{} isn't asc or desc.", order)),
+            None => sort == TaskSort::Priority,
+        };
+
+        let filter = TaskFilter {
+            priority,
+            tag: self.tag,
+            checked: self.checked,
+            due_after: self.due_after,
+            due_before: self.due_before,
+            sort,
+            descending,
+            archived: self.archived.unwrap_or_default(),
+        };
+
+        match self.due.as_deref() {
+            Some(name) => {
+                let window = DueWindow::from_name(name)
+                    .ok_or_else(|| format!("{} isn't overdue, today or week.", name))?;
+
+                Ok(filter.within(window, Utc::now().timestamp()))
+            }
+            None => Ok(filter),
+        }
+    }
+}
+
+// approving proof and answering change requests is up to the partners who
+// accepted a request for the task
+async fn check_partner<T>(
+    db_client: &Client,
+    task_id: Uuid,
+    user_id: i64,
+) -> Result<(), GenericResponse<T>> {
+    let partners = AccountabilityRequest::partners(db_client, task_id)
+        .await
+        .map_err(|e| GenericResponse::from(Err::<Option<T>, _>(e)))?;
+
+    if partners.contains(&user_id) {
+        return Ok(());
+    }
+
+    Err(GenericResponse {
+        status: 403,
+        data: vec![],
+        error: Some(GenericError {
+            message: String::from("Only an accountability partner of the task can do this."),
+        }),
+    })
+}
+
+// owners manage who a list is shared with, editors can change its tasks as
+// well. anyone on the list counts as a viewer
+async fn check_list_role<T>(
+    db_client: &Client,
+    list_id: Uuid,
+    user_id: i64,
+    needed: ListRole,
+) -> Result<(), GenericResponse<T>> {
+    let role = ListMember::role(db_client, list_id, user_id)
+        .await
+        .map_err(|e| GenericResponse::from(Err::<Option<T>, _>(e)))?;
+
+    check_role(role, needed)
+}
+
+// like check_list_role, for a list that's deleted and can only be restored
+async fn check_deleted_list_role<T>(
+    db_client: &Client,
+    list_id: Uuid,
+    user_id: i64,
+    needed: ListRole,
+) -> Result<(), GenericResponse<T>> {
+    let role = ListMember::deleted_role(db_client, list_id, user_id)
+        .await
+        .map_err(|e| GenericResponse::from(Err::<Option<T>, _>(e)))?;
+
+    check_role(role, needed)
+}
+
+fn check_role<T>(role: Option<ListRole>, needed: ListRole) -> Result<(), GenericResponse<T>> {
+    let allowed = match (role, needed) {
+        (Some(ListRole::Owner), _) | (Some(_), ListRole::Viewer) => true,
+        (Some(role), ListRole::Editor) => role.can_edit(),
+        _ => false,
+    };
+
+    if allowed {
+        return Ok(());
+    }
+
+    let message = match needed {
+        ListRole::Owner => "Only an owner of the list can do this.",
+        ListRole::Editor => "Only an owner or editor of the list can do this.",
+        ListRole::Viewer => "Only a member of the list can do this.",
+    };
+
+    Err(GenericResponse {
+        status: 403,
+        data: vec![],
+        error: Some(GenericError {
+            message: String::from(message),
+        }),
+    })
+}
+
+// the task, if the user may change it. owners and editors of its list can
+// change any task on it, assignees can always work on their own
+async fn check_task_editor<T>(
+    db_client: &Client,
+    task_id: Uuid,
+    user_id: i64,
+) -> Result<Task, GenericResponse<T>> {
+    match Task::get(db_client, task_id).await {
+        Ok(Some(task)) if task.user_id == user_id => Ok(task),
+        Ok(Some(task)) => {
+            check_list_role(db_client, task.list_id, user_id, ListRole::Editor).await?;
+            Ok(task)
+        }
+        Ok(None) => Err(GenericResponse::from(Ok::<Option<T>, String>(None))),
+        Err(e) => Err(GenericResponse::from(Err::<Option<T>, _>(e))),
+    }
+}
+
 #[derive(Debug)]
 pub enum ApiKeyError {
     Missing,
@@ -123,11 +316,59 @@ impl<'r> FromRequest<'r> for ShamebotApiKey {
 }
 
 pub mod guild {
-    use database::prelude::{Client, Guild, User};
+    use chrono::Utc;
+    use database::prelude::{
+        Balance, Client, Guild, LeaderboardEntry, MessageTemplate, NotificationChannel,
+        NotificationKind, Page, User, UserFilter, UserSort,
+    };
+    use discord::bot::Bot;
+    use discord::locale::Locale;
+    use discord::templates::{self, Placeholders};
     use rocket::serde::json::Json;
+    use rocket::serde::Deserialize;
     use rocket::{http::Status, State};
 
-    use super::GenericResponse;
+    use super::{GenericError, GenericResponse};
+
+    const USERS_PAGE: i64 = 100;
+
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct TemplatePreview {
+        kind: NotificationKind,
+        template: Option<String>,
+        locale: Option<String>,
+    }
+
+    #[derive(FromForm)]
+    pub struct UserQuery {
+        username: Option<String>,
+        sort: Option<String>,
+        order: Option<String>,
+    }
+
+    impl UserQuery {
+        // fails with a reason if a sort or order isn't known
+        fn filter(self) -> Result<UserFilter, String> {
+            let sort = match self.sort.as_deref() {
+                Some(name) => {
+                    UserSort::from_name(name).ok_or_else(|| format!("Can't sort by {}.", name))?
+                }
+                None => UserSort::default(),
+            };
+            let descending = match self.order.as_deref() {
+                Some("asc") | None => false,
+                Some("desc") => true,
+                Some(order) => return Err(format!("{} isn't asc or desc.", order)),
+            };
+
+            Ok(UserFilter {
+                username: self.username,
+                sort,
+                descending,
+            })
+        }
+    }
 
     #[post("/", format = "json", data = "<guild>")]
     pub async fn create_guild(
@@ -162,12 +403,35 @@ pub mod guild {
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
-    #[get("/<id>/users")]
+    // sort is username (the default) or id and order is asc or desc
+    #[get("/<id>/users?<limit>&<cursor>&<query..>")]
     pub async fn get_guild_users(
         db_client: &State<Client>,
         id: i64,
-    ) -> (Status, Json<GenericResponse<Vec<User>>>) {
-        let users = Guild::get_users(db_client, id).await.map(Some);
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        query: UserQuery,
+    ) -> (Status, Json<GenericResponse<Page<User>>>) {
+        let filter = match query.filter() {
+            Ok(filter) => filter,
+            Err(message) => {
+                let resp = GenericResponse {
+                    status: 400,
+                    data: vec![],
+                    error: Some(GenericError { message }),
+                };
+
+                return (Status::BadRequest, Json(resp));
+            }
+        };
+        let cursor = match super::parse_cursor(cursor, |cursor| filter.cursor(cursor)) {
+            Ok(cursor) => cursor,
+            Err(resp) => return (Status::BadRequest, Json(resp)),
+        };
+        let limit = limit.unwrap_or(USERS_PAGE).clamp(1, USERS_PAGE);
+        let users = Guild::get_users(db_client, id, &filter, cursor.as_ref(), limit)
+            .await
+            .map(Some);
         let resp = GenericResponse::from(users);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
@@ -185,6 +449,241 @@ pub mod guild {
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
+    // the role is validated against the bot's permissions and position in the
+    // role hierarchy before saving, a null role disables shaming altogether
+    #[put("/<id>/shame", format = "json", data = "<guild>")]
+    pub async fn update_shame(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        id: i64,
+        guild: Json<Guild>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        if let Some(role_id) = guild.shame_role {
+            let check = discord_bot
+                .check_shame_role(id as u64, role_id as u64, guild.shame_prefix.is_some())
+                .await;
+
+            if let Err(message) = check {
+                let resp = GenericResponse {
+                    status: 400,
+                    data: vec![],
+                    error: Some(GenericError { message }),
+                };
+
+                return (Status::BadRequest, Json(resp));
+            }
+        }
+
+        let updated =
+            Guild::update_shame(db_client, id, guild.shame_role, guild.shame_prefix.clone()).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[put("/<id>/threads", format = "json", data = "<guild>")]
+    pub async fn update_threads(
+        db_client: &State<Client>,
+        id: i64,
+        guild: Json<Guild>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        let updated = Guild::update_threads(db_client, id, guild.task_threads).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // same numbers as the weekly leaderboard the bot posts, over the last
+    // `days` days (a week by default)
+    #[get("/<id>/leaderboard?<days>")]
+    pub async fn get_leaderboard(
+        db_client: &State<Client>,
+        id: i64,
+        days: Option<u16>,
+    ) -> (Status, Json<GenericResponse<Vec<LeaderboardEntry>>>) {
+        let until = Utc::now().timestamp();
+        let since = until - i64::from(days.unwrap_or(7)) * 24 * 60 * 60;
+        let entries = LeaderboardEntry::for_guild(db_client, id, since, until)
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(entries);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // everyone who won or lost points in the guild, highest balance first
+    #[get("/<id>/points")]
+    pub async fn get_points(
+        db_client: &State<Client>,
+        id: i64,
+    ) -> (Status, Json<GenericResponse<Vec<Balance>>>) {
+        let balances = Balance::for_guild(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(balances);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>/notifications")]
+    pub async fn get_notification_channels(
+        db_client: &State<Client>,
+        id: i64,
+    ) -> (Status, Json<GenericResponse<Vec<NotificationChannel>>>) {
+        let channels = NotificationChannel::get_all(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(channels);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // routes one kind of message to its own channel, a null channel_id sends
+    // that kind back to the guild's default channel
+    #[put("/<id>/notifications", format = "json", data = "<channel>")]
+    pub async fn update_notification_channel(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        id: i64,
+        channel: Json<NotificationChannel>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        if let Some(channel_id) = channel.channel_id {
+            let text_channels = discord_bot
+                .get_text_channels(id as u64)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+
+            if !text_channels
+                .iter()
+                .any(|c| *c.id.as_u64() == channel_id as u64)
+            {
+                let resp = GenericResponse {
+                    status: 400,
+                    data: vec![],
+                    error: Some(GenericError {
+                        message: String::from("The channel isn't a text channel in this guild."),
+                    }),
+                };
+
+                return (Status::BadRequest, Json(resp));
+            }
+        }
+
+        let channel = NotificationChannel {
+            guild_id: id,
+            ..channel.into_inner()
+        };
+        let updated = NotificationChannel::set(db_client, channel).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[put("/<id>/personality", format = "json", data = "<guild>")]
+    pub async fn update_personality(
+        db_client: &State<Client>,
+        id: i64,
+        guild: Json<Guild>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        let updated = Guild::update_personality(db_client, id, guild.personality).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // a null locale goes back to whatever discord reports for the guild
+    #[put("/<id>/locale", format = "json", data = "<guild>")]
+    pub async fn update_locale(
+        db_client: &State<Client>,
+        id: i64,
+        guild: Json<Guild>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        if let Err(resp) = super::check_locale(guild.locale.as_deref()) {
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let updated = Guild::update_locale(db_client, id, guild.locale.clone()).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>/templates")]
+    pub async fn get_templates(
+        db_client: &State<Client>,
+        id: i64,
+    ) -> (Status, Json<GenericResponse<Vec<MessageTemplate>>>) {
+        let templates = MessageTemplate::get_all(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(templates);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // a null template goes back to the guild's personality preset
+    #[put("/<id>/templates", format = "json", data = "<template>")]
+    pub async fn update_template(
+        db_client: &State<Client>,
+        id: i64,
+        template: Json<MessageTemplate>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        if let Some(text) = template.template.as_ref() {
+            if let Err(message) = templates::validate(template.kind, text) {
+                let resp = GenericResponse {
+                    status: 400,
+                    data: vec![],
+                    error: Some(GenericError { message }),
+                };
+
+                return (Status::BadRequest, Json(resp));
+            }
+        }
+
+        let template = MessageTemplate {
+            guild_id: id,
+            ..template.into_inner()
+        };
+        let updated = MessageTemplate::set(db_client, template).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // renders the given template (or whatever the guild currently uses for
+    // the kind) against a sample task, without saving anything
+    #[post("/<id>/templates/preview", format = "json", data = "<preview>")]
+    pub async fn preview_template(
+        db_client: &State<Client>,
+        id: i64,
+        preview: Json<TemplatePreview>,
+    ) -> (Status, Json<GenericResponse<String>>) {
+        let guild = match Guild::get(db_client, id).await {
+            Ok(Some(guild)) => guild,
+            other => {
+                let resp = GenericResponse::from(other.map(|_| None));
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+        };
+
+        let locale = Locale::resolve(preview.locale.as_deref(), guild.locale.as_deref());
+        let template = match preview.template.as_ref() {
+            Some(template) => template.clone(),
+            None => templates::resolve(db_client, &guild, preview.kind, locale).await,
+        };
+
+        if let Err(message) = templates::validate(preview.kind, &template) {
+            let resp = GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError { message }),
+            };
+
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let rendered = templates::render(&template, &Placeholders::sample());
+        let resp = GenericResponse::from(Ok::<_, String>(Some(rendered)));
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
     #[delete("/<id>")]
     pub async fn delete_guild(
         db_client: &State<Client>,
@@ -198,11 +697,18 @@ pub mod guild {
 }
 
 pub mod user {
-    use database::prelude::{Client, User};
+    use database::prelude::{
+        Balance, Client, DeliveryPreference, HabitStats, List, ListTemplate, NotificationKind,
+        Page, Partnership, PointsEntry, Task, User,
+    };
     use rocket::serde::json::Json;
     use rocket::{http::Status, State};
 
-    use crate::routes::GenericResponse;
+    use crate::routes::{GenericError, GenericResponse, ShamebotApiKey, TaskQuery};
+
+    const LEDGER_PAGE: i64 = 50;
+    const LISTS_PAGE: i64 = 100;
+    const TASKS_PAGE: i64 = 100;
 
     #[post("/", format = "json", data = "<user>")]
     pub async fn create_user(
@@ -236,96 +742,537 @@ pub mod user {
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
-}
-
-pub mod users {
-    use database::prelude::{Client, User};
-    use rocket::serde::json::Json;
-    use rocket::{http::Status, State};
-
-    use super::GenericResponse;
 
-    #[post("/", format = "json", data = "<users>")]
-    pub async fn create_users(
+    // the user the api key belongs to, mounted at /me
+    #[get("/")]
+    pub async fn get_me(
         db_client: &State<Client>,
-        users: Json<Vec<User>>,
-    ) -> (Status, Json<GenericResponse<Vec<User>>>) {
-        let new_users = User::new_batch(db_client, users.to_vec()).await.map(Some);
-        let resp = GenericResponse::from(new_users);
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<User>>) {
+        let user = User::get(db_client, key.api_key.user_id).await;
+        let resp = GenericResponse::from(user);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
-    #[post("/associate/<guild_id>", format = "json", data = "<users>")]
-    pub async fn associate_users(
+    // sorted by title, archived lists only when asked for
+    #[get("/<id>/lists?<archived>&<limit>&<cursor>")]
+    pub async fn get_lists(
         db_client: &State<Client>,
-        users: Json<Vec<i64>>,
-        guild_id: i64,
-    ) -> (Status, Json<GenericResponse<Vec<()>>>) {
-        let associated = User::batch_associate(db_client, users.to_vec(), guild_id)
+        id: i64,
+        archived: Option<bool>,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+    ) -> (Status, Json<GenericResponse<Page<List>>>) {
+        let cursor = match crate::routes::parse_cursor(cursor, List::cursor) {
+            Ok(cursor) => cursor,
+            Err(resp) => return (Status::BadRequest, Json(resp)),
+        };
+        let limit = limit.unwrap_or(LISTS_PAGE).clamp(1, LISTS_PAGE);
+        let archived = archived.unwrap_or_default();
+        let lists = List::get_for_user(db_client, id, archived, cursor.as_ref(), limit)
             .await
             .map(Some);
-        let resp = GenericResponse::from(associated);
+        let resp = GenericResponse::from(lists);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
-}
 
-pub mod list {
-    use database::prelude::{Client, List};
-    use rocket::serde::json::Json;
-    use rocket::{http::Status, State};
-    use uuid::Uuid;
+    // without their tasks, those come with each template on its own
+    #[get("/<id>/templates")]
+    pub async fn get_templates(
+        db_client: &State<Client>,
+        id: i64,
+    ) -> (Status, Json<GenericResponse<Vec<ListTemplate>>>) {
+        let templates = ListTemplate::get_for_user(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(templates);
 
-    use crate::routes::GenericResponse;
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
 
-    #[post("/", format = "json", data = "<list>")]
-    pub async fn create_list(
+    // takes the same filters as a list's tasks. due is overdue, today or
+    // week and only leaves unchecked tasks due then
+    #[get("/<id>/tasks?<limit>&<cursor>&<query..>")]
+    pub async fn get_tasks(
         db_client: &State<Client>,
-        list: Json<List>,
-    ) -> (Status, Json<GenericResponse<List>>) {
-        let new_list = List::new(db_client, list.title.clone(), list.user_id)
+        id: i64,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        query: TaskQuery,
+    ) -> (Status, Json<GenericResponse<Page<Task>>>) {
+        let filter = match query.filter() {
+            Ok(filter) => filter,
+            Err(message) => {
+                let resp = GenericResponse {
+                    status: 400,
+                    data: vec![],
+                    error: Some(GenericError { message }),
+                };
+
+                return (Status::BadRequest, Json(resp));
+            }
+        };
+        let cursor = match crate::routes::parse_cursor(cursor, |cursor| filter.cursor(cursor)) {
+            Ok(cursor) => cursor,
+            Err(resp) => return (Status::BadRequest, Json(resp)),
+        };
+        let limit = limit.unwrap_or(TASKS_PAGE).clamp(1, TASKS_PAGE);
+        let tasks = Task::get_for_user(db_client, id, &filter, cursor.as_ref(), limit)
             .await
             .map(Some);
-        let resp = GenericResponse::from(new_list);
-
-        // if successful update status to 201 Created
-        let status = if resp.status == 200 { 201 } else { resp.status };
+        let resp = GenericResponse::from(tasks);
 
-        (Status::from_code(status).unwrap(), Json(resp))
+        (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
-    #[get("/<id>")]
-    pub async fn get_list(
+    // a null locale goes back to whatever discord reports for the user
+    #[put("/<id>/locale", format = "json", data = "<user>")]
+    pub async fn update_locale(
         db_client: &State<Client>,
-        id: Uuid,
-    ) -> (Status, Json<GenericResponse<List>>) {
-        let list = List::get(db_client, id).await;
-        let resp = GenericResponse::from(list);
+        id: i64,
+        user: Json<User>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        if let Err(resp) = super::check_locale(user.locale.as_deref()) {
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let updated = User::update_locale(db_client, id, user.locale.clone()).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>/stats")]
+    pub async fn get_stats(
+        db_client: &State<Client>,
+        id: i64,
+    ) -> (Status, Json<GenericResponse<HabitStats>>) {
+        let stats = HabitStats::for_user(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(stats);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>/points/<guild_id>")]
+    pub async fn get_points(
+        db_client: &State<Client>,
+        id: i64,
+        guild_id: i64,
+    ) -> (Status, Json<GenericResponse<Balance>>) {
+        let balance = Balance::get(db_client, guild_id, id).await.map(Some);
+        let resp = GenericResponse::from(balance);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // the entries that make up the balance, newest first
+    #[get("/<id>/points/<guild_id>/ledger?<limit>")]
+    pub async fn get_ledger(
+        db_client: &State<Client>,
+        id: i64,
+        guild_id: i64,
+        limit: Option<i64>,
+    ) -> (Status, Json<GenericResponse<Vec<PointsEntry>>>) {
+        let limit = limit.unwrap_or(LEDGER_PAGE).clamp(1, LEDGER_PAGE);
+        let entries = PointsEntry::get_for_user(db_client, guild_id, id, limit)
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(entries);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // partnerships the user asked for and the ones they're partner in
+    #[get("/<id>/partnerships")]
+    pub async fn get_partnerships(
+        db_client: &State<Client>,
+        id: i64,
+    ) -> (Status, Json<GenericResponse<Vec<Partnership>>>) {
+        let partnerships = Partnership::get_for_user(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(partnerships);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>/delivery")]
+    pub async fn get_delivery_preferences(
+        db_client: &State<Client>,
+        id: i64,
+    ) -> (Status, Json<GenericResponse<Vec<DeliveryPreference>>>) {
+        let preferences = DeliveryPreference::get_all(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(preferences);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // only messages about a user's own tasks can be moved to DMs, a null
+    // delivery sends the kind back to the guild channel
+    #[put("/<id>/delivery", format = "json", data = "<preference>")]
+    pub async fn update_delivery_preference(
+        db_client: &State<Client>,
+        id: i64,
+        preference: Json<DeliveryPreference>,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        let personal = matches!(
+            preference.kind,
+            NotificationKind::Reminder | NotificationKind::Pester | NotificationKind::Overdue
+        );
+
+        if !personal {
+            let resp = GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError {
+                    message: String::from(
+                        "Only reminder, pester and overdue messages can be delivered by DM.",
+                    ),
+                }),
+            };
+
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let preference = DeliveryPreference {
+            user_id: id,
+            ..preference.into_inner()
+        };
+        let updated = DeliveryPreference::set(db_client, preference).await;
+        let resp = GenericResponse::from(updated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+}
+
+pub mod users {
+    use database::prelude::{Client, User};
+    use rocket::serde::json::Json;
+    use rocket::{http::Status, State};
+
+    use super::GenericResponse;
+
+    #[post("/", format = "json", data = "<users>")]
+    pub async fn create_users(
+        db_client: &State<Client>,
+        users: Json<Vec<User>>,
+    ) -> (Status, Json<GenericResponse<Vec<User>>>) {
+        let new_users = User::new_batch(db_client, users.to_vec()).await.map(Some);
+        let resp = GenericResponse::from(new_users);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[post("/associate/<guild_id>", format = "json", data = "<users>")]
+    pub async fn associate_users(
+        db_client: &State<Client>,
+        users: Json<Vec<i64>>,
+        guild_id: i64,
+    ) -> (Status, Json<GenericResponse<Vec<()>>>) {
+        let associated = User::batch_associate(db_client, users.to_vec(), guild_id)
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(associated);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+}
+
+pub mod list {
+    use database::prelude::{Client, HabitStats, List, ListMember, ListRole, TaskFilter};
+    use log::{debug, error};
+    use rocket::serde::json::Json;
+    use rocket::serde::Deserialize;
+    use rocket::{http::Status, State};
+    use uuid::Uuid;
+
+    use crate::routes::{
+        check_deleted_list_role, check_list_role, GenericError, GenericResponse, ShamebotApiKey,
+    };
+
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct Membership {
+        role: ListRole,
+    }
+
+    #[post("/", format = "json", data = "<list>")]
+    pub async fn create_list(
+        db_client: &State<Client>,
+        list: Json<List>,
+    ) -> (Status, Json<GenericResponse<List>>) {
+        let new_list = List::new(db_client, list.title.clone(), list.user_id)
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(new_list);
+
+        // if successful update status to 201 Created
+        let status = if resp.status == 200 { 201 } else { resp.status };
+
+        (Status::from_code(status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>")]
+    pub async fn get_list(
+        db_client: &State<Client>,
+        id: Uuid,
+    ) -> (Status, Json<GenericResponse<List>>) {
+        let list = List::get(db_client, id).await;
+        let resp = GenericResponse::from(list);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>/stats")]
+    pub async fn get_stats(
+        db_client: &State<Client>,
+        id: Uuid,
+    ) -> (Status, Json<GenericResponse<HabitStats>>) {
+        let stats = HabitStats::for_list(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(stats);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
+    // soft deleted along with its tasks, see restore_list
     #[delete("/<list_id>")]
     pub async fn delete_list(
         db_client: &State<Client>,
         list_id: Uuid,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        let (actor, owner) = (key.api_key.user_id, ListRole::Owner);
+        if let Err(resp) = check_list_role(db_client, list_id, actor, owner).await {
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
+        }
+
+        // archived tasks have jobs too, they just don't send anything
+        let filter = TaskFilter {
+            archived: true,
+            ..TaskFilter::default()
+        };
+        let task_ids = List::get_tasks(db_client, list_id, &filter)
+            .await
+            .map_err(|e| error!("{}", e))
+            .unwrap_or_default()
+            .iter()
+            .map(|task| task.id)
+            .collect::<Vec<Uuid>>();
+
+        let deleted = List::delete(db_client, list_id, Some(actor))
+            .await
+            .map(Some);
+
+        if deleted.is_ok() && !task_ids.is_empty() {
+            task::stop_batch(&task_ids)
+                .await
+                .map_err(|e| error!("{}", e))
+                .map(|j| debug!("{:?}", j))
+                .ok();
+        }
+
+        let resp = GenericResponse::from(deleted);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // brings back the tasks that were deleted along with the list, until the
+    // retention job purges it
+    #[post("/<list_id>/restore")]
+    pub async fn restore_list(
+        db_client: &State<Client>,
+        list_id: Uuid,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<List>>) {
+        let (actor, owner) = (key.api_key.user_id, ListRole::Owner);
+        if let Err(resp) = check_deleted_list_role(db_client, list_id, actor, owner).await {
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
+        }
+
+        let restored = List::restore(db_client, list_id, Some(actor)).await;
+
+        if let Ok(Some(list)) = restored.as_ref() {
+            let filter = TaskFilter {
+                archived: true,
+                ..TaskFilter::default()
+            };
+            let task_ids = List::get_tasks(db_client, list.id, &filter)
+                .await
+                .map_err(|e| error!("{}", e))
+                .unwrap_or_default()
+                .iter()
+                .map(|task| task.id)
+                .collect::<Vec<Uuid>>();
+
+            task::register_batch(&task_ids)
+                .await
+                .map_err(|e| error!("{}", e))
+                .map(|j| debug!("{:?}", j))
+                .ok();
+        }
+
+        let resp = GenericResponse::from(restored);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[post("/<list_id>/archive")]
+    pub async fn archive_list(
+        db_client: &State<Client>,
+        list_id: Uuid,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<List>>) {
+        archive(db_client, list_id, key.api_key.user_id, true).await
+    }
+
+    #[post("/<list_id>/unarchive")]
+    pub async fn unarchive_list(
+        db_client: &State<Client>,
+        list_id: Uuid,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<List>>) {
+        archive(db_client, list_id, key.api_key.user_id, false).await
+    }
+
+    async fn archive(
+        db_client: &Client,
+        list_id: Uuid,
+        actor: i64,
+        archived: bool,
+    ) -> (Status, Json<GenericResponse<List>>) {
+        let owner = ListRole::Owner;
+        if let Err(resp) = check_list_role(db_client, list_id, actor, owner).await {
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
+        }
+
+        let list = List::archive(db_client, list_id, archived, Some(actor)).await;
+        let resp = GenericResponse::from(list);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // everyone the list is shared with, its creator is the list's user_id
+    #[get("/<list_id>/members")]
+    pub async fn get_members(
+        db_client: &State<Client>,
+        list_id: Uuid,
+    ) -> (Status, Json<GenericResponse<Vec<ListMember>>>) {
+        let members = ListMember::get_for_list(db_client, list_id).await.map(Some);
+        let resp = GenericResponse::from(members);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // shares the list with the user, or changes their role
+    #[put("/<list_id>/members/<user_id>", format = "json", data = "<membership>")]
+    pub async fn update_member(
+        db_client: &State<Client>,
+        list_id: Uuid,
+        user_id: i64,
+        membership: Json<Membership>,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<ListMember>>) {
+        let owner = ListRole::Owner;
+        if let Err(resp) = check_list_role(db_client, list_id, key.api_key.user_id, owner).await {
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
+        }
+
+        let list = List::get(db_client, list_id).await.ok().flatten();
+
+        if list.is_some_and(|list| list.user_id == user_id) {
+            let resp = GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError {
+                    message: String::from("The list's creator is always an owner."),
+                }),
+            };
+
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let member = ListMember::set(db_client, list_id, user_id, membership.role)
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(member);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // owners can remove anyone, everyone else only themselves. their tasks
+    // on the list go back to its creator
+    #[delete("/<list_id>/members/<user_id>")]
+    pub async fn delete_member(
+        db_client: &State<Client>,
+        list_id: Uuid,
+        user_id: i64,
+        key: ShamebotApiKey,
     ) -> (Status, Json<GenericResponse<()>>) {
-        let deleted = List::delete(db_client, list_id).await.map(Some);
+        let actor = key.api_key.user_id;
+
+        if actor != user_id {
+            let owner = ListRole::Owner;
+            if let Err(resp) = check_list_role(db_client, list_id, actor, owner).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+        }
+
+        let deleted = ListMember::delete(db_client, list_id, user_id)
+            .await
+            .map(Some);
         let resp = GenericResponse::from(deleted);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
     pub mod task {
-        use database::prelude::{Client, List, Task, TaskJobs};
-        use log::debug;
+        use database::prelude::{
+            Client, List, ListMember, ListRole, NewTask, Page, Task, TaskDependency, TaskEvent,
+            TaskItem, TaskJobs,
+        };
+        use discord::{bot::Bot, jobs::rescheduled};
+        use log::{debug, error};
         use rocket::serde::json::Json;
         use rocket::serde::Deserialize;
         use rocket::{http::Status, State};
         use uuid::Uuid;
 
-        use crate::routes::GenericResponse;
+        use crate::routes::{
+            check_list_role, check_task_editor, GenericError, GenericResponse, ShamebotApiKey,
+            TaskQuery,
+        };
+
+        const EVENTS_PAGE: i64 = 50;
+        const TASKS_PAGE: i64 = 100;
+
+        #[derive(Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        pub struct NewItem {
+            title: String,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        pub struct NewDependency {
+            depends_on: Uuid,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(crate = "rocket::serde")]
+        pub struct Assignee {
+            user_id: i64,
+        }
+
+        pub(crate) fn not_assignable<T>(user_id: i64) -> GenericResponse<T> {
+            GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError {
+                    message: format!(
+                        "{} isn't on the list or isn't a member of the task's guild.",
+                        user_id
+                    ),
+                }),
+            }
+        }
 
         #[derive(Debug, Deserialize)]
         #[serde(crate = "rocket::serde")]
@@ -343,234 +1290,1609 @@ pub mod list {
             error: Option<JobError>,
         }
 
-        async fn register_jobs(task_id: Uuid) -> Result<JobsResponse, reqwest::Error> {
+        pub(crate) async fn register_jobs(task_id: Uuid) -> Result<JobsResponse, reqwest::Error> {
             let client = reqwest::Client::new();
             let resp = client
                 .post(format!("http://cron:8080/jobs/{:?}", task_id))
                 .send()
                 .await;
 
-            match resp {
-                Ok(r) => r.json::<JobsResponse>().await,
-                Err(e) => Err(e),
+            match resp {
+                Ok(r) => r.json::<JobsResponse>().await,
+                Err(e) => Err(e),
+            }
+        }
+
+        // deleted tasks don't keep their jobs, they're registered again on
+        // restore
+        pub(crate) async fn stop_batch(task_ids: &[Uuid]) -> Result<JobsResponse, reqwest::Error> {
+            let client = reqwest::Client::new();
+            let resp = client
+                .delete("http://cron:8080/jobs")
+                .json(task_ids)
+                .send()
+                .await;
+
+            match resp {
+                Ok(r) => r.json::<JobsResponse>().await,
+                Err(e) => Err(e),
+            }
+        }
+
+        // one call for the jobs of many tasks
+        pub(crate) async fn register_batch(
+            task_ids: &[Uuid],
+        ) -> Result<JobsResponse, reqwest::Error> {
+            let client = reqwest::Client::new();
+            let resp = client
+                .post("http://cron:8080/jobs")
+                .json(task_ids)
+                .send()
+                .await;
+
+            match resp {
+                Ok(r) => r.json::<JobsResponse>().await,
+                Err(e) => Err(e),
+            }
+        }
+
+        // stops the jobs of tasks whose due date or pester interval moved and
+        // registers them again on the new schedule
+        pub(crate) async fn reschedule_batch(
+            task_ids: &[Uuid],
+        ) -> Result<JobsResponse, reqwest::Error> {
+            stop_batch(task_ids).await?;
+            register_batch(task_ids).await
+        }
+
+        // like check_task_editor, for an item that has to be on the task
+        async fn check_item_editor<T>(
+            db_client: &Client,
+            task_id: Uuid,
+            item_id: Uuid,
+            user_id: i64,
+        ) -> Result<(), GenericResponse<T>> {
+            check_task_editor(db_client, task_id, user_id).await?;
+
+            match TaskItem::get(db_client, item_id).await {
+                Ok(Some(item)) if item.task_id == task_id => Ok(()),
+                Ok(_) => Err(GenericResponse::from(Ok::<Option<T>, String>(None))),
+                Err(e) => Err(GenericResponse::from(Err::<Option<T>, _>(e))),
+            }
+        }
+
+        // only owners and editors of the list can add tasks. they can go to
+        // anyone on the list who's in the guild
+        #[post("/<_id>/task", format = "json", data = "<task>")]
+        pub async fn create_task(
+            db_client: &State<Client>,
+            _id: Uuid,
+            task: Json<Task>,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            let (actor, editor) = (key.api_key.user_id, ListRole::Editor);
+            if let Err(resp) = check_list_role(db_client, task.list_id, actor, editor).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let assignable =
+                ListMember::assignable(db_client, task.list_id, task.guild_id, task.user_id).await;
+
+            match assignable {
+                Ok(true) => (),
+                Ok(false) => return (Status::BadRequest, Json(not_assignable(task.user_id))),
+                Err(e) => {
+                    let resp = GenericResponse::from(Err::<Option<Task>, _>(e));
+                    return (Status::from_code(resp.status).unwrap(), Json(resp));
+                }
+            }
+
+            let new_task = NewTask::from(&*task);
+            let task = Task::new(db_client, new_task, Some(actor)).await.map(Some);
+            let resp: GenericResponse<Task>;
+
+            if let Some(task) = task.as_ref().ok().unwrap() {
+                register_jobs(task.id)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .map(|j| debug!("{:?}", j))
+                    .ok();
+
+                let updated_task = Task::get(db_client, task.id).await;
+                resp = GenericResponse::from(updated_task);
+            } else {
+                resp = GenericResponse::from(task);
+            }
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[put("/<_list_id>/task/<_task_id>", format = "json", data = "<task>")]
+        pub async fn update_task(
+            db_client: &State<Client>,
+            discord_bot: &State<Bot>,
+            _list_id: Uuid,
+            _task_id: Uuid,
+            task: Json<Task>,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            let actor = key.api_key.user_id;
+            let previous = match check_task_editor(db_client, task.id, actor).await {
+                Ok(previous) => previous,
+                Err(resp) => return (Status::from_code(resp.status).unwrap(), Json(resp)),
+            };
+
+            // moving the task needs the same rights on the list it goes to
+            if task.list_id != previous.list_id {
+                let (list_id, editor) = (task.list_id, ListRole::Editor);
+                if let Err(resp) = check_list_role(db_client, list_id, actor, editor).await {
+                    return (Status::from_code(resp.status).unwrap(), Json(resp));
+                }
+            }
+
+            let guarded = Task::update_guarded(db_client, task.into_inner(), Some(actor)).await;
+            let change = guarded.as_ref().ok().and_then(|(_, change)| change.clone());
+            let updated = guarded.map(|(updated, _)| updated);
+
+            if let Some(change) = change.as_ref() {
+                discord_bot.send_change_request(change).await;
+            }
+
+            if let Ok(Some(task)) = updated.as_ref() {
+                if rescheduled(&previous, task) {
+                    reschedule_batch(&[task.id])
+                        .await
+                        .map_err(|e| error!("{}", e))
+                        .map(|j| debug!("{:?}", j))
+                        .ok();
+                }
+
+                if task.proof_id.is_some() && task.proof_id != previous.proof_id {
+                    discord_bot.send_proof(task.id).await;
+                }
+
+                if task.checked && !previous.checked {
+                    discord_bot.send_completion(task.id).await;
+                }
+
+                if task.checked {
+                    discord_bot.lift_shame(task.user_id, task.guild_id).await;
+                }
+            }
+
+            let mut resp = GenericResponse::from(updated);
+
+            // the rest of the update went through, the held back part waits
+            // for the partner
+            if change.is_some() && resp.status == 200 {
+                resp.status = 202;
+            }
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[get("/<_list_id>/task/<task_id>")]
+        pub async fn get_task(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            let task = Task::get(db_client, task_id).await;
+            let resp = GenericResponse::from(task);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        // reminders and the rest of the task's notifications go to whoever
+        // it's assigned to
+        #[put(
+            "/<_list_id>/task/<task_id>/assignee",
+            format = "json",
+            data = "<assignee>"
+        )]
+        pub async fn assign_task(
+            db_client: &State<Client>,
+            discord_bot: &State<Bot>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            assignee: Json<Assignee>,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            let task = match Task::get(db_client, task_id).await {
+                Ok(Some(task)) => task,
+                result => {
+                    let resp = GenericResponse::from(result);
+                    return (Status::from_code(resp.status).unwrap(), Json(resp));
+                }
+            };
+            let actor = key.api_key.user_id;
+            let editor = ListRole::Editor;
+
+            if let Err(resp) = check_list_role(db_client, task.list_id, actor, editor).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let assigned = Task::assign(db_client, &task, assignee.user_id, Some(actor)).await;
+
+            match assigned {
+                Ok(Some(assigned)) => {
+                    if assigned.user_id != task.user_id && assigned.user_id != actor {
+                        discord_bot.send_assignment(&assigned, actor).await;
+                    }
+
+                    let resp = GenericResponse::from(Ok::<_, String>(Some(assigned)));
+                    (Status::Ok, Json(resp))
+                }
+                Ok(None) => (Status::BadRequest, Json(not_assignable(assignee.user_id))),
+                Err(e) => {
+                    let resp = GenericResponse::from(Err::<Option<Task>, _>(e));
+                    (Status::from_code(resp.status).unwrap(), Json(resp))
+                }
+            }
+        }
+
+        // archived tasks keep their place on the list, but get no reminders
+        #[post("/<_list_id>/task/<task_id>/archive")]
+        pub async fn archive_task(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            archive(db_client, task_id, key.api_key.user_id, true).await
+        }
+
+        #[post("/<_list_id>/task/<task_id>/unarchive")]
+        pub async fn unarchive_task(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            archive(db_client, task_id, key.api_key.user_id, false).await
+        }
+
+        async fn archive(
+            db_client: &Client,
+            task_id: Uuid,
+            actor: i64,
+            archived: bool,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            if let Err(resp) = check_task_editor(db_client, task_id, actor).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let updated = Task::archive(db_client, task_id, archived, Some(actor)).await;
+            let resp = GenericResponse::from(updated);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        // only works while the list itself isn't deleted
+        #[post("/<list_id>/task/<task_id>/restore")]
+        pub async fn restore_task(
+            db_client: &State<Client>,
+            list_id: Uuid,
+            task_id: Uuid,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<Task>>) {
+            let (actor, editor) = (key.api_key.user_id, ListRole::Editor);
+            if let Err(resp) = check_list_role(db_client, list_id, actor, editor).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let restored = Task::restore(db_client, list_id, task_id, Some(actor)).await;
+            let resp: GenericResponse<Task>;
+
+            if let Ok(Some(task)) = restored.as_ref() {
+                register_jobs(task.id)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .map(|j| debug!("{:?}", j))
+                    .ok();
+
+                let registered = Task::get(db_client, task.id).await;
+                resp = GenericResponse::from(registered);
+            } else {
+                resp = GenericResponse::from(restored);
+            }
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        // newest first, `before` is the id of the last event of the previous page
+        #[get("/<_list_id>/task/<task_id>/events?<before>&<limit>")]
+        pub async fn get_events(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            before: Option<i64>,
+            limit: Option<i64>,
+        ) -> (Status, Json<GenericResponse<Vec<TaskEvent>>>) {
+            let limit = limit.unwrap_or(EVENTS_PAGE).clamp(1, EVENTS_PAGE);
+            let events = TaskEvent::get_page(db_client, task_id, before, limit)
+                .await
+                .map(Some);
+            let resp = GenericResponse::from(events);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[delete("/<_list_id>/task/<task_id>")]
+        pub async fn delete_task(
+            db_client: &State<Client>,
+            discord_bot: &State<Bot>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<()>>) {
+            let actor = key.api_key.user_id;
+
+            if let Err(resp) = check_task_editor(db_client, task_id, actor).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let deleted = Task::delete_guarded(db_client, task_id, Some(actor)).await;
+            let change = deleted.as_ref().ok().cloned().flatten();
+
+            if let Some(change) = change.as_ref() {
+                discord_bot.send_change_request(change).await;
+            } else if deleted.is_ok() {
+                stop_batch(&[task_id])
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .map(|j| debug!("{:?}", j))
+                    .ok();
+            }
+
+            let mut resp = GenericResponse::from(deleted.map(|_| Some(())));
+
+            if change.is_some() && resp.status == 200 {
+                resp.status = 202;
+            }
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[get("/<_list_id>/task/<task_id>/items")]
+        pub async fn get_items(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+        ) -> (Status, Json<GenericResponse<Vec<TaskItem>>>) {
+            let items = TaskItem::get_for_task(db_client, task_id).await.map(Some);
+            let resp = GenericResponse::from(items);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[post("/<_list_id>/task/<task_id>/items", format = "json", data = "<item>")]
+        pub async fn create_item(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            item: Json<NewItem>,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<TaskItem>>) {
+            if let Err(resp) = check_task_editor(db_client, task_id, key.api_key.user_id).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let item = TaskItem::new(db_client, task_id, item.into_inner().title)
+                .await
+                .map(Some);
+            let resp = GenericResponse::from(item);
+
+            // if successful update status to 201 Created
+            let status = if resp.status == 200 { 201 } else { resp.status };
+
+            (Status::from_code(status).unwrap(), Json(resp))
+        }
+
+        // the ids of the task's items in their new order
+        #[put(
+            "/<_list_id>/task/<task_id>/items/order",
+            format = "json",
+            data = "<ids>"
+        )]
+        pub async fn reorder_items(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            ids: Json<Vec<Uuid>>,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<Vec<TaskItem>>>) {
+            if let Err(resp) = check_task_editor(db_client, task_id, key.api_key.user_id).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let items = TaskItem::reorder(db_client, task_id, ids.into_inner())
+                .await
+                .map(Some);
+            let resp = GenericResponse::from(items);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        // checking the last item off checks the task off too if it has
+        // auto_check set, which partners may have to approve
+        #[put(
+            "/<_list_id>/task/<task_id>/items/<item_id>",
+            format = "json",
+            data = "<item>"
+        )]
+        pub async fn update_item(
+            db_client: &State<Client>,
+            discord_bot: &State<Bot>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            item_id: Uuid,
+            item: Json<TaskItem>,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<TaskItem>>) {
+            let actor = key.api_key.user_id;
+
+            if let Err(resp) = check_item_editor(db_client, task_id, item_id, actor).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let item = TaskItem {
+                id: item_id,
+                ..item.into_inner()
+            };
+            let updated = TaskItem::update(db_client, item).await;
+
+            if let Ok(Some(item)) = updated.as_ref() {
+                let checked = TaskItem::auto_check(db_client, item.task_id, Some(actor))
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .unwrap_or_default();
+
+                match checked {
+                    (_, Some(change)) => discord_bot.send_change_request(&change).await,
+                    (Some(task), None) if task.checked => {
+                        discord_bot.send_completion(task.id).await;
+                        discord_bot.lift_shame(task.user_id, task.guild_id).await;
+                    }
+                    _ => {}
+                }
+            }
+
+            let resp = GenericResponse::from(updated);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[delete("/<_list_id>/task/<task_id>/items/<item_id>")]
+        pub async fn delete_item(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            item_id: Uuid,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<()>>) {
+            let actor = key.api_key.user_id;
+
+            if let Err(resp) = check_item_editor(db_client, task_id, item_id, actor).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let deleted = TaskItem::delete(db_client, item_id).await.map(Some);
+            let resp = GenericResponse::from(deleted);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[get("/<_list_id>/task/<task_id>/dependencies")]
+        pub async fn get_dependencies(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+        ) -> (Status, Json<GenericResponse<Vec<TaskDependency>>>) {
+            let dependencies = TaskDependency::get_for_task(db_client, task_id)
+                .await
+                .map(Some);
+            let resp = GenericResponse::from(dependencies);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        // the unfinished tasks it depends on, the task is blocked unless this
+        // is empty
+        #[get("/<_list_id>/task/<task_id>/blockers")]
+        pub async fn get_blockers(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+        ) -> (Status, Json<GenericResponse<Vec<Task>>>) {
+            let blockers = TaskDependency::blockers(db_client, task_id).await.map(Some);
+            let resp = GenericResponse::from(blockers);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        #[post(
+            "/<_list_id>/task/<task_id>/dependencies",
+            format = "json",
+            data = "<dependency>"
+        )]
+        pub async fn create_dependency(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            dependency: Json<NewDependency>,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<TaskDependency>>) {
+            if let Err(resp) = check_task_editor(db_client, task_id, key.api_key.user_id).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let dependency = TaskDependency::new(db_client, task_id, dependency.depends_on).await;
+
+            if let Ok(None) = dependency {
+                let resp = GenericResponse {
+                    status: 409,
+                    data: vec![],
+                    error: Some(GenericError {
+                        message: String::from("The dependency would create a cycle."),
+                    }),
+                };
+
+                return (Status::Conflict, Json(resp));
+            }
+
+            let resp = GenericResponse::from(dependency);
+
+            // if successful update status to 201 Created
+            let status = if resp.status == 200 { 201 } else { resp.status };
+
+            (Status::from_code(status).unwrap(), Json(resp))
+        }
+
+        #[delete("/<_list_id>/task/<task_id>/dependencies/<depends_on>")]
+        pub async fn delete_dependency(
+            db_client: &State<Client>,
+            _list_id: Uuid,
+            task_id: Uuid,
+            depends_on: Uuid,
+            key: ShamebotApiKey,
+        ) -> (Status, Json<GenericResponse<()>>) {
+            if let Err(resp) = check_task_editor(db_client, task_id, key.api_key.user_id).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+
+            let deleted = TaskDependency::delete(db_client, task_id, depends_on)
+                .await
+                .map(Some);
+            let resp = GenericResponse::from(deleted);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+
+        // sort is one of due (the default), priority, tag or checked, and
+        // order is asc or desc. priorities are sorted most urgent first
+        // unless asked otherwise
+        #[get("/<list_id>/tasks?<limit>&<cursor>&<query..>")]
+        pub async fn get_tasks(
+            db_client: &State<Client>,
+            list_id: Uuid,
+            limit: Option<i64>,
+            cursor: Option<&str>,
+            query: TaskQuery,
+        ) -> (Status, Json<GenericResponse<Page<Task>>>) {
+            let filter = match query.filter() {
+                Ok(filter) => filter,
+                Err(message) => {
+                    let resp = GenericResponse {
+                        status: 400,
+                        data: vec![],
+                        error: Some(GenericError { message }),
+                    };
+
+                    return (Status::BadRequest, Json(resp));
+                }
+            };
+            let cursor = match crate::routes::parse_cursor(cursor, |cursor| filter.cursor(cursor)) {
+                Ok(cursor) => cursor,
+                Err(resp) => return (Status::BadRequest, Json(resp)),
+            };
+            let limit = limit.unwrap_or(TASKS_PAGE).clamp(1, TASKS_PAGE);
+            let tasks = List::get_tasks_page(db_client, list_id, &filter, cursor.as_ref(), limit)
+                .await
+                .map(Some);
+            let resp = GenericResponse::from(tasks);
+
+            (Status::from_code(resp.status).unwrap(), Json(resp))
+        }
+    }
+}
+
+pub mod tasks {
+    use database::prelude::{
+        Client, DatabaseError, ListMember, ListRole, NewTask, PendingChange, Task,
+    };
+    use discord::{bot::Bot, jobs::rescheduled};
+    use log::{debug, error};
+    use rocket::serde::json::Json;
+    use rocket::serde::{Deserialize, Serialize};
+    use rocket::{http::Status, State};
+    use uuid::Uuid;
+
+    use crate::routes::list::task::{not_assignable, register_batch, reschedule_batch, stop_batch};
+    use crate::routes::{
+        check_list_role, check_task_editor, GenericError, GenericResponse, ShamebotApiKey,
+    };
+
+    const BULK_LIMIT: usize = 100;
+
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde", tag = "op", rename_all = "lowercase")]
+    pub enum Operation {
+        Create { task: Task },
+        Update { task: Task },
+        Check { task_id: Uuid, checked: bool },
+        Delete { task_id: Uuid },
+    }
+
+    // one per operation, in the order they were sent. task is None for
+    // deletions
+    #[derive(Serialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct OperationResult {
+        status: u16,
+        task: Option<Task>,
+        error: Option<GenericError>,
+    }
+
+    // an operation that went through, the notifications wait for the commit
+    struct Applied {
+        status: u16,
+        task: Option<Task>,
+        previous: Option<Task>,
+        change: Option<PendingChange>,
+    }
+
+    fn rejected<T>(resp: GenericResponse<T>) -> OperationResult {
+        OperationResult {
+            status: resp.status,
+            task: None,
+            error: resp.error,
+        }
+    }
+
+    fn failed(e: DatabaseError) -> OperationResult {
+        rejected(GenericResponse::from(Err::<Option<Task>, _>(e)))
+    }
+
+    fn found(task: Result<Option<Task>, DatabaseError>) -> Result<Task, OperationResult> {
+        match task {
+            Ok(Some(task)) => Ok(task),
+            result => Err(rejected(GenericResponse::from(result))),
+        }
+    }
+
+    // the same checks as update_task
+    async fn update(tx: &Client, task: Task, actor: i64) -> Result<Applied, OperationResult> {
+        let previous = check_task_editor::<Task>(tx, task.id, actor)
+            .await
+            .map_err(rejected)?;
+
+        if task.list_id != previous.list_id {
+            check_list_role::<Task>(tx, task.list_id, actor, ListRole::Editor)
+                .await
+                .map_err(rejected)?;
+        }
+
+        let (updated, change) = Task::update_guarded(tx, task, Some(actor))
+            .await
+            .map_err(failed)?;
+        let status = if change.is_some() { 202 } else { 200 };
+
+        Ok(Applied {
+            status,
+            task: Some(found(Ok(updated))?),
+            previous: Some(previous),
+            change,
+        })
+    }
+
+    async fn apply(
+        tx: &Client,
+        operation: Operation,
+        actor: i64,
+    ) -> Result<Applied, OperationResult> {
+        match operation {
+            Operation::Create { task } => {
+                check_list_role::<Task>(tx, task.list_id, actor, ListRole::Editor)
+                    .await
+                    .map_err(rejected)?;
+
+                let assignable =
+                    ListMember::assignable(tx, task.list_id, task.guild_id, task.user_id)
+                        .await
+                        .map_err(failed)?;
+
+                if !assignable {
+                    return Err(rejected(not_assignable::<Task>(task.user_id)));
+                }
+
+                let created = Task::new(tx, NewTask::from(&task), Some(actor))
+                    .await
+                    .map_err(failed)?;
+
+                Ok(Applied {
+                    status: 201,
+                    task: Some(created),
+                    previous: None,
+                    change: None,
+                })
+            }
+            Operation::Update { task } => update(tx, task, actor).await,
+            Operation::Check { task_id, checked } => {
+                let current = found(Task::get(tx, task_id).await)?;
+
+                update(tx, Task { checked, ..current }, actor).await
+            }
+            Operation::Delete { task_id } => {
+                let current = check_task_editor::<Task>(tx, task_id, actor)
+                    .await
+                    .map_err(rejected)?;
+
+                let change = Task::delete_guarded(tx, task_id, Some(actor))
+                    .await
+                    .map_err(failed)?;
+                let status = if change.is_some() { 202 } else { 200 };
+
+                Ok(Applied {
+                    status,
+                    task: None,
+                    previous: Some(current),
+                    change,
+                })
+            }
+        }
+    }
+
+    // the same notifications update_task and delete_task send
+    async fn notify(discord_bot: &Bot, applied: &Applied) {
+        if let Some(change) = applied.change.as_ref() {
+            discord_bot.send_change_request(change).await;
+        }
+
+        if let (Some(task), Some(previous)) = (applied.task.as_ref(), applied.previous.as_ref()) {
+            if task.proof_id.is_some() && task.proof_id != previous.proof_id {
+                discord_bot.send_proof(task.id).await;
+            }
+
+            if task.checked && !previous.checked {
+                discord_bot.send_completion(task.id).await;
+            }
+
+            if task.checked {
+                discord_bot.lift_shame(task.user_id, task.guild_id).await;
+            }
+        }
+    }
+
+    // every result but the failed one is 424 Failed Dependency, since none
+    // of them were applied either
+    fn rolled_back(
+        index: usize,
+        result: OperationResult,
+        count: usize,
+    ) -> (Status, Json<GenericResponse<Vec<OperationResult>>>) {
+        let status = result.status;
+        let mut results = (0..count)
+            .map(|_| OperationResult {
+                status: 424,
+                task: None,
+                error: Some(GenericError {
+                    message: format!("Not applied, operation {} failed.", index),
+                }),
+            })
+            .collect::<Vec<OperationResult>>();
+        results[index] = result;
+
+        let resp = GenericResponse {
+            status,
+            data: vec![results],
+            error: Some(GenericError {
+                message: format!("Operation {} failed, nothing was applied.", index),
+            }),
+        };
+
+        (Status::from_code(status).unwrap(), Json(resp))
+    }
+
+    // creates, updates, checks off and deletes tasks in one transaction, all
+    // or nothing, with the same role checks as the single task routes.
+    // notifications go out once it's committed, and the jobs of every
+    // created task are registered with a single call to cron
+    #[post("/bulk", format = "json", data = "<operations>")]
+    pub async fn bulk(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        operations: Json<Vec<Operation>>,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<Vec<OperationResult>>>) {
+        let operations = operations.into_inner();
+        let count = operations.len();
+
+        if count == 0 || count > BULK_LIMIT {
+            let resp = GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError {
+                    message: format!("Send between 1 and {} operations.", BULK_LIMIT),
+                }),
+            };
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let tx = match db_client.transaction().await {
+            Ok(tx) => tx,
+            Err(e) => {
+                let resp = GenericResponse::from(Err::<Option<Vec<OperationResult>>, _>(e));
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+        };
+        let actor = key.api_key.user_id;
+        let mut applied = Vec::with_capacity(count);
+
+        for operation in operations {
+            match apply(&tx, operation, actor).await {
+                Ok(done) => applied.push(done),
+                Err(result) => {
+                    let index = applied.len();
+                    tx.rollback().await.map_err(|e| error!("{}", e)).ok();
+
+                    return rolled_back(index, result, count);
+                }
+            }
+        }
+
+        if let Err(e) = tx.commit().await {
+            let resp = GenericResponse::from(Err::<Option<Vec<OperationResult>>, _>(e));
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
+        }
+
+        let created = applied
+            .iter()
+            .filter(|done| done.status == 201)
+            .filter_map(|done| done.task.as_ref().map(|task| task.id))
+            .collect::<Vec<Uuid>>();
+
+        if !created.is_empty() {
+            register_batch(&created)
+                .await
+                .map_err(|e| error!("{}", e))
+                .map(|j| debug!("{:?}", j))
+                .ok();
+        }
+
+        let moved = applied
+            .iter()
+            .filter_map(|done| done.previous.as_ref().zip(done.task.as_ref()))
+            .filter(|(previous, task)| rescheduled(previous, task))
+            .map(|(_, task)| task.id)
+            .collect::<Vec<Uuid>>();
+
+        if !moved.is_empty() {
+            reschedule_batch(&moved)
+                .await
+                .map_err(|e| error!("{}", e))
+                .map(|j| debug!("{:?}", j))
+                .ok();
+        }
+
+        // deletions that didn't have to wait for a partner
+        let deleted = applied
+            .iter()
+            .filter(|done| done.task.is_none() && done.change.is_none())
+            .filter_map(|done| done.previous.as_ref().map(|task| task.id))
+            .collect::<Vec<Uuid>>();
+
+        if !deleted.is_empty() {
+            stop_batch(&deleted)
+                .await
+                .map_err(|e| error!("{}", e))
+                .map(|j| debug!("{:?}", j))
+                .ok();
+        }
+
+        let mut results = Vec::with_capacity(count);
+
+        for done in applied {
+            notify(discord_bot, &done).await;
+
+            // created tasks are fetched again for their job ids
+            let task = match done.task {
+                Some(task) if done.status == 201 => {
+                    let registered = Task::get(db_client, task.id).await.ok().flatten();
+                    registered.or(Some(task))
+                }
+                task => task,
+            };
+
+            results.push(OperationResult {
+                status: done.status,
+                task,
+                error: None,
+            });
+        }
+
+        let resp = GenericResponse {
+            status: 200,
+            data: vec![results],
+            error: None,
+        };
+
+        (Status::Ok, Json(resp))
+    }
+}
+
+pub mod proof {
+    use database::prelude::{AccountabilityRequest, Client, ListRole, Proof, Task};
+    use discord::bot::Bot;
+    use log::error;
+    use rocket::serde::json::Json;
+    use rocket::{http::Status, State};
+    use uuid::Uuid;
+
+    use crate::routes::{check_list_role, check_partner, GenericResponse, ShamebotApiKey};
+
+    #[post("/", format = "json", data = "<proof>")]
+    pub async fn create_proof(
+        db_client: &State<Client>,
+        proof: Json<Proof>,
+    ) -> (Status, Json<GenericResponse<Proof>>) {
+        let new_proof = Proof::new(db_client, proof.content.clone(), proof.image.clone())
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(new_proof);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[get("/<id>")]
+    pub async fn get_proof(
+        db_client: &State<Client>,
+        id: Uuid,
+    ) -> (Status, Json<GenericResponse<Proof>>) {
+        let proof = Proof::get(db_client, id).await;
+        let resp = GenericResponse::from(proof);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // counts the partner's approval, the proof is only approved once the
+    // task's quorum is reached. a task without partners has no one to wait
+    // for, so an editor of its list approves it right away
+    #[post("/<id>/approve")]
+    pub async fn approve(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        id: Uuid,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<Proof>>) {
+        let task = Task::get_by_proof(db_client, id)
+            .await
+            .map_err(|e| error!("{}", e))
+            .ok()
+            .flatten();
+
+        let task = match task {
+            Some(task) => task,
+            None => {
+                let resp = GenericResponse::from(Ok::<Option<Proof>, String>(None));
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+        };
+
+        let actor = key.api_key.user_id;
+        let allowed = match AccountabilityRequest::partners(db_client, task.id).await {
+            Ok(partners) if partners.is_empty() => {
+                check_list_role(db_client, task.list_id, actor, ListRole::Editor).await
+            }
+            Ok(_) => check_partner(db_client, task.id, actor).await,
+            Err(e) => Err(GenericResponse::from(Err::<Option<Proof>, _>(e))),
+        };
+
+        if let Err(resp) = allowed {
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
+        }
+
+        let approval = Proof::approve(db_client, id, key.api_key.user_id).await;
+
+        if let Ok(Some(proof)) = approval.as_ref() {
+            if proof.approved {
+                discord_bot.lift_shame(task.user_id, task.guild_id).await;
+            }
+        }
+
+        let resp = GenericResponse::from(approval);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[delete("/<id>")]
+    pub async fn delete_proof(
+        db_client: &State<Client>,
+        id: Uuid,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        let deleted = Proof::delete(db_client, id).await.map(Some);
+        let resp = GenericResponse::from(deleted);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+}
+
+pub mod accountability {
+    use database::prelude::{AccountabilityRequest, Client, PendingChange, Task};
+    use discord::{bot::Bot, jobs};
+    use log::error;
+    use rocket::serde::json::Json;
+    use rocket::serde::Deserialize;
+    use rocket::{http::Status, State};
+    use uuid::Uuid;
+
+    use crate::routes::{check_partner, GenericError, GenericResponse, ShamebotApiKey};
+
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct Comment {
+        content: String,
+    }
+
+    #[post("/", format = "json", data = "<request>")]
+    pub async fn create_request(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        request: Json<AccountabilityRequest>,
+    ) -> (Status, Json<GenericResponse<AccountabilityRequest>>) {
+        let new_request = AccountabilityRequest::new(
+            db_client,
+            request.requesting_user,
+            request.requested_user,
+            request.task_id,
+        )
+        .await
+        .map(Some);
+
+        if let Ok(r) = new_request.as_ref() {
+            discord_bot.send_accountability_request(r).await;
+        }
+
+        let resp = GenericResponse::from(new_request);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // every request for the task, whatever its status
+    #[get("/<id>")]
+    pub async fn get_request(
+        db_client: &State<Client>,
+        id: Uuid,
+    ) -> (Status, Json<GenericResponse<Vec<AccountabilityRequest>>>) {
+        let requests = AccountabilityRequest::get_all(db_client, id)
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(requests);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // only the requested user can answer a request
+    #[put("/<_id>", format = "json", data = "<request>")]
+    pub async fn update_status(
+        db_client: &State<Client>,
+        _id: Uuid,
+        request: Json<AccountabilityRequest>,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        if request.requested_user != key.api_key.user_id {
+            let resp = GenericResponse {
+                status: 403,
+                data: vec![],
+                error: Some(GenericError {
+                    message: String::from("Only the requested user can answer the request."),
+                }),
+            };
+
+            return (Status::Forbidden, Json(resp));
+        }
+
+        let approval = AccountabilityRequest::update_status(
+            db_client,
+            request.task_id,
+            request.requested_user,
+            request.status,
+        )
+        .await
+        .map(Some);
+        let resp = GenericResponse::from(approval);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // without a user, every request for the task is deleted. partners can
+    // leave on their own, the owner dropping an accepted partner waits for
+    // the partners to approve it and returns 202 Accepted
+    #[delete("/<id>?<user_id>")]
+    pub async fn delete_request(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        id: Uuid,
+        user_id: Option<i64>,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        let actor = key.api_key.user_id;
+
+        if user_id != Some(actor) {
+            let owner = Task::get(db_client, id)
+                .await
+                .map_err(|e| error!("{}", e))
+                .ok()
+                .flatten()
+                .is_some_and(|task| task.user_id == actor);
+
+            if !owner {
+                let resp = GenericResponse {
+                    status: 403,
+                    data: vec![],
+                    error: Some(GenericError {
+                        message: String::from("Only the owner of the task can do this."),
+                    }),
+                };
+
+                return (Status::Forbidden, Json(resp));
             }
         }
 
-        #[post("/<_id>/task", format = "json", data = "<task>")]
-        pub async fn create_task(
-            db_client: &State<Client>,
-            _id: Uuid,
-            task: Json<Task>,
-        ) -> (Status, Json<GenericResponse<Task>>) {
-            let task = Task::new(
-                db_client,
-                task.list_id,
-                task.user_id,
-                task.guild_id,
-                task.title.clone(),
-                task.content.clone(),
-                task.pester,
-                task.due_at,
-            )
-            .await
-            .map(Some);
-            let resp: GenericResponse<Task>;
+        let deleted = AccountabilityRequest::delete_guarded(db_client, id, user_id, actor).await;
+        let change = deleted.as_ref().ok().cloned().flatten();
 
-            if let Some(task) = task.as_ref().ok().unwrap() {
-                register_jobs(task.id)
-                    .await
-                    .map_err(|e| error!("{}", e))
-                    .map(|j| debug!("{:?}", j))
-                    .ok();
+        if let Some(change) = change.as_ref() {
+            discord_bot.send_change_request(change).await;
+        }
 
-                let updated_task = Task::get(db_client, task.id).await;
-                resp = GenericResponse::from(updated_task);
-            } else {
-                resp = GenericResponse::from(task);
-            }
+        let mut resp = GenericResponse::from(deleted.map(|_| Some(())));
 
-            (Status::from_code(resp.status).unwrap(), Json(resp))
+        if change.is_some() && resp.status == 200 {
+            resp.status = 202;
         }
 
-        #[put("/<_list_id>/task/<_task_id>", format = "json", data = "<task>")]
-        pub async fn update_task(
-            db_client: &State<Client>,
-            _list_id: Uuid,
-            _task_id: Uuid,
-            task: Json<Task>,
-        ) -> (Status, Json<GenericResponse<Task>>) {
-            let updated = Task::update(db_client, task.into_inner()).await;
-            let resp = GenericResponse::from(updated);
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
 
-            (Status::from_code(resp.status).unwrap(), Json(resp))
+    // partner comments, and the owner's replies, are posted wherever the
+    // task's messages go, which is its own thread if the guild has them
+    // enabled. they're attributed to whoever the key belongs to
+    #[post("/<id>/comment", format = "json", data = "<comment>")]
+    pub async fn comment(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        id: Uuid,
+        comment: Json<Comment>,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<()>>) {
+        let user_id = key.api_key.user_id;
+        let owner = Task::get(db_client, id)
+            .await
+            .map_err(|e| error!("{}", e))
+            .ok()
+            .flatten()
+            .is_some_and(|task| task.user_id == user_id);
+
+        if !owner {
+            if let Err(resp) = check_partner(db_client, id, user_id).await {
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
         }
 
-        #[get("/<_list_id>/task/<task_id>")]
-        pub async fn get_task(
-            db_client: &State<Client>,
-            _list_id: Uuid,
-            task_id: Uuid,
-        ) -> (Status, Json<GenericResponse<Task>>) {
-            let task = Task::get(db_client, task_id).await;
-            let resp = GenericResponse::from(task);
+        discord_bot
+            .send_comment(id, user_id, comment.into_inner().content)
+            .await;
+        let resp = GenericResponse::from(Ok::<_, String>(Some(())));
 
-            (Status::from_code(resp.status).unwrap(), Json(resp))
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // the change to the task that's waiting for its partners, if any
+    #[get("/<id>/change")]
+    pub async fn get_change(
+        db_client: &State<Client>,
+        id: Uuid,
+    ) -> (Status, Json<GenericResponse<PendingChange>>) {
+        let change = PendingChange::get(db_client, id).await;
+        let resp = GenericResponse::from(change);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[post("/<id>/change/approve")]
+    pub async fn approve_change(
+        db_client: &State<Client>,
+        discord_bot: &State<Bot>,
+        id: Uuid,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<PendingChange>>) {
+        if let Err(resp) = check_partner(db_client, id, key.api_key.user_id).await {
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
         }
 
-        #[delete("/<_list_id>/task/<task_id>")]
-        pub async fn delete_task(
-            db_client: &State<Client>,
-            _list_id: Uuid,
-            task_id: Uuid,
-        ) -> (Status, Json<GenericResponse<()>>) {
-            let deleted = Task::delete(db_client, task_id).await.map(Some);
-            let resp = GenericResponse::from(deleted);
+        let task = Task::get(db_client, id)
+            .await
+            .map_err(|e| error!("{}", e))
+            .ok()
+            .flatten();
+        let approved = PendingChange::approve(db_client, id, key.api_key.user_id).await;
 
-            (Status::from_code(resp.status).unwrap(), Json(resp))
+        if let (Ok(Some(change)), Some(task)) = (approved.as_ref(), task) {
+            jobs::approved(change, &task).await;
+
+            if change.checked && !task.checked {
+                discord_bot.send_completion(task.id).await;
+                discord_bot.lift_shame(task.user_id, task.guild_id).await;
+            }
         }
 
-        #[get("/<list_id>/tasks")]
-        pub async fn get_tasks(
-            db_client: &State<Client>,
-            list_id: Uuid,
-        ) -> (Status, Json<GenericResponse<Vec<Task>>>) {
-            let tasks = List::get_tasks(db_client, list_id).await.map(Some);
-            let resp = GenericResponse::from(tasks);
+        let resp = GenericResponse::from(approved);
 
-            (Status::from_code(resp.status).unwrap(), Json(resp))
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    #[post("/<id>/change/reject")]
+    pub async fn reject_change(
+        db_client: &State<Client>,
+        id: Uuid,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<PendingChange>>) {
+        if let Err(resp) = check_partner(db_client, id, key.api_key.user_id).await {
+            return (Status::from_code(resp.status).unwrap(), Json(resp));
         }
+
+        let rejected = PendingChange::reject(db_client, id).await;
+        let resp = GenericResponse::from(rejected);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 }
 
-pub mod proof {
-    use database::prelude::{Client, Proof};
+pub mod partnership {
+    use database::prelude::{Client, Partnership, RequestStatus};
+    use discord::bot::Bot;
     use rocket::serde::json::Json;
+    use rocket::serde::Deserialize;
     use rocket::{http::Status, State};
     use uuid::Uuid;
 
-    use crate::routes::GenericResponse;
+    use crate::routes::{GenericError, GenericResponse, ShamebotApiKey};
 
-    #[post("/", format = "json", data = "<proof>")]
-    pub async fn create_proof(
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct NewPartnership {
+        user_id: i64,
+        partner_id: i64,
+        list_id: Option<Uuid>,
+        guild_id: Option<i64>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct Answer {
+        status: RequestStatus,
+    }
+
+    // a partnership covers either one list or everything in a guild, the
+    // partner is asked in a dm
+    #[post("/", format = "json", data = "<partnership>")]
+    pub async fn create_partnership(
         db_client: &State<Client>,
-        proof: Json<Proof>,
-    ) -> (Status, Json<GenericResponse<Proof>>) {
-        let new_proof = Proof::new(db_client, proof.content.clone(), proof.image.clone())
-            .await
-            .map(Some);
-        let resp = GenericResponse::from(new_proof);
+        discord_bot: &State<Bot>,
+        partnership: Json<NewPartnership>,
+    ) -> (Status, Json<GenericResponse<Partnership>>) {
+        let message = if partnership.list_id.is_some() == partnership.guild_id.is_some() {
+            Some("A partnership needs either a list_id or a guild_id.")
+        } else if partnership.user_id == partnership.partner_id {
+            Some("You can't be your own partner.")
+        } else {
+            None
+        };
 
-        (Status::from_code(resp.status).unwrap(), Json(resp))
+        if let Some(message) = message {
+            let resp = GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError {
+                    message: String::from(message),
+                }),
+            };
+
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let new_partnership = Partnership::new(
+            db_client,
+            partnership.user_id,
+            partnership.partner_id,
+            partnership.list_id,
+            partnership.guild_id,
+        )
+        .await
+        .map(Some);
+
+        if let Ok(Some(p)) = new_partnership.as_ref() {
+            discord_bot.send_partnership_request(p).await;
+        }
+
+        let resp = GenericResponse::from(new_partnership);
+
+        // if successful update status to 201 Created
+        let status = if resp.status == 200 { 201 } else { resp.status };
+
+        (Status::from_code(status).unwrap(), Json(resp))
     }
 
     #[get("/<id>")]
-    pub async fn get_proof(
+    pub async fn get_partnership(
         db_client: &State<Client>,
         id: Uuid,
-    ) -> (Status, Json<GenericResponse<Proof>>) {
-        let proof = Proof::get(db_client, id).await;
-        let resp = GenericResponse::from(proof);
+    ) -> (Status, Json<GenericResponse<Partnership>>) {
+        let partnership = Partnership::get(db_client, id).await;
+        let resp = GenericResponse::from(partnership);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
-    #[post("/<id>/approve")]
-    pub async fn approve(
+    // only the partner can accept or reject
+    #[put("/<id>", format = "json", data = "<answer>")]
+    pub async fn update_status(
         db_client: &State<Client>,
         id: Uuid,
-    ) -> (Status, Json<GenericResponse<()>>) {
-        let approval = Proof::approve(db_client, id).await.map(Some);
-        let resp = GenericResponse::from(approval);
+        answer: Json<Answer>,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<Partnership>>) {
+        match Partnership::get(db_client, id).await {
+            Ok(Some(p)) if p.partner_id != key.api_key.user_id => {
+                let resp = GenericResponse {
+                    status: 403,
+                    data: vec![],
+                    error: Some(GenericError {
+                        message: String::from("Only the partner can answer a partnership."),
+                    }),
+                };
+
+                return (Status::Forbidden, Json(resp));
+            }
+            Ok(Some(_)) => {}
+            other => {
+                let resp = GenericResponse::from(other);
+
+                return (Status::from_code(resp.status).unwrap(), Json(resp));
+            }
+        }
+
+        let updated = Partnership::update_status(db_client, id, answer.status).await;
+        let resp = GenericResponse::from(updated);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
+    // tasks that already inherited the partner keep them
     #[delete("/<id>")]
-    pub async fn delete_proof(
+    pub async fn delete_partnership(
         db_client: &State<Client>,
         id: Uuid,
     ) -> (Status, Json<GenericResponse<()>>) {
-        let deleted = Proof::delete(db_client, id).await.map(Some);
+        let deleted = Partnership::delete(db_client, id).await.map(Some);
         let resp = GenericResponse::from(deleted);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 }
 
-pub mod accountability {
-    use database::prelude::{AccountabilityRequest, Client};
-    use discord::bot::Bot;
+pub mod template {
+    use database::prelude::{Client, List, ListTemplate, TaskFilter, TemplateTask};
+    use log::{debug, error};
     use rocket::serde::json::Json;
+    use rocket::serde::Deserialize;
     use rocket::{http::Status, State};
     use uuid::Uuid;
 
-    use crate::routes::GenericResponse;
+    use crate::routes::list::task::register_batch;
+    use crate::routes::{GenericError, GenericResponse};
 
-    #[post("/", format = "json", data = "<request>")]
-    pub async fn create_request(
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct NewTemplate {
+        title: String,
+        user_id: i64,
+        pester: Option<i16>,
+        tasks: Vec<TemplateTask>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct ListClone {
+        title: Option<String>,
+    }
+
+    // start is a UNIX timestamp, the list's title defaults to the template's
+    #[derive(Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    pub struct Instantiation {
+        user_id: i64,
+        guild_id: i64,
+        start: i64,
+        title: Option<String>,
+    }
+
+    #[post("/", format = "json", data = "<template>")]
+    pub async fn create_template(
         db_client: &State<Client>,
-        discord_bot: &State<Bot>,
-        request: Json<AccountabilityRequest>,
-    ) -> (Status, Json<GenericResponse<AccountabilityRequest>>) {
-        let new_request = AccountabilityRequest::new(
+        template: Json<NewTemplate>,
+    ) -> (Status, Json<GenericResponse<ListTemplate>>) {
+        let template = template.into_inner();
+
+        let negative = |task: &TemplateTask| task.due_offset.is_some_and(|offset| offset < 0);
+
+        if template.tasks.iter().any(negative) {
+            let resp = GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError {
+                    message: String::from("Due offsets can't be negative."),
+                }),
+            };
+
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let new_template = ListTemplate::new(
             db_client,
-            request.requesting_user,
-            request.requested_user,
-            request.task_id,
+            template.title,
+            template.user_id,
+            template.pester,
+            template.tasks,
         )
         .await
         .map(Some);
+        let resp = GenericResponse::from(new_template);
 
-        if let Ok(r) = new_request.as_ref() {
-            discord_bot.send_accountability_request(r).await;
-        }
-
-        let resp = GenericResponse::from(new_request);
+        // if successful update status to 201 Created
+        let status = if resp.status == 200 { 201 } else { resp.status };
 
-        (Status::from_code(resp.status).unwrap(), Json(resp))
+        (Status::from_code(status).unwrap(), Json(resp))
     }
 
     #[get("/<id>")]
-    pub async fn get_request(
+    pub async fn get_template(
         db_client: &State<Client>,
         id: Uuid,
-    ) -> (Status, Json<GenericResponse<AccountabilityRequest>>) {
-        let request = AccountabilityRequest::get(db_client, id).await;
-        let resp = GenericResponse::from(request);
+    ) -> (Status, Json<GenericResponse<ListTemplate>>) {
+        let template = ListTemplate::get(db_client, id).await;
+        let resp = GenericResponse::from(template);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
-    #[put("/<_id>", format = "json", data = "<request>")]
-    pub async fn update_status(
+    // turns an existing list into a template, with offsets counted from the
+    // list's earliest due date
+    #[post("/from/<list_id>", format = "json", data = "<clone>")]
+    pub async fn clone_list(
         db_client: &State<Client>,
-        _id: Uuid,
-        request: Json<AccountabilityRequest>,
-    ) -> (Status, Json<GenericResponse<()>>) {
-        let approval =
-            AccountabilityRequest::update_status(db_client, request.task_id, request.status)
+        list_id: Uuid,
+        clone: Json<ListClone>,
+    ) -> (Status, Json<GenericResponse<ListTemplate>>) {
+        let template = ListTemplate::from_list(db_client, list_id, clone.title.clone()).await;
+        let resp = GenericResponse::from(template);
+
+        // if successful update status to 201 Created
+        let status = if resp.status == 200 { 201 } else { resp.status };
+
+        (Status::from_code(status).unwrap(), Json(resp))
+    }
+
+    // every generated task gets its jobs registered right away, like it
+    // would have been created by hand
+    #[post("/<id>/instantiate", format = "json", data = "<instantiation>")]
+    pub async fn instantiate(
+        db_client: &State<Client>,
+        id: Uuid,
+        instantiation: Json<Instantiation>,
+    ) -> (Status, Json<GenericResponse<List>>) {
+        let list = ListTemplate::instantiate(
+            db_client,
+            id,
+            instantiation.user_id,
+            instantiation.guild_id,
+            instantiation.start,
+            instantiation.title.clone(),
+        )
+        .await;
+
+        if let Ok(Some(list)) = list.as_ref() {
+            let tasks = List::get_tasks(db_client, list.id, &TaskFilter::default())
                 .await
-                .map(Some);
-        let resp = GenericResponse::from(approval);
+                .map_err(|e| error!("{}", e))
+                .unwrap_or_default();
 
-        (Status::from_code(resp.status).unwrap(), Json(resp))
+            let task_ids = tasks.iter().map(|task| task.id).collect::<Vec<Uuid>>();
+
+            register_batch(&task_ids)
+                .await
+                .map_err(|e| error!("{}", e))
+                .map(|j| debug!("{:?}", j))
+                .ok();
+        }
+
+        let resp = GenericResponse::from(list);
+
+        // if successful update status to 201 Created
+        let status = if resp.status == 200 { 201 } else { resp.status };
+
+        (Status::from_code(status).unwrap(), Json(resp))
     }
 
     #[delete("/<id>")]
-    pub async fn delete_request(
+    pub async fn delete_template(
         db_client: &State<Client>,
         id: Uuid,
     ) -> (Status, Json<GenericResponse<()>>) {
-        let deleted = AccountabilityRequest::delete(db_client, id).await.map(Some);
+        let deleted = ListTemplate::delete(db_client, id).await.map(Some);
         let resp = GenericResponse::from(deleted);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 }
 
+pub mod search {
+    use database::prelude::{Client, SearchHit};
+    use rocket::serde::json::Json;
+    use rocket::{http::Status, State};
+
+    use crate::routes::{GenericError, GenericResponse, ShamebotApiKey};
+
+    const SEARCH_PAGE: i64 = 20;
+
+    // only finds what the key's user can see, best matches first. the next
+    // page starts at offset + limit, there are no more once a page comes
+    // back short
+    #[get("/?<q>&<offset>&<limit>")]
+    pub async fn search(
+        db_client: &State<Client>,
+        q: &str,
+        offset: Option<i64>,
+        limit: Option<i64>,
+        key: ShamebotApiKey,
+    ) -> (Status, Json<GenericResponse<Vec<SearchHit>>>) {
+        if q.trim().is_empty() {
+            let resp = GenericResponse {
+                status: 400,
+                data: vec![],
+                error: Some(GenericError {
+                    message: String::from("The search can't be empty."),
+                }),
+            };
+
+            return (Status::BadRequest, Json(resp));
+        }
+
+        let offset = offset.unwrap_or_default().max(0);
+        let limit = limit.unwrap_or(SEARCH_PAGE).clamp(1, SEARCH_PAGE);
+        let hits = SearchHit::search(db_client, key.api_key.user_id, q, offset, limit)
+            .await
+            .map(Some);
+        let resp = GenericResponse::from(hits);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+}
+
 pub mod discord {
     use chrono::Utc;
-    use database::prelude::{ApiKey, Client, Token, User};
+    use database::prelude::{ApiKey, Client, Page, Token, User};
     use discord::bot::{Bot, GuildChannel, Member, User as DiscordUser};
     use log::{error, info};
     use rocket::serde::json::Json;
@@ -579,10 +2901,12 @@ pub mod discord {
     use uuid::Uuid;
 
     use crate::environment;
-    use crate::routes::GenericResponse;
+    use crate::routes::{GenericError, GenericResponse};
 
     use super::ShamebotApiKey;
 
+    const MEMBERS_PAGE: u64 = 100;
+
     #[derive(Serialize, Deserialize)]
     #[serde(crate = "rocket::serde")]
     struct TokenRequest {
@@ -625,12 +2949,30 @@ pub mod discord {
         refresh_token: String,
     }
 
-    #[get("/guild/<id>/members")]
+    // the cursor is the user id of the last member on the previous page
+    #[get("/guild/<id>/members?<limit>&<cursor>")]
     pub async fn get_guild_members(
         discord_bot: &State<Bot>,
         id: u64,
-    ) -> (Status, Json<GenericResponse<Vec<Member>>>) {
-        let members = discord_bot.get_guild_members(id).await;
+        limit: Option<u64>,
+        cursor: Option<&str>,
+    ) -> (Status, Json<GenericResponse<Page<Member>>>) {
+        let after = match cursor.map(str::parse::<u64>).transpose() {
+            Ok(after) => after,
+            Err(_) => {
+                let resp = GenericResponse {
+                    status: 400,
+                    data: vec![],
+                    error: Some(GenericError {
+                        message: String::from("The cursor isn't valid."),
+                    }),
+                };
+
+                return (Status::BadRequest, Json(resp));
+            }
+        };
+        let limit = limit.unwrap_or(MEMBERS_PAGE).clamp(1, MEMBERS_PAGE);
+        let members = discord_bot.get_guild_members(id, limit, after).await;
         let resp = GenericResponse::from(members);
 
         (Status::from_code(resp.status).unwrap(), Json(resp))
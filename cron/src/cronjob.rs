@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use chrono::{Datelike, TimeZone, Timelike, Utc};
-use database::prelude::{Client, DatabaseError, JobType, Task, TaskJobs};
+use database::prelude::{
+    Client, DatabaseError, Guild, JobType, List, NotificationKind, Partnership, Task, TaskEvent,
+    TaskJobs,
+};
 use discord::bot::Bot;
 use log::{error, info};
 use tokio::sync::Mutex;
@@ -11,6 +14,15 @@ use tokio_cron_scheduler::{
 };
 use uuid::Uuid;
 
+// sunday evening, so the week's deadlines have passed
+const LEADERBOARD_SCHEDULE: &str = "0 0 18 * * Sun";
+const WEEK: i64 = 7 * 24 * 60 * 60;
+// every morning, covering the tasks created since the previous digest
+const DIGEST_SCHEDULE: &str = "0 0 9 * * *";
+const DAY: i64 = 24 * 60 * 60;
+// every night, for whatever was soft deleted before the retention period
+const RETENTION_SCHEDULE: &str = "0 0 3 * * *";
+
 #[derive(Clone)]
 pub struct Scheduler {
     scheduler: JobScheduler,
@@ -61,6 +73,30 @@ impl Scheduler {
         self.scheduler.remove(job_id).await
     }
 
+    // stops the jobs of deleted tasks and detaches them, so restoring a task
+    // registers them from scratch. returns the jobs that were stopped
+    pub async fn stop_batch(&self, task_ids: &[Uuid]) -> Result<Vec<TaskJobs>, DatabaseError> {
+        let mut stopped = Vec::with_capacity(task_ids.len());
+
+        for task_id in task_ids {
+            let task_jobs = self.get_jobs(*task_id).await?;
+
+            for (job_type, job_id) in task_jobs.iter() {
+                if let Some(job_id) = job_id {
+                    self.stop_job(job_id)
+                        .await
+                        .map_err(|e| error!("{}", e))
+                        .ok();
+                    Task::remove_job(&self.db_client, *task_id, *job_id, job_type).await?;
+                }
+            }
+
+            stopped.push(task_jobs);
+        }
+
+        Ok(stopped)
+    }
+
     pub async fn resume_jobs(&self) {
         info!("attempting to resume existing jobs");
 
@@ -96,15 +132,174 @@ impl Scheduler {
         }
     }
 
+    // not tied to any task, so this is registered fresh on every start
+    pub async fn register_leaderboard_job(&self) {
+        info!("registering weekly leaderboard cron");
+
+        let db_client = self.db_client.clone();
+        let discord_mtx = Arc::new(Mutex::new(Bot::new().await));
+
+        let job = Job::new_async(LEADERBOARD_SCHEDULE, move |uuid, _| {
+            let db_client = db_client.clone();
+            let discord_clone = Arc::clone(&discord_mtx);
+            Box::pin(async move {
+                let guilds = Guild::get_all(&db_client)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .unwrap_or_default();
+
+                let until = Utc::now().timestamp();
+                let discord_lock = discord_clone.lock().await;
+
+                for guild in guilds {
+                    discord_lock
+                        .send_leaderboard(guild.id, until - WEEK, until)
+                        .await;
+                }
+
+                info!("triggered cron {:?}", uuid);
+            })
+        })
+        .map_err(|e| error!("{}", e))
+        .ok();
+
+        if let Some(job) = job {
+            let uuid = self
+                .scheduler
+                .add(job)
+                .await
+                .map_err(|e| error!("{}", e))
+                .ok();
+
+            if let Some(uuid) = uuid {
+                info!("registered leaderboard cron {:?}", uuid);
+            }
+        }
+    }
+
+    // like the leaderboard, this is registered fresh on every start
+    pub async fn register_digest_job(&self) {
+        info!("registering daily partner digest cron");
+
+        let db_client = self.db_client.clone();
+        let discord_mtx = Arc::new(Mutex::new(Bot::new().await));
+
+        let job = Job::new_async(DIGEST_SCHEDULE, move |uuid, _| {
+            let db_client = db_client.clone();
+            let discord_clone = Arc::clone(&discord_mtx);
+            Box::pin(async move {
+                let partners = Partnership::digest_recipients(&db_client)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .unwrap_or_default();
+
+                let until = Utc::now().timestamp();
+                let discord_lock = discord_clone.lock().await;
+
+                for partner_id in partners {
+                    discord_lock
+                        .send_partner_digest(partner_id, until - DAY, until)
+                        .await;
+                }
+
+                info!("triggered cron {:?}", uuid);
+            })
+        })
+        .map_err(|e| error!("{}", e))
+        .ok();
+
+        if let Some(job) = job {
+            let uuid = self
+                .scheduler
+                .add(job)
+                .await
+                .map_err(|e| error!("{}", e))
+                .ok();
+
+            if let Some(uuid) = uuid {
+                info!("registered partner digest cron {:?}", uuid);
+            }
+        }
+    }
+
+    // purged rows are gone for good, restores only work within the retention period
+    pub async fn register_retention_job(&self, retention_days: i64) {
+        info!(
+            "registering nightly retention cron, keeping deleted rows for {} days",
+            retention_days
+        );
+
+        let db_client = self.db_client.clone();
+
+        let job = Job::new_async(RETENTION_SCHEDULE, move |uuid, _| {
+            let db_client = db_client.clone();
+            Box::pin(async move {
+                let before = Utc::now().timestamp() - retention_days * DAY;
+
+                // lists take their tasks with them, the rest were deleted on their own
+                let lists = List::purge(&db_client, before)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .unwrap_or_default();
+                let tasks = Task::purge(&db_client, before)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .unwrap_or_default();
+
+                info!("purged {} deleted lists and {} deleted tasks", lists, tasks);
+                info!("triggered cron {:?}", uuid);
+            })
+        })
+        .map_err(|e| error!("{}", e))
+        .ok();
+
+        if let Some(job) = job {
+            let uuid = self
+                .scheduler
+                .add(job)
+                .await
+                .map_err(|e| error!("{}", e))
+                .ok();
+
+            if let Some(uuid) = uuid {
+                info!("registered retention cron {:?}", uuid);
+            }
+        }
+    }
+
     pub async fn register_all(&self, task_id: Uuid) -> Result<TaskJobs, DatabaseError> {
+        let discord_mtx = Arc::new(Mutex::new(Bot::new().await));
+
+        self.register_task(discord_mtx, task_id).await
+    }
+
+    // like register_all, with one bot shared by the jobs of every task in the
+    // batch
+    pub async fn register_batch(&self, task_ids: &[Uuid]) -> Result<Vec<TaskJobs>, DatabaseError> {
+        let discord_mtx = Arc::new(Mutex::new(Bot::new().await));
+        let mut jobs = Vec::with_capacity(task_ids.len());
+
+        for task_id in task_ids {
+            let task_jobs = self
+                .register_task(Arc::clone(&discord_mtx), *task_id)
+                .await?;
+            jobs.push(task_jobs);
+        }
+
+        Ok(jobs)
+    }
+
+    async fn register_task(
+        &self,
+        discord_mtx: Arc<Mutex<Bot>>,
+        task_id: Uuid,
+    ) -> Result<TaskJobs, DatabaseError> {
         let task = Task::get(&self.db_client, task_id)
             .await
             .map_err(|e| error!("{}", e))
             .ok()
             .unwrap();
 
-        let discord_mtx = Arc::new(Mutex::new(Bot::new().await));
-
         if let Some(task) = task {
             if let Some(pester_interval) = task.pester {
                 // TODO: change this back to hours after testing
@@ -162,9 +357,16 @@ impl Scheduler {
     ) {
         info!("registering pester cron for task {:?}", task_id);
 
+        let db_client = self.db_client.clone();
         let job = Job::new_async(cron_schedule, move |uuid, _| {
+            let db_client = db_client.clone();
             let discord_clone = Arc::clone(&discord_mtx);
             Box::pin(async move {
+                TaskEvent::job_fired(&db_client, task_id, &JobType::Pester)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .ok();
+
                 let discord_lock = discord_clone.lock().await;
 
                 discord_lock.send_pester_message(task_id, guild_id).await;
@@ -203,14 +405,23 @@ impl Scheduler {
     ) {
         info!("registering reminder cron for task {:?}", task_id);
 
+        let db_client = self.db_client.clone();
         let job = Job::new_async(cron_schedule, move |uuid, _| {
+            let db_client = db_client.clone();
             let discord_clone = Arc::clone(&discord_mtx);
             Box::pin(async move {
+                TaskEvent::job_fired(&db_client, task_id, &JobType::Reminder)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .ok();
+
                 let discord_lock = discord_clone.lock().await;
 
                 discord_lock.send_reminder(task_id, guild_id).await;
 
-                discord_lock.send_task(task_id, guild_id).await;
+                discord_lock
+                    .send_task(task_id, guild_id, Some(NotificationKind::Reminder))
+                    .await;
 
                 info!("triggered cron {:?}", uuid);
             })
@@ -246,14 +457,23 @@ impl Scheduler {
     ) {
         info!("registering overdue cron for task {:?}", task_id);
 
+        let db_client = self.db_client.clone();
         let job = Job::new_async(cron_schedule, move |uuid, _| {
+            let db_client = db_client.clone();
             let discord_clone = Arc::clone(&discord_mtx);
             Box::pin(async move {
+                TaskEvent::job_fired(&db_client, task_id, &JobType::Overdue)
+                    .await
+                    .map_err(|e| error!("{}", e))
+                    .ok();
+
                 let discord_lock = discord_clone.lock().await;
 
                 discord_lock.send_overdue_notice(task_id, guild_id).await;
 
-                discord_lock.send_task(task_id, guild_id).await;
+                discord_lock
+                    .send_task(task_id, guild_id, Some(NotificationKind::Overdue))
+                    .await;
 
                 info!("triggered cron {:?}", uuid);
             })
@@ -0,0 +1,24 @@
+use log::warn;
+use std::env;
+
+// purged rows can't be restored, so this errs on the long side
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+#[derive(Clone)]
+pub struct Env {
+    pub retention_days: i64,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        let retention_days = env::var("SHAMEBOT_RETENTION_DAYS")
+            .map_err(|_| warn!("environment variable SHAMEBOT_RETENTION_DAYS not set"))
+            .and_then(|days| {
+                days.parse()
+                    .map_err(|_| warn!("SHAMEBOT_RETENTION_DAYS is not a number of days"))
+            })
+            .unwrap_or(DEFAULT_RETENTION_DAYS);
+
+        Env { retention_days }
+    }
+}
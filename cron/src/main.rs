@@ -9,6 +9,7 @@ use log::warn;
 use utils::logging;
 
 mod cronjob;
+mod environment;
 mod routes;
 
 #[launch]
@@ -33,6 +34,13 @@ async fn rocket() -> _ {
 
     scheduler.resume_jobs().await;
 
+    scheduler.register_leaderboard_job().await;
+
+    scheduler.register_digest_job().await;
+
+    let env = environment::Env::new();
+    scheduler.register_retention_job(env.retention_days).await;
+
     rocket::build()
         .manage(db_client)
         .manage(scheduler)
@@ -42,6 +50,8 @@ async fn rocket() -> _ {
             routes![
                 routes::jobs::get_jobs,
                 routes::jobs::register_jobs,
+                routes::jobs::register_batch,
+                routes::jobs::stop_batch,
                 routes::jobs::delete_jobs
             ],
         )
@@ -46,10 +46,16 @@ pub mod jobs {
 
     impl From<Result<TaskJobs, DatabaseError>> for JobsResponse {
         fn from(value: Result<TaskJobs, DatabaseError>) -> Self {
+            JobsResponse::from(value.map(|v| vec![v]))
+        }
+    }
+
+    impl From<Result<Vec<TaskJobs>, DatabaseError>> for JobsResponse {
+        fn from(value: Result<Vec<TaskJobs>, DatabaseError>) -> Self {
             match value {
-                Ok(v) => JobsResponse {
+                Ok(data) => JobsResponse {
                     status: 200,
-                    data: vec![v],
+                    data,
                     error: None,
                 },
                 Err(e) => {
@@ -88,6 +94,30 @@ pub mod jobs {
         (Status::from_code(resp.status).unwrap(), Json(resp))
     }
 
+    // registers the jobs of several tasks at once, in the order they're sent
+    #[post("/", format = "json", data = "<task_ids>")]
+    pub async fn register_batch(
+        scheduler: &State<Scheduler>,
+        task_ids: Json<Vec<Uuid>>,
+    ) -> (Status, Json<JobsResponse>) {
+        let jobs = scheduler.register_batch(&task_ids).await;
+        let resp = JobsResponse::from(jobs);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
+    // stops the jobs of several tasks at once, once they're deleted
+    #[delete("/", format = "json", data = "<task_ids>")]
+    pub async fn stop_batch(
+        scheduler: &State<Scheduler>,
+        task_ids: Json<Vec<Uuid>>,
+    ) -> (Status, Json<JobsResponse>) {
+        let jobs = scheduler.stop_batch(&task_ids).await;
+        let resp = JobsResponse::from(jobs);
+
+        (Status::from_code(resp.status).unwrap(), Json(resp))
+    }
+
     #[delete("/<task_id>")]
     pub async fn delete_jobs(
         scheduler: &State<Scheduler>,
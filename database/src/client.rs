@@ -1,6 +1,6 @@
-use std::{str::FromStr, sync::Arc, time::Duration};
+use std::{ops::Deref, str::FromStr, sync::Arc, time::Duration};
 
-use log::error;
+use log::{error, warn};
 use mobc::Pool as MobcPool;
 use mobc_postgres::{
     tokio_postgres::{types::ToSql, Config, NoTls, Row, ToStatement},
@@ -49,16 +49,43 @@ impl Pool {
     }
 }
 
-#[derive(Clone)]
 pub struct Client {
     pool: Pool,
+    // only set on the client of a Transaction, every query then goes through
+    // this connection instead of the pool
+    conn: Option<DatabaseConnection>,
+}
+
+// clones always get their connections from the pool, so a transaction is
+// never shared and can't outlive its Transaction
+impl Clone for Client {
+    fn clone(&self) -> Self {
+        Client {
+            pool: self.pool.clone(),
+            conn: None,
+        }
+    }
 }
 
 impl Client {
     pub async fn new() -> Self {
         let pool = Pool::new().await;
 
-        Client { pool }
+        Client { pool, conn: None }
+    }
+
+    // transactions don't nest, this always starts a new one on its own
+    // connection
+    pub async fn transaction(&self) -> Result<Transaction, DatabaseError> {
+        let conn = self.pool.connection().await?;
+        conn.batch_execute("BEGIN").await?;
+
+        Ok(Transaction {
+            client: Client {
+                pool: self.pool.clone(),
+                conn: Some(conn),
+            },
+        })
     }
 
     // db is considered healthy if:
@@ -69,12 +96,25 @@ impl Client {
         let query = "SELECT
             'job'::regclass,
             'guilds'::regclass,
+            'guild_channels'::regclass,
+            'message_templates'::regclass,
             'users'::regclass,
             'user_guild'::regclass,
+            'delivery_preferences'::regclass,
             'proof'::regclass,
             'lists'::regclass,
+            'list_members'::regclass,
+            'list_templates'::regclass,
+            'template_tasks'::regclass,
             'tasks'::regclass,
-            'accountability_requests'::regclass";
+            'task_items'::regclass,
+            'task_dependencies'::regclass,
+            'task_events'::regclass,
+            'accountability_requests'::regclass,
+            'partnerships'::regclass,
+            'proof_approvals'::regclass,
+            'pending_changes'::regclass,
+            'points_ledger'::regclass";
         self.query_one(query, &[])
             .await
             .map_err(|e| error!("{:?}", e))
@@ -141,11 +181,12 @@ impl Client {
     where
         T: ?Sized + ToStatement,
     {
-        let conn = self.pool.connection().await?;
+        let result = match &self.conn {
+            Some(conn) => conn.query_one(query, params).await,
+            None => self.pool.connection().await?.query_one(query, params).await,
+        };
 
-        conn.query_one(query, params)
-            .await
-            .map_err(DatabaseError::DBQueryError)
+        result.map_err(DatabaseError::DBQueryError)
     }
 
     pub async fn query_opt<T>(
@@ -156,11 +197,12 @@ impl Client {
     where
         T: ?Sized + ToStatement,
     {
-        let conn = self.pool.connection().await?;
+        let result = match &self.conn {
+            Some(conn) => conn.query_opt(query, params).await,
+            None => self.pool.connection().await?.query_opt(query, params).await,
+        };
 
-        conn.query_opt(query, params)
-            .await
-            .map_err(DatabaseError::DBQueryError)
+        result.map_err(DatabaseError::DBQueryError)
     }
 
     pub async fn query<T>(
@@ -171,10 +213,62 @@ impl Client {
     where
         T: ?Sized + ToStatement,
     {
-        let conn = self.pool.connection().await?;
+        let result = match &self.conn {
+            Some(conn) => conn.query(query, params).await,
+            None => self.pool.connection().await?.query(query, params).await,
+        };
 
-        conn.query(query, params)
-            .await
-            .map_err(DatabaseError::DBQueryError)
+        result.map_err(DatabaseError::DBQueryError)
+    }
+}
+
+// a client whose queries all run in one transaction, the models take it like
+// any other client through deref. if it's dropped without commit or
+// rollback, on a panic or an early return, the connection is taken out of
+// the pool and closed, which makes postgres roll the transaction back
+pub struct Transaction {
+    client: Client,
+}
+
+impl Transaction {
+    pub async fn commit(mut self) -> Result<(), DatabaseError> {
+        self.finish("COMMIT").await
+    }
+
+    pub async fn rollback(mut self) -> Result<(), DatabaseError> {
+        self.finish("ROLLBACK").await
+    }
+
+    // a connection that failed to finish the transaction doesn't go back to
+    // the pool either
+    async fn finish(&mut self, statement: &str) -> Result<(), DatabaseError> {
+        let conn = match self.client.conn.take() {
+            Some(conn) => conn,
+            None => return Ok(()),
+        };
+        let finished = conn.batch_execute(statement).await;
+
+        if finished.is_err() {
+            drop(conn.into_inner());
+        }
+
+        finished.map_err(DatabaseError::DBQueryError)
+    }
+}
+
+impl Deref for Transaction {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if let Some(conn) = self.client.conn.take() {
+            warn!("transaction dropped without commit or rollback, closing its connection");
+            drop(conn.into_inner());
+        }
     }
 }
@@ -5,11 +5,12 @@ use mobc_postgres::tokio_postgres::{NoTls, Row};
 use mobc_postgres::{tokio_postgres, PgConnectionManager};
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 use thiserror::Error;
 use tokio::task::JoinError;
 use uuid::Uuid;
 
-pub use crate::client::Client;
+pub use crate::client::{Client, Transaction};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Guild {
@@ -17,6 +18,13 @@ pub struct Guild {
     pub name: String,
     pub icon: Option<String>,
     pub send_to: Option<i64>,
+    pub shame_role: Option<i64>,
+    pub shame_prefix: Option<String>,
+    #[serde(default)]
+    pub task_threads: bool,
+    #[serde(default)]
+    pub personality: Personality,
+    pub locale: Option<String>,
 }
 
 impl Guild {
@@ -43,18 +51,48 @@ impl Guild {
         }
     }
 
-    pub async fn get_users(db_client: &Client, guild_id: i64) -> Result<Vec<User>, DatabaseError> {
-        let query =
-            "SELECT * FROM users WHERE id IN (SELECT user_id FROM user_guild WHERE guild_id = $1)";
-        let result = db_client.query(query, &[&guild_id]).await?;
+    pub async fn get_all(db_client: &Client) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM guilds";
+        let result = db_client.query(query, &[]).await?;
 
-        let mut users: Vec<User> = Vec::new();
+        let mut guilds: Vec<Guild> = Vec::new();
 
-        for u in result {
-            users.push(u.into())
+        for row in result {
+            guilds.push(row.into())
         }
 
-        Ok(users)
+        Ok(guilds)
+    }
+
+    pub async fn get_users(
+        db_client: &Client,
+        guild_id: i64,
+        filter: &UserFilter,
+        cursor: Option<&Cursor>,
+        limit: i64,
+    ) -> Result<Page<User>, DatabaseError> {
+        let (key, key_type) = filter.sort.key();
+        let (direction, comparison) = direction(filter.descending);
+        let query = format!(
+            "SELECT *, ({0})::text AS sort_key, id::text AS page_id FROM users
+            WHERE {1}
+                AND ($3::text IS NULL OR ({0}, id) {2} ($3::text::{3}, $4::text::bigint))
+            ORDER BY {0} {4}, id {4}
+            LIMIT $5",
+            key, USER_FILTER, comparison, key_type, direction
+        );
+        let count_query = format!("SELECT COUNT(*) AS total FROM users WHERE {}", USER_FILTER);
+        let (after, after_id) = Cursor::split(cursor);
+
+        fetch_page(
+            db_client,
+            &query,
+            &[&guild_id, &filter.username, &after, &after_id, &(limit + 1)],
+            &count_query,
+            &[&guild_id, &filter.username],
+            limit,
+        )
+        .await
     }
 
     pub async fn update_channel(
@@ -68,6 +106,69 @@ impl Guild {
         Ok(Some(()))
     }
 
+    pub async fn update_shame(
+        db_client: &Client,
+        guild_id: i64,
+        shame_role: Option<i64>,
+        shame_prefix: Option<String>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let query = "UPDATE guilds SET shame_role = $1, shame_prefix = $2 WHERE id = $3";
+        db_client
+            .query_opt(query, &[&shame_role, &shame_prefix, &guild_id])
+            .await?;
+
+        Ok(Some(()))
+    }
+
+    pub async fn update_threads(
+        db_client: &Client,
+        guild_id: i64,
+        task_threads: bool,
+    ) -> Result<Option<()>, DatabaseError> {
+        let query = "UPDATE guilds SET task_threads = $1 WHERE id = $2";
+        db_client
+            .query_opt(query, &[&task_threads, &guild_id])
+            .await?;
+
+        Ok(Some(()))
+    }
+
+    pub async fn update_personality(
+        db_client: &Client,
+        guild_id: i64,
+        personality: Personality,
+    ) -> Result<Option<()>, DatabaseError> {
+        let query = "UPDATE guilds SET personality = $1 WHERE id = $2";
+        db_client
+            .query_opt(query, &[&personality, &guild_id])
+            .await?;
+
+        Ok(Some(()))
+    }
+
+    pub async fn update_locale(
+        db_client: &Client,
+        guild_id: i64,
+        locale: Option<String>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let query = "UPDATE guilds SET locale = $1 WHERE id = $2";
+        db_client.query_opt(query, &[&locale, &guild_id]).await?;
+
+        Ok(Some(()))
+    }
+
+    // only fills in the locale if nobody has picked one yet
+    pub async fn default_locale(
+        db_client: &Client,
+        guild_id: i64,
+        locale: String,
+    ) -> Result<(), DatabaseError> {
+        let query = "UPDATE guilds SET locale = $1 WHERE id = $2 AND locale IS NULL";
+        db_client.query_opt(query, &[&locale, &guild_id]).await?;
+
+        Ok(())
+    }
+
     pub async fn delete(db_client: &Client, id: i64) -> Result<(), DatabaseError> {
         let query = "DELETE FROM guilds WHERE id = $1";
         db_client.query_opt(query, &[&id]).await?;
@@ -103,12 +204,212 @@ impl From<Row> for Guild {
         let name = row.get("name");
         let icon = row.get("icon");
         let send_to = row.get("send_to");
+        let shame_role = row.get("shame_role");
+        let shame_prefix = row.get("shame_prefix");
+        let task_threads = row.get("task_threads");
+        let personality = row.get("personality");
+        let locale = row.get("locale");
 
         Guild {
             id,
             name,
             icon,
             send_to,
+            shame_role,
+            shame_prefix,
+            task_threads,
+            personality,
+            locale,
+        }
+    }
+}
+
+// built-in sets of message templates, used for anything a guild hasn't
+// written its own template for
+#[derive(Debug, Clone, Copy, Default, ToSql, FromSql, PartialEq, Serialize, Deserialize)]
+#[postgres(name = "personality")]
+#[serde(rename_all = "snake_case")]
+pub enum Personality {
+    #[default]
+    #[postgres(name = "default")]
+    Default,
+    #[postgres(name = "gentle_coach")]
+    GentleCoach,
+    #[postgres(name = "drill_sergeant")]
+    DrillSergeant,
+    #[postgres(name = "passive_aggressive")]
+    PassiveAggressive,
+}
+
+#[derive(Debug, Clone, Copy, ToSql, FromSql, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[postgres(name = "notification_kind")]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    #[postgres(name = "reminder")]
+    Reminder,
+    #[postgres(name = "pester")]
+    Pester,
+    #[postgres(name = "overdue")]
+    Overdue,
+    #[postgres(name = "completion")]
+    Completion,
+    #[postgres(name = "leaderboard")]
+    Leaderboard,
+    #[postgres(name = "accountability")]
+    Accountability,
+}
+
+// a guild without an override for a kind falls back to its send_to channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationChannel {
+    #[serde(default)]
+    pub guild_id: i64,
+    pub kind: NotificationKind,
+    pub channel_id: Option<i64>,
+}
+
+impl NotificationChannel {
+    pub async fn get_all(db_client: &Client, guild_id: i64) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM guild_channels WHERE guild_id = $1";
+        let result = db_client.query(query, &[&guild_id]).await?;
+
+        let mut channels: Vec<NotificationChannel> = Vec::new();
+
+        for row in result {
+            channels.push(row.into())
+        }
+
+        Ok(channels)
+    }
+
+    pub async fn resolve(
+        db_client: &Client,
+        guild: &Guild,
+        kind: NotificationKind,
+    ) -> Result<Option<i64>, DatabaseError> {
+        let query = "SELECT channel_id FROM guild_channels WHERE guild_id = $1 AND kind = $2";
+        let result = db_client.query_opt(query, &[&guild.id, &kind]).await?;
+
+        if let Some(row) = result {
+            Ok(Some(row.get("channel_id")))
+        } else {
+            Ok(guild.send_to)
+        }
+    }
+
+    // a missing channel_id removes the override
+    pub async fn set(
+        db_client: &Client,
+        channel: NotificationChannel,
+    ) -> Result<Option<()>, DatabaseError> {
+        if let Some(channel_id) = channel.channel_id {
+            let query = "INSERT INTO
+                guild_channels (guild_id, kind, channel_id)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (guild_id, kind) DO UPDATE
+                SET channel_id = EXCLUDED.channel_id";
+            db_client
+                .query_opt(query, &[&channel.guild_id, &channel.kind, &channel_id])
+                .await?;
+        } else {
+            let query = "DELETE FROM guild_channels WHERE guild_id = $1 AND kind = $2";
+            db_client
+                .query_opt(query, &[&channel.guild_id, &channel.kind])
+                .await?;
+        }
+
+        Ok(Some(()))
+    }
+}
+
+impl From<Row> for NotificationChannel {
+    fn from(row: Row) -> Self {
+        let guild_id = row.get("guild_id");
+        let kind = row.get("kind");
+        let channel_id = row.get("channel_id");
+
+        NotificationChannel {
+            guild_id,
+            kind,
+            channel_id,
+        }
+    }
+}
+
+// a guild's own wording for one kind of message, see the discord crate for
+// the placeholders it can contain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTemplate {
+    #[serde(default)]
+    pub guild_id: i64,
+    pub kind: NotificationKind,
+    pub template: Option<String>,
+}
+
+impl MessageTemplate {
+    pub async fn get(
+        db_client: &Client,
+        guild_id: i64,
+        kind: NotificationKind,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM message_templates WHERE guild_id = $1 AND kind = $2";
+        let template = db_client.query_opt(query, &[&guild_id, &kind]).await?;
+
+        if let Some(template) = template {
+            Ok(Some(template.into()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn get_all(db_client: &Client, guild_id: i64) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM message_templates WHERE guild_id = $1";
+        let result = db_client.query(query, &[&guild_id]).await?;
+
+        let mut templates: Vec<MessageTemplate> = Vec::new();
+
+        for row in result {
+            templates.push(row.into())
+        }
+
+        Ok(templates)
+    }
+
+    // a missing template goes back to the guild's personality preset
+    pub async fn set(
+        db_client: &Client,
+        template: MessageTemplate,
+    ) -> Result<Option<()>, DatabaseError> {
+        if let Some(text) = template.template {
+            let query = "INSERT INTO
+                message_templates (guild_id, kind, template)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (guild_id, kind) DO UPDATE
+                SET template = EXCLUDED.template";
+            db_client
+                .query_opt(query, &[&template.guild_id, &template.kind, &text])
+                .await?;
+        } else {
+            let query = "DELETE FROM message_templates WHERE guild_id = $1 AND kind = $2";
+            db_client
+                .query_opt(query, &[&template.guild_id, &template.kind])
+                .await?;
+        }
+
+        Ok(Some(()))
+    }
+}
+
+impl From<Row> for MessageTemplate {
+    fn from(row: Row) -> Self {
+        let guild_id = row.get("guild_id");
+        let kind = row.get("kind");
+        let template = row.get("template");
+
+        MessageTemplate {
+            guild_id,
+            kind,
+            template,
         }
     }
 }
@@ -119,6 +420,7 @@ pub struct User {
     pub username: String,
     pub discriminator: String,
     pub avatar_hash: String,
+    pub locale: Option<String>,
 }
 
 impl User {
@@ -189,6 +491,29 @@ impl User {
         }
     }
 
+    pub async fn update_locale(
+        db_client: &Client,
+        user_id: i64,
+        locale: Option<String>,
+    ) -> Result<Option<()>, DatabaseError> {
+        let query = "UPDATE users SET locale = $1 WHERE id = $2";
+        db_client.query_opt(query, &[&locale, &user_id]).await?;
+
+        Ok(Some(()))
+    }
+
+    // only fills in the locale if the user hasn't picked one yet
+    pub async fn default_locale(
+        db_client: &Client,
+        user_id: i64,
+        locale: String,
+    ) -> Result<(), DatabaseError> {
+        let query = "UPDATE users SET locale = $1 WHERE id = $2 AND locale IS NULL";
+        db_client.query_opt(query, &[&locale, &user_id]).await?;
+
+        Ok(())
+    }
+
     pub async fn delete(db_client: &Client, id: i64) -> Result<(), DatabaseError> {
         let query = "DELETE FROM users WHERE id = $1";
         db_client.query_opt(query, &[&id]).await?;
@@ -224,12 +549,105 @@ impl From<Row> for User {
         let username = row.get("username");
         let discriminator = row.get("discriminator");
         let avatar_hash = row.get("avatar_hash");
+        let locale = row.get("locale");
 
         User {
             id,
             username,
             discriminator,
             avatar_hash,
+            locale,
+        }
+    }
+}
+
+// where a user wants a kind of message about their tasks to go
+#[derive(Debug, Clone, Copy, Default, ToSql, FromSql, PartialEq, Serialize, Deserialize)]
+#[postgres(name = "delivery")]
+#[serde(rename_all = "lowercase")]
+pub enum Delivery {
+    #[default]
+    #[postgres(name = "channel")]
+    Channel,
+    #[postgres(name = "dm")]
+    Dm,
+    #[postgres(name = "both")]
+    Both,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryPreference {
+    #[serde(default)]
+    pub user_id: i64,
+    pub kind: NotificationKind,
+    pub delivery: Option<Delivery>,
+}
+
+impl DeliveryPreference {
+    pub async fn get_all(db_client: &Client, user_id: i64) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM delivery_preferences WHERE user_id = $1";
+        let result = db_client.query(query, &[&user_id]).await?;
+
+        let mut preferences: Vec<DeliveryPreference> = Vec::new();
+
+        for row in result {
+            preferences.push(row.into())
+        }
+
+        Ok(preferences)
+    }
+
+    // users without a preference for the kind get it in the guild channel
+    pub async fn resolve(
+        db_client: &Client,
+        user_id: i64,
+        kind: NotificationKind,
+    ) -> Result<Delivery, DatabaseError> {
+        let query = "SELECT delivery FROM delivery_preferences WHERE user_id = $1 AND kind = $2";
+        let result = db_client.query_opt(query, &[&user_id, &kind]).await?;
+
+        if let Some(row) = result {
+            Ok(row.get("delivery"))
+        } else {
+            Ok(Delivery::default())
+        }
+    }
+
+    // a missing delivery removes the preference
+    pub async fn set(
+        db_client: &Client,
+        preference: DeliveryPreference,
+    ) -> Result<Option<()>, DatabaseError> {
+        if let Some(delivery) = preference.delivery {
+            let query = "INSERT INTO
+                delivery_preferences (user_id, kind, delivery)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, kind) DO UPDATE
+                SET delivery = EXCLUDED.delivery";
+            db_client
+                .query_opt(query, &[&preference.user_id, &preference.kind, &delivery])
+                .await?;
+        } else {
+            let query = "DELETE FROM delivery_preferences WHERE user_id = $1 AND kind = $2";
+            db_client
+                .query_opt(query, &[&preference.user_id, &preference.kind])
+                .await?;
+        }
+
+        Ok(Some(()))
+    }
+}
+
+impl From<Row> for DeliveryPreference {
+    fn from(row: Row) -> Self {
+        let user_id = row.get("user_id");
+        let kind = row.get("kind");
+        let delivery = row.get("delivery");
+
+        DeliveryPreference {
+            user_id,
+            kind,
+            delivery,
         }
     }
 }
@@ -239,6 +657,9 @@ pub struct List {
     pub id: Uuid,
     pub title: String,
     pub user_id: i64,
+    // both are set through archive and delete, whatever is sent is ignored
+    pub archived_at: Option<i64>,
+    pub deleted_at: Option<i64>,
 }
 
 impl List {
@@ -253,7 +674,7 @@ impl List {
     }
 
     pub async fn get(db_client: &Client, id: Uuid) -> Result<Option<Self>, DatabaseError> {
-        let query = "SELECT * FROM lists WHERE id = $1";
+        let query = "SELECT * FROM lists WHERE id = $1 AND deleted_at IS NULL";
         let list = db_client.query_opt(query, &[&id]).await?;
 
         if let Some(l) = list {
@@ -263,17 +684,159 @@ impl List {
         }
     }
 
-    pub async fn delete(db_client: &Client, id: Uuid) -> Result<(), DatabaseError> {
-        let query = "DELETE FROM lists WHERE id = $1";
-        db_client.query_opt(query, &[&id]).await?;
+    pub async fn get_by_title(
+        db_client: &Client,
+        user_id: i64,
+        title: &str,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM lists
+            WHERE user_id = $1 AND lower(title) = lower($2) AND deleted_at IS NULL
+            LIMIT 1";
+        let list = db_client.query_opt(query, &[&user_id, &title]).await?;
+
+        if let Some(l) = list {
+            Ok(Some(l.into()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // soft deletes the list and its tasks with the same timestamp, so that
+    // restoring the list brings back exactly those tasks. each of them gets
+    // a deleted event naming the list
+    pub async fn delete(
+        db_client: &Client,
+        id: Uuid,
+        actor: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        let query = "WITH
+            deleted AS (
+                UPDATE lists SET deleted_at = extract(epoch from now())::bigint
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING id, deleted_at
+            ),
+            deleted_tasks AS (
+                UPDATE tasks SET deleted_at = deleted.deleted_at
+                FROM deleted
+                WHERE tasks.list_id = deleted.id AND tasks.deleted_at IS NULL
+                RETURNING tasks.id
+            )
+            INSERT INTO task_events (task_id, kind, actor, details)
+            SELECT id, 'deleted', $2, jsonb_build_object('list_id', $1::uuid)
+            FROM deleted_tasks";
+        db_client.query(query, &[&id, &actor]).await?;
 
         Ok(())
     }
 
-    pub async fn get_tasks(db_client: &Client, id: Uuid) -> Result<Vec<Task>, DatabaseError> {
-        let query = "SELECT * FROM tasks WHERE list_id = $1";
+    // None if the list isn't deleted, tasks deleted on their own before the
+    // list stay deleted
+    pub async fn restore(
+        db_client: &Client,
+        id: Uuid,
+        actor: Option<i64>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "WITH
+            previous AS (SELECT deleted_at FROM lists WHERE id = $1),
+            restored AS (
+                UPDATE lists SET deleted_at = NULL
+                WHERE id = $1 AND deleted_at IS NOT NULL
+                RETURNING *
+            ),
+            restored_tasks AS (
+                UPDATE tasks SET deleted_at = NULL
+                FROM previous, restored
+                WHERE tasks.list_id = restored.id AND tasks.deleted_at = previous.deleted_at
+                RETURNING tasks.id
+            ),
+            events AS (
+                INSERT INTO task_events (task_id, kind, actor, details)
+                SELECT id, 'restored', $2, jsonb_build_object('list_id', $1::uuid)
+                FROM restored_tasks
+            )
+            SELECT * FROM restored";
+        let list = db_client.query_opt(query, &[&id, &actor]).await?;
+
+        Ok(list.map(List::from))
+    }
+
+    // archived lists are left out of get_for_user unless asked for. when
+    // that changes, each of its tasks gets an event naming the list
+    pub async fn archive(
+        db_client: &Client,
+        id: Uuid,
+        archived: bool,
+        actor: Option<i64>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "WITH
+            previous AS (SELECT archived_at FROM lists WHERE id = $1),
+            updated AS (
+                UPDATE lists
+                SET archived_at = CASE
+                    WHEN NOT $2 THEN NULL
+                    ELSE COALESCE(archived_at, extract(epoch from now())::bigint)
+                END
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING *
+            ),
+            events AS (
+                INSERT INTO task_events (task_id, kind, actor, details)
+                SELECT
+                    tasks.id,
+                    CASE WHEN $2 THEN 'archived' ELSE 'unarchived' END::task_event_kind,
+                    $3,
+                    jsonb_build_object('list_id', $1::uuid)
+                FROM tasks, updated, previous
+                WHERE tasks.list_id = updated.id
+                    AND tasks.deleted_at IS NULL
+                    AND (previous.archived_at IS NULL) = $2
+            )
+            SELECT * FROM updated";
+        let list = db_client
+            .query_opt(query, &[&id, &archived, &actor])
+            .await?;
+
+        Ok(list.map(List::from))
+    }
+
+    // hard deletes lists that were soft deleted before `before`, along with
+    // their tasks. returns how many lists went away
+    pub async fn purge(db_client: &Client, before: i64) -> Result<usize, DatabaseError> {
+        let query = "DELETE FROM lists WHERE deleted_at < $1 RETURNING id";
+        let purged = db_client.query(query, &[&before]).await?;
+
+        Ok(purged.len())
+    }
+
+    pub async fn get_tasks(
+        db_client: &Client,
+        id: Uuid,
+        filter: &TaskFilter,
+    ) -> Result<Vec<Task>, DatabaseError> {
+        let (key, _) = filter.sort.key(filter.descending);
+        let (direction, _) = direction(filter.descending);
+        let query = format!(
+            "SELECT * FROM tasks
+            WHERE list_id = $1 AND {0}
+            ORDER BY {1} {2}, id {2}",
+            TASK_FILTER, key, direction
+        );
+        let tag = filter.tag.as_ref().map(|tag| tag.trim().to_lowercase());
         let mut tasks: Vec<Task> = Vec::new();
-        let result = db_client.query(query, &[&id]).await?;
+        let result = db_client
+            .query(
+                query.as_str(),
+                &[
+                    &id,
+                    &filter.priority,
+                    &tag,
+                    &filter.checked,
+                    &filter.due_after,
+                    &filter.due_before,
+                    &filter.archived,
+                ],
+            )
+            .await?;
 
         for row in result {
             tasks.push(row.into())
@@ -282,6 +845,86 @@ impl List {
         Ok(tasks)
     }
 
+    // same as get_tasks but a page at a time, starting after the cursor
+    pub async fn get_tasks_page(
+        db_client: &Client,
+        id: Uuid,
+        filter: &TaskFilter,
+        cursor: Option<&Cursor>,
+        limit: i64,
+    ) -> Result<Page<Task>, DatabaseError> {
+        Task::get_page(db_client, "list_id", &id, filter, cursor, limit).await
+    }
+
+    // a cursor of a page from get_for_user
+    pub fn cursor(cursor: &str) -> Option<Cursor> {
+        Cursor::parse(cursor, "text", "uuid")
+    }
+
+    // the user's own lists and the ones shared with them, archived ones only
+    // if asked for
+    pub async fn get_for_user(
+        db_client: &Client,
+        user_id: i64,
+        archived: bool,
+        cursor: Option<&Cursor>,
+        limit: i64,
+    ) -> Result<Page<Self>, DatabaseError> {
+        let query = "SELECT *, lower(title) AS sort_key, id::text AS page_id FROM lists
+            WHERE (user_id = $1 OR id IN (SELECT list_id FROM list_members WHERE user_id = $1))
+                AND deleted_at IS NULL
+                AND ($2 OR archived_at IS NULL)
+                AND ($3::text IS NULL OR (lower(title), id) > ($3, $4::text::uuid))
+            ORDER BY lower(title), id
+            LIMIT $5";
+        let count_query = "SELECT COUNT(*) AS total FROM lists
+            WHERE (user_id = $1 OR id IN (SELECT list_id FROM list_members WHERE user_id = $1))
+                AND deleted_at IS NULL
+                AND ($2 OR archived_at IS NULL)";
+        let (after, after_id) = Cursor::split(cursor);
+
+        fetch_page(
+            db_client,
+            query,
+            &[&user_id, &archived, &after, &after_id, &(limit + 1)],
+            count_query,
+            &[&user_id, &archived],
+            limit,
+        )
+        .await
+    }
+
+    // used for autocompletion, lists without any tasks yet are included since
+    // they don't belong to any particular guild
+    pub async fn search_titles(
+        db_client: &Client,
+        user_id: i64,
+        guild_id: i64,
+        prefix: &str,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM lists
+            WHERE user_id = $1
+                AND deleted_at IS NULL
+                AND archived_at IS NULL
+                AND starts_with(lower(title), lower($3))
+                AND (
+                    EXISTS (SELECT 1 FROM tasks WHERE list_id = lists.id AND guild_id = $2) OR
+                    NOT EXISTS (SELECT 1 FROM tasks WHERE list_id = lists.id)
+                )
+            ORDER BY title
+            LIMIT 25";
+        let mut lists: Vec<List> = Vec::new();
+        let result = db_client
+            .query(query, &[&user_id, &guild_id, &prefix])
+            .await?;
+
+        for row in result {
+            lists.push(row.into())
+        }
+
+        Ok(lists)
+    }
+
     async fn insert(db_client: &Client, title: String, user_id: i64) -> Result<Row, DatabaseError> {
         let query = "INSERT INTO 
             lists (title, user_id)
@@ -297,9 +940,422 @@ impl From<Row> for List {
         let title = row.get("title");
         let user_id = row.get("user_id");
 
-        List { id, title, user_id }
-    }
-}
+        let archived_at = row.get("archived_at");
+        let deleted_at = row.get("deleted_at");
+
+        List {
+            id,
+            title,
+            user_id,
+            archived_at,
+            deleted_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ToSql, FromSql, PartialEq, Eq, Serialize, Deserialize)]
+#[postgres(name = "list_role")]
+#[serde(rename_all = "lowercase")]
+pub enum ListRole {
+    #[postgres(name = "owner")]
+    Owner,
+    #[postgres(name = "editor")]
+    Editor,
+    #[postgres(name = "viewer")]
+    Viewer,
+}
+
+impl ListRole {
+    // viewers can only look at a list, and at the tasks assigned to them
+    pub fn can_edit(&self) -> bool {
+        *self != ListRole::Viewer
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListMember {
+    pub list_id: Uuid,
+    pub user_id: i64,
+    pub role: ListRole,
+    pub added_at: i64,
+}
+
+impl ListMember {
+    // adds the user to the list, or changes their role if they're already in
+    pub async fn set(
+        db_client: &Client,
+        list_id: Uuid,
+        user_id: i64,
+        role: ListRole,
+    ) -> Result<Self, DatabaseError> {
+        let query = "INSERT INTO
+            list_members (list_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (list_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            RETURNING *";
+        let row = db_client
+            .query_one(query, &[&list_id, &user_id, &role])
+            .await?;
+
+        Ok(row.into())
+    }
+
+    // doesn't include the list's creator, see List.user_id
+    pub async fn get_for_list(
+        db_client: &Client,
+        list_id: Uuid,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM list_members WHERE list_id = $1 ORDER BY added_at, user_id";
+        let mut members: Vec<ListMember> = Vec::new();
+        let result = db_client.query(query, &[&list_id]).await?;
+
+        for row in result {
+            members.push(row.into())
+        }
+
+        Ok(members)
+    }
+
+    // None if the user has nothing to do with the list
+    pub async fn role(
+        db_client: &Client,
+        list_id: Uuid,
+        user_id: i64,
+    ) -> Result<Option<ListRole>, DatabaseError> {
+        ListMember::find_role(db_client, list_id, user_id, false).await
+    }
+
+    // the role the user had on a list that's deleted, which is what
+    // restoring it is checked against
+    pub async fn deleted_role(
+        db_client: &Client,
+        list_id: Uuid,
+        user_id: i64,
+    ) -> Result<Option<ListRole>, DatabaseError> {
+        ListMember::find_role(db_client, list_id, user_id, true).await
+    }
+
+    async fn find_role(
+        db_client: &Client,
+        list_id: Uuid,
+        user_id: i64,
+        deleted: bool,
+    ) -> Result<Option<ListRole>, DatabaseError> {
+        let query = "SELECT
+                CASE WHEN lists.user_id = $2 THEN 'owner' ELSE list_members.role END AS role
+            FROM lists
+            LEFT JOIN list_members ON list_members.list_id = lists.id AND list_members.user_id = $2
+            WHERE lists.id = $1 AND (lists.deleted_at IS NOT NULL) = $3";
+        let row = db_client
+            .query_opt(query, &[&list_id, &user_id, &deleted])
+            .await?;
+
+        Ok(row.and_then(|row| row.get("role")))
+    }
+
+    // a list's tasks can go to its creator, or to a member who's also in
+    // the task's guild
+    pub async fn assignable(
+        db_client: &Client,
+        list_id: Uuid,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<bool, DatabaseError> {
+        let query = "SELECT
+                EXISTS (SELECT 1 FROM lists WHERE id = $1 AND user_id = $3) OR (
+                    EXISTS (SELECT 1 FROM list_members WHERE list_id = $1 AND user_id = $3) AND
+                    EXISTS (SELECT 1 FROM user_guild WHERE guild_id = $2 AND user_id = $3)
+                ) AS assignable";
+        let row = db_client
+            .query_one(query, &[&list_id, &guild_id, &user_id])
+            .await?;
+
+        Ok(row.get("assignable"))
+    }
+
+    // tasks that were assigned to the member go back to the list's creator
+    pub async fn delete(
+        db_client: &Client,
+        list_id: Uuid,
+        user_id: i64,
+    ) -> Result<(), DatabaseError> {
+        let query = "WITH removed AS (
+                DELETE FROM list_members WHERE list_id = $1 AND user_id = $2
+                RETURNING list_id, user_id
+            )
+            UPDATE tasks SET user_id = lists.user_id
+            FROM removed, lists
+            WHERE tasks.list_id = removed.list_id
+                AND tasks.user_id = removed.user_id
+                AND lists.id = removed.list_id";
+        db_client.query(query, &[&list_id, &user_id]).await?;
+
+        Ok(())
+    }
+}
+
+impl From<Row> for ListMember {
+    fn from(row: Row) -> Self {
+        let list_id = row.get("list_id");
+        let user_id = row.get("user_id");
+        let role = row.get("role");
+        let added_at = row.get("added_at");
+
+        ListMember {
+            list_id,
+            user_id,
+            role,
+            added_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListTemplate {
+    pub id: Uuid,
+    pub title: String,
+    pub user_id: i64,
+    pub pester: Option<i16>,
+    pub created_at: i64,
+    #[serde(default)]
+    pub tasks: Vec<TemplateTask>,
+}
+
+impl ListTemplate {
+    pub async fn new(
+        db_client: &Client,
+        title: String,
+        user_id: i64,
+        pester: Option<i16>,
+        tasks: Vec<TemplateTask>,
+    ) -> Result<Self, DatabaseError> {
+        let titles: Vec<String> = tasks.iter().map(|task| task.title.clone()).collect();
+        let contents: Vec<Option<String>> = tasks.iter().map(|task| task.content.clone()).collect();
+        let offsets: Vec<Option<i64>> = tasks.iter().map(|task| task.due_offset).collect();
+        let pesters: Vec<Option<i16>> = tasks.iter().map(|task| task.pester).collect();
+        let query = "WITH
+            new_template AS (
+                INSERT INTO list_templates (title, user_id, pester)
+                VALUES ($1, $2, $3)
+                RETURNING *
+            ),
+            new_tasks AS (
+                INSERT INTO template_tasks (template_id, title, content, due_offset, pester, position)
+                SELECT new_template.id, t.title, t.content, t.due_offset, t.pester, t.position
+                FROM new_template,
+                    unnest($4::text[], $5::text[], $6::bigint[], $7::smallint[])
+                    WITH ORDINALITY AS t (title, content, due_offset, pester, position)
+            )
+            SELECT * FROM new_template";
+        let row = db_client
+            .query_one(
+                query,
+                &[
+                    &title, &user_id, &pester, &titles, &contents, &offsets, &pesters,
+                ],
+            )
+            .await?;
+        let mut template: ListTemplate = row.into();
+        template.tasks = tasks;
+
+        Ok(template)
+    }
+
+    pub async fn get(db_client: &Client, id: Uuid) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM list_templates WHERE id = $1";
+        let template = db_client.query_opt(query, &[&id]).await?;
+
+        match template {
+            Some(row) => {
+                let mut template: ListTemplate = row.into();
+                template.tasks = TemplateTask::get_for_template(db_client, id).await?;
+
+                Ok(Some(template))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // without their tasks, get a template for those
+    pub async fn get_for_user(
+        db_client: &Client,
+        user_id: i64,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM list_templates WHERE user_id = $1 ORDER BY lower(title), id";
+        let mut templates: Vec<ListTemplate> = Vec::new();
+        let result = db_client.query(query, &[&user_id]).await?;
+
+        for row in result {
+            templates.push(row.into())
+        }
+
+        Ok(templates)
+    }
+
+    // clones a list into a template owned by the list's creator. offsets are
+    // counted from the list's earliest due date, None if there's no list
+    pub async fn from_list(
+        db_client: &Client,
+        list_id: Uuid,
+        title: Option<String>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "WITH
+            first_due AS (
+                SELECT MIN(NULLIF(due_at, 0)) AS due_at FROM tasks
+                WHERE list_id = $1 AND deleted_at IS NULL
+            ),
+            new_template AS (
+                INSERT INTO list_templates (title, user_id)
+                SELECT COALESCE($2, title), user_id FROM lists
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING *
+            ),
+            new_tasks AS (
+                INSERT INTO template_tasks (template_id, title, content, due_offset, pester, position)
+                SELECT
+                    new_template.id,
+                    tasks.title,
+                    tasks.content,
+                    NULLIF(tasks.due_at, 0) - first_due.due_at,
+                    tasks.pester,
+                    row_number() OVER (ORDER BY NULLIF(tasks.due_at, 0) NULLS LAST, tasks.title)
+                FROM tasks, new_template, first_due
+                WHERE tasks.list_id = $1 AND tasks.deleted_at IS NULL
+            )
+            SELECT * FROM new_template";
+        let template = db_client.query_opt(query, &[&list_id, &title]).await?;
+
+        match template {
+            Some(row) => {
+                let template: ListTemplate = row.into();
+                ListTemplate::get(db_client, template.id).await
+            }
+            None => Ok(None),
+        }
+    }
+
+    // creates the user's list along with its tasks in a single statement, so
+    // there's never a half instantiated list. None if there's no template
+    pub async fn instantiate(
+        db_client: &Client,
+        id: Uuid,
+        user_id: i64,
+        guild_id: i64,
+        start: i64,
+        title: Option<String>,
+    ) -> Result<Option<List>, DatabaseError> {
+        let query = "WITH
+            source AS (SELECT * FROM list_templates WHERE id = $1),
+            new_list AS (
+                INSERT INTO lists (title, user_id)
+                SELECT COALESCE($5, title), $2 FROM source
+                RETURNING *
+            ),
+            new_tasks AS (
+                INSERT INTO tasks (list_id, user_id, guild_id, title, content, pester, due_at)
+                SELECT
+                    new_list.id,
+                    $2,
+                    $3,
+                    t.title,
+                    t.content,
+                    COALESCE(t.pester, source.pester, 0),
+                    COALESCE($4 + t.due_offset, 0)
+                FROM template_tasks t, source, new_list
+                WHERE t.template_id = source.id
+                ORDER BY t.position
+                RETURNING id
+            ),
+            events AS (
+                INSERT INTO task_events (task_id, kind, actor)
+                SELECT id, 'created', $2 FROM new_tasks
+            )
+            SELECT * FROM new_list";
+        let list = db_client
+            .query_opt(query, &[&id, &user_id, &guild_id, &start, &title])
+            .await?;
+
+        let list: List = match list {
+            Some(row) => row.into(),
+            None => return Ok(None),
+        };
+
+        for task in List::get_tasks(db_client, list.id, &TaskFilter::default()).await? {
+            Partnership::inherit(db_client, &task).await?;
+        }
+
+        Ok(Some(list))
+    }
+
+    pub async fn delete(db_client: &Client, id: Uuid) -> Result<(), DatabaseError> {
+        let query = "DELETE FROM list_templates WHERE id = $1";
+        db_client.query_opt(query, &[&id]).await?;
+
+        Ok(())
+    }
+}
+
+impl From<Row> for ListTemplate {
+    fn from(row: Row) -> Self {
+        let id = row.get("id");
+        let title = row.get("title");
+        let user_id = row.get("user_id");
+        let pester = row.get("pester");
+        let created_at = row.get("created_at");
+
+        ListTemplate {
+            id,
+            title,
+            user_id,
+            pester,
+            created_at,
+            tasks: vec![],
+        }
+    }
+}
+
+// due_offset is in seconds after the start date the template is
+// instantiated with, pester falls back to the template's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTask {
+    pub title: String,
+    pub content: Option<String>,
+    pub due_offset: Option<i64>,
+    pub pester: Option<i16>,
+}
+
+impl TemplateTask {
+    pub async fn get_for_template(
+        db_client: &Client,
+        template_id: Uuid,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM template_tasks WHERE template_id = $1 ORDER BY position";
+        let mut tasks: Vec<TemplateTask> = Vec::new();
+        let result = db_client.query(query, &[&template_id]).await?;
+
+        for row in result {
+            tasks.push(row.into())
+        }
+
+        Ok(tasks)
+    }
+}
+
+impl From<Row> for TemplateTask {
+    fn from(row: Row) -> Self {
+        let title = row.get("title");
+        let content = row.get("content");
+        let due_offset = row.get("due_offset");
+        let pester = row.get("pester");
+
+        TemplateTask {
+            title,
+            content,
+            due_offset,
+            pester,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum JobType {
@@ -320,409 +1376,2713 @@ impl JobType {
     }
 }
 
-impl From<&str> for JobType {
-    fn from(value: &str) -> Self {
-        match value {
-            "pester" => Self::Pester,
-            "overdue" => Self::Overdue,
-            "reminder" => Self::Reminder,
-            _ => Self::Unknown,
+impl From<&str> for JobType {
+    fn from(value: &str) -> Self {
+        match value {
+            "pester" => Self::Pester,
+            "overdue" => Self::Overdue,
+            "reminder" => Self::Reminder,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+pub type TaskJobs = HashMap<JobType, Option<Uuid>>;
+
+// tags are cut off after this many characters
+pub const MAX_TAG: usize = 32;
+
+#[derive(Debug, Clone, Copy, Default, ToSql, FromSql, PartialEq, Eq, Serialize, Deserialize)]
+#[postgres(name = "priority")]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[postgres(name = "low")]
+    Low,
+    #[default]
+    #[postgres(name = "normal")]
+    Normal,
+    #[postgres(name = "high")]
+    High,
+    #[postgres(name = "urgent")]
+    Urgent,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 4] = [
+        Priority::Low,
+        Priority::Normal,
+        Priority::High,
+        Priority::Urgent,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Priority::ALL.into_iter().find(|p| p.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TaskSort {
+    #[default]
+    Due,
+    Priority,
+    Tag,
+    Checked,
+}
+
+impl TaskSort {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "due" => Some(TaskSort::Due),
+            "priority" => Some(TaskSort::Priority),
+            "tag" => Some(TaskSort::Tag),
+            "checked" => Some(TaskSort::Checked),
+            _ => None,
+        }
+    }
+
+    // the expression to sort and page by along with its type, it can't be
+    // null or keyset pagination would skip rows. tasks without a due date go
+    // last either way and ties are broken by id
+    fn key(&self, descending: bool) -> (&'static str, &'static str) {
+        match self {
+            TaskSort::Due if descending => ("COALESCE(due_at, 0)", "bigint"),
+            TaskSort::Due => ("COALESCE(NULLIF(due_at, 0), 9223372036854775807)", "bigint"),
+            TaskSort::Priority => (
+                "array_position(enum_range(NULL::priority), priority)",
+                "integer",
+            ),
+            TaskSort::Tag => ("COALESCE(tags[1], '')", "text"),
+            TaskSort::Checked => ("COALESCE(checked, false)::integer", "integer"),
+        }
+    }
+}
+
+// shared by the queries for a list's or user's tasks, $1 is the list or user
+// and $2 to $7 are the filter's priority, tag, checked, due_after, due_before
+// and archived
+const TASK_FILTER: &str = "deleted_at IS NULL
+    AND ($7 OR archived_at IS NULL)
+    AND ($2::priority IS NULL OR priority = $2)
+    AND ($3::text IS NULL OR $3 = ANY (tags))
+    AND ($4::boolean IS NULL OR checked = $4)
+    AND ($5::bigint IS NULL OR due_at > $5)
+    AND ($6::bigint IS NULL OR (due_at > 0 AND due_at <= $6))";
+
+// every field narrows the tasks down further, the default returns all of
+// them by due date. archived tasks are only included when archived is set
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    pub priority: Option<Priority>,
+    pub tag: Option<String>,
+    pub checked: Option<bool>,
+    pub due_after: Option<i64>,
+    pub due_before: Option<i64>,
+    pub sort: TaskSort,
+    pub descending: bool,
+    pub archived: bool,
+}
+
+const DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueWindow {
+    Overdue,
+    Today,
+    Week,
+}
+
+impl DueWindow {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "overdue" => Some(DueWindow::Overdue),
+            "today" => Some(DueWindow::Today),
+            "week" => Some(DueWindow::Week),
+            _ => None,
+        }
+    }
+}
+
+impl TaskFilter {
+    // narrows the filter down to unchecked tasks due within the window. days
+    // are in UTC and weeks start on monday
+    pub fn within(mut self, window: DueWindow, now: i64) -> Self {
+        let today = now - now.rem_euclid(DAY);
+        let (after, before) = match window {
+            DueWindow::Overdue => (None, now - 1),
+            DueWindow::Today => (Some(today - 1), today + DAY - 1),
+            DueWindow::Week => {
+                // the epoch was a thursday
+                let monday = today - (today / DAY + 3).rem_euclid(7) * DAY;
+                (Some(monday - 1), monday + 7 * DAY - 1)
+            }
+        };
+
+        self.checked = Some(false);
+        self.due_after = self.due_after.max(after);
+        self.due_before = Some(self.due_before.map_or(before, |due| due.min(before)));
+        self
+    }
+
+    // a cursor of a page fetched with this filter
+    pub fn cursor(&self, cursor: &str) -> Option<Cursor> {
+        let (_, key_type) = self.sort.key(self.descending);
+        Cursor::parse(cursor, key_type, "uuid")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UserSort {
+    #[default]
+    Username,
+    Id,
+}
+
+impl UserSort {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "username" => Some(UserSort::Username),
+            "id" => Some(UserSort::Id),
+            _ => None,
+        }
+    }
+
+    fn key(&self) -> (&'static str, &'static str) {
+        match self {
+            UserSort::Username => ("lower(username)", "text"),
+            UserSort::Id => ("id", "bigint"),
+        }
+    }
+}
+
+// username matches the start of a name regardless of case
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    pub username: Option<String>,
+    pub sort: UserSort,
+    pub descending: bool,
+}
+
+impl UserFilter {
+    // a cursor of a page fetched with this filter
+    pub fn cursor(&self, cursor: &str) -> Option<Cursor> {
+        let (_, key_type) = self.sort.key();
+        Cursor::parse(cursor, key_type, "bigint")
+    }
+}
+
+// $1 is the guild and $2 the username prefix
+const USER_FILTER: &str = "id IN (SELECT user_id FROM user_guild WHERE guild_id = $1)
+    AND ($2::text IS NULL OR starts_with(lower(username), lower($2)))";
+
+// lowercase, trimmed, sorted and without duplicates or empty tags
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags = tags
+        .iter()
+        .map(|tag| {
+            tag.trim()
+                .to_lowercase()
+                .chars()
+                .take(MAX_TAG)
+                .collect::<String>()
+        })
+        .filter(|tag| !tag.is_empty())
+        .collect::<Vec<String>>();
+
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: Uuid,
+    pub list_id: Uuid,
+    pub user_id: i64,
+    pub guild_id: i64,
+    pub title: String,
+    pub content: Option<String>,
+    pub checked: bool,
+    pub pester: Option<i16>,
+    pub due_at: Option<i64>,
+    pub proof_id: Option<Uuid>,
+    pub pester_job: Option<Uuid>,
+    pub overdue_job: Option<Uuid>,
+    pub reminder_job: Option<Uuid>,
+    pub thread_id: Option<i64>,
+    // both are maintained by the database, whatever is sent is ignored
+    pub checked_at: Option<i64>,
+    pub lateness: Option<i64>,
+    // always set on tasks read from the database. older clients leave them
+    // out, a new task then gets the defaults and an update keeps what's
+    // stored. an empty penalty clears it
+    pub quorum: Option<Quorum>,
+    pub stake: Option<i32>,
+    pub penalty: Option<String>,
+    pub stake_to_partners: Option<bool>,
+    pub auto_check: Option<bool>,
+    pub priority: Option<Priority>,
+    pub tags: Option<Vec<String>>,
+    // like checked_at, set through archive and delete
+    pub archived_at: Option<i64>,
+    pub deleted_at: Option<i64>,
+}
+
+// everything a task can be created with, the defaults are what the
+// database would pick
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    pub list_id: Uuid,
+    pub user_id: i64,
+    pub guild_id: i64,
+    pub title: String,
+    pub content: Option<String>,
+    pub pester: Option<i16>,
+    pub due_at: Option<i64>,
+    pub quorum: Quorum,
+    pub stake: i32,
+    pub penalty: Option<String>,
+    pub stake_to_partners: bool,
+    pub auto_check: bool,
+    pub priority: Priority,
+    pub tags: Vec<String>,
+}
+
+impl From<&Task> for NewTask {
+    fn from(task: &Task) -> Self {
+        NewTask {
+            list_id: task.list_id,
+            user_id: task.user_id,
+            guild_id: task.guild_id,
+            title: task.title.clone(),
+            content: task.content.clone(),
+            pester: task.pester,
+            due_at: task.due_at,
+            quorum: task.quorum.unwrap_or_default(),
+            stake: task.stake.unwrap_or_default(),
+            penalty: task.penalty.clone().filter(|penalty| !penalty.is_empty()),
+            stake_to_partners: task.stake_to_partners.unwrap_or_default(),
+            auto_check: task.auto_check.unwrap_or_default(),
+            priority: task.priority.unwrap_or_default(),
+            tags: task.tags.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl Task {
+    // `actor` is whoever created the task, not necessarily its assignee
+    pub async fn new(
+        db_client: &Client,
+        new_task: NewTask,
+        actor: Option<i64>,
+    ) -> Result<Self, DatabaseError> {
+        let task: Task = Task::insert(db_client, &new_task).await?.into();
+
+        TaskEvent::record(db_client, task.id, TaskEventKind::Created, actor, None).await?;
+        Partnership::inherit(db_client, &task).await?;
+
+        Ok(task)
+    }
+
+    pub async fn get(db_client: &Client, id: Uuid) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM tasks WHERE id = $1 AND deleted_at IS NULL";
+        let task = db_client.query_opt(query, &[&id]).await?;
+
+        if let Some(t) = task {
+            Ok(Some(t.into()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn get_by_proof(
+        db_client: &Client,
+        proof_id: Uuid,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM tasks WHERE proof_id = $1 AND deleted_at IS NULL";
+        let task = db_client.query_opt(query, &[&proof_id]).await?;
+
+        if let Some(t) = task {
+            Ok(Some(t.into()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // every task of the user across lists and guilds
+    pub async fn get_for_user(
+        db_client: &Client,
+        user_id: i64,
+        filter: &TaskFilter,
+        cursor: Option<&Cursor>,
+        limit: i64,
+    ) -> Result<Page<Self>, DatabaseError> {
+        Task::get_page(db_client, "user_id", &user_id, filter, cursor, limit).await
+    }
+
+    // hands the task to someone else on its list, None if they can't have it
+    pub async fn assign(
+        db_client: &Client,
+        task: &Task,
+        assignee: i64,
+        actor: Option<i64>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        if !ListMember::assignable(db_client, task.list_id, task.guild_id, assignee).await? {
+            return Ok(None);
+        }
+
+        let query =
+            "UPDATE tasks SET user_id = $2 WHERE id = $1 AND deleted_at IS NULL RETURNING *";
+        let row = db_client.query_opt(query, &[&task.id, &assignee]).await?;
+        let assigned: Option<Task> = row.map(Task::from);
+
+        if let Some(assigned) = assigned.as_ref() {
+            if assigned.user_id != task.user_id {
+                let details = json!({ "user_id": [task.user_id, assigned.user_id] });
+                TaskEvent::record(
+                    db_client,
+                    task.id,
+                    TaskEventKind::Assigned,
+                    actor,
+                    Some(details),
+                )
+                .await?;
+                Partnership::inherit(db_client, assigned).await?;
+            }
+        }
+
+        Ok(assigned)
+    }
+
+    // the tasks whose scope column equals owner, $1 in the queries
+    async fn get_page(
+        db_client: &Client,
+        scope: &str,
+        owner: &(dyn ToSql + Sync),
+        filter: &TaskFilter,
+        cursor: Option<&Cursor>,
+        limit: i64,
+    ) -> Result<Page<Self>, DatabaseError> {
+        let (key, key_type) = filter.sort.key(filter.descending);
+        let (direction, comparison) = direction(filter.descending);
+        let query = format!(
+            "SELECT *, ({0})::text AS sort_key, id::text AS page_id FROM tasks
+            WHERE {1} = $1 AND {2}
+                AND ($8::text IS NULL OR ({0}, id) {3} ($8::text::{4}, $9::text::uuid))
+            ORDER BY {0} {5}, id {5}
+            LIMIT $10",
+            key, scope, TASK_FILTER, comparison, key_type, direction
+        );
+        let count_query = format!(
+            "SELECT COUNT(*) AS total FROM tasks WHERE {} = $1 AND {}",
+            scope, TASK_FILTER
+        );
+        let tag = filter.tag.as_ref().map(|tag| tag.trim().to_lowercase());
+        let (after, after_id) = Cursor::split(cursor);
+
+        fetch_page(
+            db_client,
+            &query,
+            &[
+                owner,
+                &filter.priority,
+                &tag,
+                &filter.checked,
+                &filter.due_after,
+                &filter.due_before,
+                &filter.archived,
+                &after,
+                &after_id,
+                &(limit + 1),
+            ],
+            &count_query,
+            &[
+                owner,
+                &filter.priority,
+                &tag,
+                &filter.checked,
+                &filter.due_after,
+                &filter.due_before,
+                &filter.archived,
+            ],
+            limit,
+        )
+        .await
+    }
+
+    // a task stops counting as overdue once it is checked or its proof is approved
+    pub async fn count_overdue(
+        db_client: &Client,
+        user_id: i64,
+        guild_id: i64,
+    ) -> Result<i64, DatabaseError> {
+        let query = "SELECT COUNT(*) AS overdue FROM tasks
+            LEFT JOIN proof ON proof.id = tasks.proof_id
+            WHERE tasks.user_id = $1
+                AND tasks.guild_id = $2
+                AND tasks.checked = false
+                AND tasks.deleted_at IS NULL
+                AND tasks.archived_at IS NULL
+                AND tasks.due_at > 0
+                AND tasks.due_at < extract(epoch from now())::bigint
+                AND COALESCE(proof.approved, false) = false";
+        let row = db_client.query_one(query, &[&user_id, &guild_id]).await?;
+
+        Ok(row.get("overdue"))
+    }
+
+    pub async fn search_unchecked(
+        db_client: &Client,
+        user_id: i64,
+        guild_id: i64,
+        prefix: &str,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM tasks
+            WHERE user_id = $1
+                AND guild_id = $2
+                AND checked = false
+                AND deleted_at IS NULL
+                AND archived_at IS NULL
+                AND starts_with(lower(title), lower($3))
+            ORDER BY due_at, title
+            LIMIT 25";
+        let mut tasks: Vec<Task> = Vec::new();
+        let result = db_client
+            .query(query, &[&user_id, &guild_id, &prefix])
+            .await?;
+
+        for row in result {
+            tasks.push(row.into())
+        }
+
+        Ok(tasks)
+    }
+
+    // checked_at keeps the time of the first check, so re-saving a checked
+    // task doesn't change its lateness. every change ends up in task_events,
+    // the actor is None when the bot makes the change on its own
+    pub async fn update(
+        db_client: &Client,
+        task: Task,
+        actor: Option<i64>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let previous = Task::get(db_client, task.id).await?;
+        let tags = task.tags.as_deref().map(normalize_tags);
+        let query = "UPDATE tasks
+            SET
+                list_id = $1,
+                title = $2,
+                content = $3,
+                checked = $4,
+                pester = $5,
+                due_at = $6,
+                proof_id = $7,
+                quorum = COALESCE($9, quorum),
+                stake = COALESCE($10, stake),
+                penalty = NULLIF(COALESCE($11, penalty), ''),
+                stake_to_partners = COALESCE($12, stake_to_partners),
+                auto_check = COALESCE($13, auto_check),
+                priority = COALESCE($14, priority),
+                tags = COALESCE($15, tags),
+                checked_at = CASE
+                    WHEN NOT $4 THEN NULL
+                    WHEN checked THEN checked_at
+                    ELSE extract(epoch from now())::bigint
+                END
+            WHERE
+                id = $8";
+        db_client
+            .query_opt(
+                query,
+                &[
+                    &task.list_id,
+                    &task.title,
+                    &task.content,
+                    &task.checked,
+                    &task.pester,
+                    &task.due_at,
+                    &task.proof_id,
+                    &task.id,
+                    &task.quorum,
+                    &task.stake,
+                    &task.penalty,
+                    &task.stake_to_partners,
+                    &task.auto_check,
+                    &task.priority,
+                    &tags,
+                ],
+            )
+            .await?;
+
+        let slf = Task::get(db_client, task.id).await?;
+
+        if let (Some(previous), Some(current)) = (&previous, &slf) {
+            TaskEvent::record_changes(db_client, previous, current, actor).await?;
+        }
+
+        Ok(slf)
+    }
+
+    // soft deletes, the task keeps counting towards statistics until it's
+    // purged
+    pub async fn delete(
+        db_client: &Client,
+        id: Uuid,
+        actor: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        let query = "WITH deleted AS (
+                UPDATE tasks SET deleted_at = extract(epoch from now())::bigint
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING id
+            )
+            INSERT INTO task_events (task_id, kind, actor)
+            SELECT id, 'deleted', $2 FROM deleted";
+        db_client.query_opt(query, &[&id, &actor]).await?;
+
+        Ok(())
+    }
+
+    // None if the task isn't deleted, isn't on the list, or the whole list is
+    // deleted. that one has to be restored instead. deleted tasks can't be
+    // looked up, so the list is what permissions are checked against
+    pub async fn restore(
+        db_client: &Client,
+        list_id: Uuid,
+        id: Uuid,
+        actor: Option<i64>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "WITH
+            restored AS (
+                UPDATE tasks SET deleted_at = NULL
+                WHERE id = $1
+                    AND list_id = $2
+                    AND deleted_at IS NOT NULL
+                    AND $2 IN (SELECT id FROM lists WHERE deleted_at IS NULL)
+                RETURNING *
+            ),
+            event AS (
+                INSERT INTO task_events (task_id, kind, actor)
+                SELECT id, 'restored', $3 FROM restored
+            )
+            SELECT * FROM restored";
+        let task = db_client.query_opt(query, &[&id, &list_id, &actor]).await?;
+
+        Ok(task.map(Task::from))
+    }
+
+    // archived tasks are left out of collections unless the filter asks for
+    // them, and never get reminders
+    pub async fn archive(
+        db_client: &Client,
+        id: Uuid,
+        archived: bool,
+        actor: Option<i64>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "WITH
+            previous AS (SELECT archived_at FROM tasks WHERE id = $1),
+            updated AS (
+                UPDATE tasks
+                SET archived_at = CASE
+                    WHEN NOT $2 THEN NULL
+                    ELSE COALESCE(archived_at, extract(epoch from now())::bigint)
+                END
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING *
+            ),
+            event AS (
+                INSERT INTO task_events (task_id, kind, actor)
+                SELECT
+                    updated.id,
+                    CASE WHEN $2 THEN 'archived' ELSE 'unarchived' END::task_event_kind,
+                    $3
+                FROM updated, previous
+                WHERE (previous.archived_at IS NULL) = $2
+            )
+            SELECT * FROM updated";
+        let task = db_client
+            .query_opt(query, &[&id, &archived, &actor])
+            .await?;
+
+        Ok(task.map(Task::from))
+    }
+
+    // like List::purge, for tasks deleted on their own
+    pub async fn purge(db_client: &Client, before: i64) -> Result<usize, DatabaseError> {
+        let query = "DELETE FROM tasks WHERE deleted_at < $1 RETURNING id";
+        let purged = db_client.query(query, &[&before]).await?;
+
+        Ok(purged.len())
+    }
+
+    // once a partner has accepted, changes to the deadline, the pester interval,
+    // the quorum, lowering the stake and checking off without proof are held
+    // back until one of the partners approves them. everything else in the
+    // update goes through right away, and partners' own changes aren't held back
+    pub async fn update_guarded(
+        db_client: &Client,
+        task: Task,
+        actor: Option<i64>,
+    ) -> Result<(Option<Self>, Option<PendingChange>), DatabaseError> {
+        let current = match Task::get(db_client, task.id).await? {
+            Some(current) => current,
+            None => return Ok((None, None)),
+        };
+        // what an older client left out stays as it is
+        let task = Task {
+            quorum: task.quorum.or(current.quorum),
+            stake: task.stake.or(current.stake),
+            ..task
+        };
+        let partners = AccountabilityRequest::partners(db_client, task.id).await?;
+        let by_partner = actor.is_some_and(|actor| partners.contains(&actor));
+        let proven = match task.proof_id {
+            Some(proof_id) => Proof::approved_for(db_client, proof_id, task.id).await?,
+            None => false,
+        };
+
+        if partners.is_empty() || by_partner || !PendingChange::needed(&current, &task, proven) {
+            let updated = Task::update(db_client, task, actor).await?;
+
+            return Ok((updated, None));
+        }
+
+        let requested_by = actor.unwrap_or(current.user_id);
+        let change =
+            PendingChange::propose(db_client, &task, requested_by, ChangeKind::Update, None)
+                .await?;
+        let held_back = Task {
+            due_at: current.due_at,
+            pester: current.pester,
+            quorum: current.quorum,
+            stake: current.stake.max(task.stake),
+            checked: current.checked,
+            ..task
+        };
+        let updated = Task::update(db_client, held_back, actor).await?;
+
+        Ok((updated, Some(change)))
+    }
+
+    // returns the pending change if the partner has to approve the deletion
+    pub async fn delete_guarded(
+        db_client: &Client,
+        id: Uuid,
+        actor: Option<i64>,
+    ) -> Result<Option<PendingChange>, DatabaseError> {
+        let partners = AccountabilityRequest::partners(db_client, id).await?;
+        let by_partner = actor.is_some_and(|actor| partners.contains(&actor));
+        let task = Task::get(db_client, id).await?;
+
+        match task {
+            Some(task) if !partners.is_empty() && !by_partner => {
+                let requested_by = actor.unwrap_or(task.user_id);
+                let kind = ChangeKind::Delete;
+                let change =
+                    PendingChange::propose(db_client, &task, requested_by, kind, None).await?;
+
+                Ok(Some(change))
+            }
+            _ => {
+                Task::delete(db_client, id, actor).await?;
+
+                Ok(None)
+            }
+        }
+    }
+
+    pub async fn set_thread(
+        db_client: &Client,
+        task_id: Uuid,
+        thread_id: i64,
+    ) -> Result<(), DatabaseError> {
+        let query = "UPDATE tasks SET thread_id = $1 WHERE id = $2";
+        db_client.query_opt(query, &[&thread_id, &task_id]).await?;
+
+        Ok(())
+    }
+
+    pub async fn attach_job(
+        db_client: &Client,
+        task_id: Uuid,
+        job_id: Uuid,
+        job_type: JobType,
+    ) -> Result<(), DatabaseError> {
+        let query = format!(
+            "UPDATE tasks SET {}_job = $1 WHERE id = $2",
+            job_type.as_str()
+        );
+        db_client
+            .query_opt(query.as_str(), &[&job_id, &task_id])
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_job(
+        db_client: &Client,
+        task_id: Uuid,
+        job_id: Uuid,
+        job_type: &JobType,
+    ) -> Result<(), DatabaseError> {
+        let query = format!(
+            "UPDATE tasks SET {}_job = NULL WHERE id = $1",
+            job_type.as_str()
+        );
+        db_client.query_opt(query.as_str(), &[&task_id]).await?;
+
+        let remove_job_query = "DELETE FROM job WHERE id = $1";
+        db_client.query_opt(remove_job_query, &[&job_id]).await?;
+
+        Ok(())
+    }
+
+    pub async fn collect_jobs(
+        db_client: &Client,
+        task_id: Uuid,
+    ) -> Result<TaskJobs, DatabaseError> {
+        let mut result: HashMap<JobType, Option<Uuid>> = HashMap::new();
+        let query = "SELECT pester_job, reminder_job, overdue_job FROM tasks WHERE id = $1";
+        let row = db_client.query_one(query, &[&task_id]).await?;
+
+        let pester_job: Option<Uuid> = row.get("pester_job");
+        let reminder_job: Option<Uuid> = row.get("reminder_job");
+        let overdue_job: Option<Uuid> = row.get("overdue_job");
+
+        result.insert(JobType::Pester, pester_job);
+        result.insert(JobType::Reminder, reminder_job);
+        result.insert(JobType::Overdue, overdue_job);
+
+        Ok(result)
+    }
+
+    pub async fn collect_all_jobs(
+        db_client: &Client,
+    ) -> Result<HashMap<Uuid, TaskJobs>, DatabaseError> {
+        let mut result: HashMap<Uuid, TaskJobs> = HashMap::new();
+        let query = "SELECT 
+            id, pester_job, reminder_job, overdue_job 
+            FROM tasks
+            WHERE deleted_at IS NULL AND (
+                pester_job IS NOT NULL OR
+                reminder_job IS NOT NULL OR
+                overdue_job IS NOT NULL
+            )";
+        let rows = db_client.query(query, &[]).await?;
+
+        for row in rows {
+            let pester_job: Option<Uuid> = row.get("pester_job");
+            let reminder_job: Option<Uuid> = row.get("reminder_job");
+            let overdue_job: Option<Uuid> = row.get("overdue_job");
+
+            let jobs = HashMap::from([
+                (JobType::Pester, pester_job),
+                (JobType::Reminder, reminder_job),
+                (JobType::Overdue, overdue_job),
+            ]);
+
+            result.insert(row.get("id"), jobs);
+        }
+
+        Ok(result)
+    }
+
+    async fn insert(db_client: &Client, task: &NewTask) -> Result<Row, DatabaseError> {
+        let tags = normalize_tags(&task.tags);
+        let query = "INSERT INTO
+            tasks (
+                list_id, user_id, guild_id, title, content, pester, due_at, quorum,
+                stake, penalty, stake_to_partners, auto_check, priority, tags
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING *";
+        db_client
+            .query_one(
+                query,
+                &[
+                    &task.list_id,
+                    &task.user_id,
+                    &task.guild_id,
+                    &task.title,
+                    &task.content,
+                    &task.pester,
+                    &task.due_at,
+                    &task.quorum,
+                    &task.stake,
+                    &task.penalty,
+                    &task.stake_to_partners,
+                    &task.auto_check,
+                    &task.priority,
+                    &tags,
+                ],
+            )
+            .await
+    }
+}
+
+impl From<Row> for Task {
+    fn from(row: Row) -> Self {
+        let id = row.get("id");
+        let list_id = row.get("list_id");
+        let user_id = row.get("user_id");
+        let guild_id = row.get("guild_id");
+        let title = row.get("title");
+        let content = row.get("content");
+        let checked = row.get("checked");
+        let pester = row.get("pester");
+        let due_at = row.get("due_at");
+        let proof_id = row.get("proof_id");
+        let pester_job = row.get("pester_job");
+        let overdue_job = row.get("overdue_job");
+        let reminder_job = row.get("reminder_job");
+        let thread_id = row.get("thread_id");
+        let checked_at = row.get("checked_at");
+        let lateness = row.get("lateness");
+        let quorum = row.get("quorum");
+        let stake = row.get("stake");
+        let penalty = row.get("penalty");
+        let stake_to_partners = row.get("stake_to_partners");
+        let auto_check = row.get("auto_check");
+        let priority = row.get("priority");
+        let tags = row.get("tags");
+        let archived_at = row.get("archived_at");
+        let deleted_at = row.get("deleted_at");
+
+        Task {
+            id,
+            list_id,
+            user_id,
+            guild_id,
+            title,
+            content,
+            checked,
+            pester,
+            due_at,
+            proof_id,
+            pester_job,
+            overdue_job,
+            reminder_job,
+            thread_id,
+            checked_at,
+            lateness,
+            quorum,
+            stake,
+            penalty,
+            stake_to_partners,
+            auto_check,
+            priority,
+            tags,
+            archived_at,
+            deleted_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskItem {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub title: String,
+    #[serde(default)]
+    pub position: i32,
+    #[serde(default)]
+    pub checked: bool,
+    // maintained by the database, whatever is sent is ignored
+    pub checked_at: Option<i64>,
+}
+
+impl TaskItem {
+    // new items go to the end of the checklist
+    pub async fn new(
+        db_client: &Client,
+        task_id: Uuid,
+        title: String,
+    ) -> Result<Self, DatabaseError> {
+        let query = "INSERT INTO
+            task_items (task_id, title, position)
+            SELECT $1, $2, COALESCE(MAX(position) + 1, 0)
+            FROM task_items
+            WHERE task_id = $1
+            RETURNING *";
+        let row = db_client.query_one(query, &[&task_id, &title]).await?;
+
+        Ok(row.into())
+    }
+
+    pub async fn get(db_client: &Client, id: Uuid) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM task_items WHERE id = $1";
+        let item = db_client.query_opt(query, &[&id]).await?;
+
+        Ok(item.map(|i| i.into()))
+    }
+
+    pub async fn get_for_task(
+        db_client: &Client,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM task_items WHERE task_id = $1 ORDER BY position, id";
+        let mut items: Vec<TaskItem> = Vec::new();
+        let result = db_client.query(query, &[&task_id]).await?;
+
+        for row in result {
+            items.push(row.into())
+        }
+
+        Ok(items)
+    }
+
+    // only the title and whether it's checked can change, positions go
+    // through reorder
+    pub async fn update(db_client: &Client, item: TaskItem) -> Result<Option<Self>, DatabaseError> {
+        let query = "UPDATE task_items
+            SET
+                title = $1,
+                checked = $2,
+                checked_at = CASE
+                    WHEN NOT $2 THEN NULL
+                    WHEN checked THEN checked_at
+                    ELSE extract(epoch from now())::bigint
+                END
+            WHERE id = $3
+            RETURNING *";
+        let item = db_client
+            .query_opt(query, &[&item.title, &item.checked, &item.id])
+            .await?;
+
+        Ok(item.map(|i| i.into()))
+    }
+
+    // items are put in the order of `ids`, the ones that are left out keep
+    // their order after them. ids of other tasks' items are ignored
+    pub async fn reorder(
+        db_client: &Client,
+        task_id: Uuid,
+        ids: Vec<Uuid>,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "UPDATE task_items
+            SET position = COALESCE(
+                (SELECT ord.n - 1 FROM unnest($2::uuid[]) WITH ORDINALITY AS ord (id, n)
+                    WHERE ord.id = task_items.id),
+                cardinality($2::uuid[]) + position
+            )::integer
+            WHERE task_id = $1";
+        db_client.query_opt(query, &[&task_id, &ids]).await?;
+
+        TaskItem::get_for_task(db_client, task_id).await
+    }
+
+    pub async fn delete(db_client: &Client, id: Uuid) -> Result<(), DatabaseError> {
+        let query = "DELETE FROM task_items WHERE id = $1";
+        db_client.query_opt(query, &[&id]).await?;
+
+        Ok(())
+    }
+
+    // checks off a task with auto_check once every item is done, through
+    // the partner guard like any other check-off. returns nothing if the
+    // task didn't need checking
+    pub async fn auto_check(
+        db_client: &Client,
+        task_id: Uuid,
+        actor: Option<i64>,
+    ) -> Result<(Option<Task>, Option<PendingChange>), DatabaseError> {
+        let task = match Task::get(db_client, task_id).await? {
+            Some(task) if task.auto_check == Some(true) && !task.checked => task,
+            _ => return Ok((None, None)),
+        };
+        let progress = Progress::for_task(db_client, task_id).await?;
+
+        if progress.total == 0 || progress.done < progress.total {
+            return Ok((None, None));
+        }
+
+        Task::update_guarded(
+            db_client,
+            Task {
+                checked: true,
+                ..task
+            },
+            actor,
+        )
+        .await
+    }
+}
+
+impl From<Row> for TaskItem {
+    fn from(row: Row) -> Self {
+        let id = row.get("id");
+        let task_id = row.get("task_id");
+        let title = row.get("title");
+        let position = row.get("position");
+        let checked = row.get("checked");
+        let checked_at = row.get("checked_at");
+
+        TaskItem {
+            id,
+            task_id,
+            title,
+            position,
+            checked,
+            checked_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Progress {
+    pub done: i64,
+    pub total: i64,
+}
+
+impl Progress {
+    pub async fn for_task(db_client: &Client, task_id: Uuid) -> Result<Self, DatabaseError> {
+        let query = "SELECT COUNT(*) FILTER (WHERE checked) AS done, COUNT(*) AS total
+            FROM task_items
+            WHERE task_id = $1";
+        let row = db_client.query_one(query, &[&task_id]).await?;
+
+        Ok(Progress {
+            done: row.get("done"),
+            total: row.get("total"),
+        })
+    }
+
+    // how many of a list's tasks are checked off
+    pub async fn for_list(db_client: &Client, list_id: Uuid) -> Result<Self, DatabaseError> {
+        let query = "SELECT COUNT(*) FILTER (WHERE checked) AS done, COUNT(*) AS total
+            FROM tasks
+            WHERE list_id = $1 AND deleted_at IS NULL";
+        let row = db_client.query_one(query, &[&list_id]).await?;
+
+        Ok(Progress {
+            done: row.get("done"),
+            total: row.get("total"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub depends_on: Uuid,
+    pub created_at: i64,
+}
+
+impl TaskDependency {
+    // None if the edge would close a cycle, that is if `depends_on` already
+    // depends on `task_id` through any number of edges. adding an existing
+    // edge returns it unchanged
+    pub async fn new(
+        db_client: &Client,
+        task_id: Uuid,
+        depends_on: Uuid,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "INSERT INTO
+            task_dependencies (task_id, depends_on)
+            SELECT $1, $2
+            WHERE $1 <> $2 AND NOT EXISTS (
+                WITH RECURSIVE upstream (id) AS (
+                    SELECT depends_on FROM task_dependencies WHERE task_id = $2
+                    UNION
+                    SELECT d.depends_on FROM task_dependencies d
+                    JOIN upstream ON d.task_id = upstream.id
+                )
+                SELECT 1 FROM upstream WHERE id = $1
+            )
+            ON CONFLICT (task_id, depends_on) DO UPDATE
+            SET created_at = task_dependencies.created_at
+            RETURNING *";
+        let dependency = db_client.query_opt(query, &[&task_id, &depends_on]).await?;
+
+        Ok(dependency.map(|d| d.into()))
+    }
+
+    pub async fn get_for_task(
+        db_client: &Client,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM task_dependencies WHERE task_id = $1 ORDER BY created_at";
+        let mut dependencies: Vec<TaskDependency> = Vec::new();
+        let result = db_client.query(query, &[&task_id]).await?;
+
+        for row in result {
+            dependencies.push(row.into())
+        }
+
+        Ok(dependencies)
+    }
+
+    // the tasks this one depends on that aren't done yet, the task is blocked
+    // as long as there are any. like count_overdue an approved proof counts
+    // as done
+    pub async fn blockers(db_client: &Client, task_id: Uuid) -> Result<Vec<Task>, DatabaseError> {
+        let query = "SELECT tasks.* FROM task_dependencies
+            JOIN tasks ON tasks.id = task_dependencies.depends_on
+            LEFT JOIN proof ON proof.id = tasks.proof_id
+            WHERE task_dependencies.task_id = $1
+                AND tasks.checked = false
+                AND tasks.deleted_at IS NULL
+                AND COALESCE(proof.approved, false) = false
+            ORDER BY tasks.due_at, tasks.title";
+        let mut tasks: Vec<Task> = Vec::new();
+        let result = db_client.query(query, &[&task_id]).await?;
+
+        for row in result {
+            tasks.push(row.into())
+        }
+
+        Ok(tasks)
+    }
+
+    pub async fn is_blocked(db_client: &Client, task_id: Uuid) -> Result<bool, DatabaseError> {
+        let blockers = TaskDependency::blockers(db_client, task_id).await?;
+
+        Ok(!blockers.is_empty())
+    }
+
+    // unchecked tasks depending on `task_id` that nothing else is holding up
+    // anymore, meant to be called once `task_id` is done
+    pub async fn unblocked(db_client: &Client, task_id: Uuid) -> Result<Vec<Task>, DatabaseError> {
+        let query = "SELECT tasks.* FROM task_dependencies
+            JOIN tasks ON tasks.id = task_dependencies.task_id
+            WHERE task_dependencies.depends_on = $1
+                AND tasks.checked = false
+                AND tasks.deleted_at IS NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM task_dependencies other
+                    JOIN tasks blocker ON blocker.id = other.depends_on
+                    LEFT JOIN proof ON proof.id = blocker.proof_id
+                    WHERE other.task_id = tasks.id
+                        AND blocker.checked = false
+                        AND blocker.deleted_at IS NULL
+                        AND COALESCE(proof.approved, false) = false
+                )";
+        let mut tasks: Vec<Task> = Vec::new();
+        let result = db_client.query(query, &[&task_id]).await?;
+
+        for row in result {
+            tasks.push(row.into())
+        }
+
+        Ok(tasks)
+    }
+
+    pub async fn delete(
+        db_client: &Client,
+        task_id: Uuid,
+        depends_on: Uuid,
+    ) -> Result<(), DatabaseError> {
+        let query = "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on = $2";
+        db_client.query_opt(query, &[&task_id, &depends_on]).await?;
+
+        Ok(())
+    }
+}
+
+impl From<Row> for TaskDependency {
+    fn from(row: Row) -> Self {
+        let task_id = row.get("task_id");
+        let depends_on = row.get("depends_on");
+        let created_at = row.get("created_at");
+
+        TaskDependency {
+            task_id,
+            depends_on,
+            created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ToSql, FromSql, PartialEq, Eq, Serialize, Deserialize)]
+#[postgres(name = "task_event_kind")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventKind {
+    #[postgres(name = "created")]
+    Created,
+    #[postgres(name = "edited")]
+    Edited,
+    #[postgres(name = "checked")]
+    Checked,
+    #[postgres(name = "unchecked")]
+    Unchecked,
+    #[postgres(name = "due_changed")]
+    DueChanged,
+    #[postgres(name = "proof_attached")]
+    ProofAttached,
+    #[postgres(name = "proof_approved")]
+    ProofApproved,
+    #[postgres(name = "job_fired")]
+    JobFired,
+    #[postgres(name = "assigned")]
+    Assigned,
+    #[postgres(name = "archived")]
+    Archived,
+    #[postgres(name = "unarchived")]
+    Unarchived,
+    #[postgres(name = "deleted")]
+    Deleted,
+    #[postgres(name = "restored")]
+    Restored,
+}
+
+// the audit trail of a task, events are only ever inserted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub kind: TaskEventKind,
+    pub actor: Option<i64>,
+    pub details: Option<Value>,
+    pub created_at: i64,
+}
+
+impl TaskEvent {
+    pub async fn record(
+        db_client: &Client,
+        task_id: Uuid,
+        kind: TaskEventKind,
+        actor: Option<i64>,
+        details: Option<Value>,
+    ) -> Result<Self, DatabaseError> {
+        let query = "INSERT INTO
+            task_events (task_id, kind, actor, details)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *";
+        let row = db_client
+            .query_one(query, &[&task_id, &kind, &actor, &details])
+            .await?;
+
+        Ok(row.into())
+    }
+
+    pub async fn job_fired(
+        db_client: &Client,
+        task_id: Uuid,
+        job_type: &JobType,
+    ) -> Result<Self, DatabaseError> {
+        let details = json!({ "job": job_type.as_str() });
+
+        TaskEvent::record(
+            db_client,
+            task_id,
+            TaskEventKind::JobFired,
+            None,
+            Some(details),
+        )
+        .await
+    }
+
+    // newest first, pass the id of the last event of a page as `before` to
+    // get the next one
+    pub async fn get_page(
+        db_client: &Client,
+        task_id: Uuid,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM task_events
+            WHERE task_id = $1
+                AND ($2::bigint IS NULL OR id < $2)
+            ORDER BY id DESC
+            LIMIT $3";
+        let mut events: Vec<TaskEvent> = Vec::new();
+        let result = db_client.query(query, &[&task_id, &before, &limit]).await?;
+
+        for row in result {
+            events.push(row.into())
+        }
+
+        Ok(events)
+    }
+
+    // a single update can touch several things at once, each gets its own
+    // event so the timeline can be filtered by kind
+    async fn record_changes(
+        db_client: &Client,
+        before: &Task,
+        after: &Task,
+        actor: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        let mut edits = Map::new();
+
+        if before.list_id != after.list_id {
+            edits.insert("list_id".into(), json!([before.list_id, after.list_id]));
+        }
+        if before.title != after.title {
+            edits.insert("title".into(), json!([before.title, after.title]));
+        }
+        if before.content != after.content {
+            edits.insert("content".into(), json!([before.content, after.content]));
+        }
+        if before.pester != after.pester {
+            edits.insert("pester".into(), json!([before.pester, after.pester]));
+        }
+        if before.quorum != after.quorum {
+            edits.insert("quorum".into(), json!([before.quorum, after.quorum]));
+        }
+        if before.stake != after.stake {
+            edits.insert("stake".into(), json!([before.stake, after.stake]));
+        }
+        if before.penalty != after.penalty {
+            edits.insert("penalty".into(), json!([before.penalty, after.penalty]));
+        }
+        if before.stake_to_partners != after.stake_to_partners {
+            edits.insert(
+                "stake_to_partners".into(),
+                json!([before.stake_to_partners, after.stake_to_partners]),
+            );
+        }
+        if before.auto_check != after.auto_check {
+            edits.insert(
+                "auto_check".into(),
+                json!([before.auto_check, after.auto_check]),
+            );
+        }
+        if before.priority != after.priority {
+            edits.insert("priority".into(), json!([before.priority, after.priority]));
+        }
+        if before.tags != after.tags {
+            edits.insert("tags".into(), json!([before.tags, after.tags]));
+        }
+
+        if !edits.is_empty() {
+            let details = Some(Value::Object(edits));
+            TaskEvent::record(db_client, after.id, TaskEventKind::Edited, actor, details).await?;
+        }
+
+        if before.due_at != after.due_at {
+            let details = Some(json!({ "due_at": [before.due_at, after.due_at] }));
+            TaskEvent::record(
+                db_client,
+                after.id,
+                TaskEventKind::DueChanged,
+                actor,
+                details,
+            )
+            .await?;
+        }
+
+        if let Some(proof_id) = after.proof_id.filter(|_| before.proof_id != after.proof_id) {
+            let details = Some(json!({ "proof_id": proof_id }));
+            TaskEvent::record(
+                db_client,
+                after.id,
+                TaskEventKind::ProofAttached,
+                actor,
+                details,
+            )
+            .await?;
+        }
+
+        let checked = match (before.checked, after.checked) {
+            (false, true) => Some(TaskEventKind::Checked),
+            (true, false) => Some(TaskEventKind::Unchecked),
+            _ => None,
+        };
+
+        if let Some(kind) = checked {
+            TaskEvent::record(db_client, after.id, kind, actor, None).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Row> for TaskEvent {
+    fn from(row: Row) -> Self {
+        let id = row.get("id");
+        let task_id = row.get("task_id");
+        let kind = row.get("kind");
+        let actor = row.get("actor");
+        let details = row.get("details");
+        let created_at = row.get("created_at");
+
+        TaskEvent {
+            id,
+            task_id,
+            kind,
+            actor,
+            details,
+            created_at,
+        }
+    }
+}
+
+// checked off or with an approved proof, like in count_overdue. the same for
+// the habit stats and the leaderboard, both join proof on the task's proof_id
+const COMPLETED: &str = "(tasks.checked OR COALESCE(proof.approved, false))";
+
+// how reliably someone finishes on time. a streak is the number of on-time
+// completions in a row, broken by a late completion or a task that's overdue
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HabitStats {
+    pub on_time: i64,
+    pub late: i64,
+    pub missed: i64,
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    // in seconds, negative if tasks are usually finished early
+    pub average_lateness: Option<i64>,
+}
+
+impl HabitStats {
+    // only tasks with a due date that were either completed or are overdue
+    // count towards the stats
+    pub async fn for_user(db_client: &Client, user_id: i64) -> Result<Self, DatabaseError> {
+        let query = format!(
+            "SELECT {0} AS completed, tasks.lateness FROM tasks
+            LEFT JOIN proof ON proof.id = tasks.proof_id
+            WHERE tasks.user_id = $1
+                AND tasks.due_at > 0
+                AND ({0} OR tasks.due_at < extract(epoch from now())::bigint)
+            ORDER BY COALESCE(tasks.checked_at, tasks.due_at)",
+            COMPLETED
+        );
+        let result = db_client.query(&query, &[&user_id]).await?;
+
+        Ok(HabitStats::from_rows(result))
+    }
+
+    pub async fn for_list(db_client: &Client, list_id: Uuid) -> Result<Self, DatabaseError> {
+        let query = format!(
+            "SELECT {0} AS completed, tasks.lateness FROM tasks
+            LEFT JOIN proof ON proof.id = tasks.proof_id
+            WHERE tasks.list_id = $1
+                AND tasks.due_at > 0
+                AND ({0} OR tasks.due_at < extract(epoch from now())::bigint)
+            ORDER BY COALESCE(tasks.checked_at, tasks.due_at)",
+            COMPLETED
+        );
+        let result = db_client.query(&query, &[&list_id]).await?;
+
+        Ok(HabitStats::from_rows(result))
+    }
+
+    // rows have to be in the order the tasks were finished or missed
+    fn from_rows(rows: Vec<Row>) -> Self {
+        HabitStats::from_outcomes(
+            rows.iter()
+                .map(|row| (row.get("completed"), row.get("lateness"))),
+        )
+    }
+
+    // whether each task was completed and how late, split out of from_rows so
+    // the counting doesn't need the database
+    fn from_outcomes(outcomes: impl IntoIterator<Item = (bool, Option<i64>)>) -> Self {
+        let mut stats = HabitStats::default();
+        let mut total_lateness = 0;
+
+        for (completed, lateness) in outcomes {
+            match (completed, lateness) {
+                (true, Some(lateness)) => {
+                    total_lateness += lateness;
+
+                    if lateness <= 0 {
+                        stats.on_time += 1;
+                        stats.current_streak += 1;
+                        stats.longest_streak = stats.longest_streak.max(stats.current_streak);
+                    } else {
+                        stats.late += 1;
+                        stats.current_streak = 0;
+                    }
+                }
+                // checked before completion times were recorded, or only
+                // completed through an approved proof, so there's no lateness
+                (true, None) => {}
+                (false, _) => {
+                    stats.missed += 1;
+                    stats.current_streak = 0;
+                }
+            }
+        }
+
+        let completed = stats.on_time + stats.late;
+
+        if completed > 0 {
+            stats.average_lateness = Some(total_lateness / completed);
+        }
+
+        stats
+    }
+}
+
+// how a user did on the tasks that came due in a guild over some period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub user_id: i64,
+    pub completed: i64,
+    pub overdue: i64,
+    pub completion_rate: f64,
+    // completed tasks in a row, counting back from the most recent one
+    pub streak: i64,
+}
+
+impl LeaderboardEntry {
+    // only tasks with a due date between since and until are counted. sorted
+    // best first
+    pub async fn for_guild(
+        db_client: &Client,
+        guild_id: i64,
+        since: i64,
+        until: i64,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = format!(
+            "SELECT tasks.user_id, {} AS completed FROM tasks
+            LEFT JOIN proof ON proof.id = tasks.proof_id
+            WHERE tasks.guild_id = $1
+                AND tasks.due_at > $2
+                AND tasks.due_at <= $3
+            ORDER BY tasks.user_id, tasks.due_at DESC",
+            COMPLETED
+        );
+        let result = db_client
+            .query(&query, &[&guild_id, &since, &until])
+            .await?;
+
+        let mut entries: Vec<LeaderboardEntry> = Vec::new();
+
+        for row in result {
+            let user_id: i64 = row.get("user_id");
+            let completed: bool = row.get("completed");
+
+            if entries.last().map(|e| e.user_id) != Some(user_id) {
+                entries.push(LeaderboardEntry {
+                    user_id,
+                    completed: 0,
+                    overdue: 0,
+                    completion_rate: 0.0,
+                    streak: 0,
+                });
+            }
+
+            let entry = entries.last_mut().unwrap();
+
+            // rows come newest first, so the streak ends at the first miss
+            if completed {
+                if entry.overdue == 0 {
+                    entry.streak += 1;
+                }
+                entry.completed += 1;
+            } else {
+                entry.overdue += 1;
+            }
+        }
+
+        for entry in entries.iter_mut() {
+            entry.completion_rate =
+                entry.completed as f64 / (entry.completed + entry.overdue) as f64;
+        }
+
+        entries.sort_by(|a, b| {
+            b.completion_rate
+                .total_cmp(&a.completion_rate)
+                .then(b.completed.cmp(&a.completed))
+                .then(a.overdue.cmp(&b.overdue))
+        });
+
+        Ok(entries)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Proof {
+    pub id: Uuid,
+    pub content: Option<String>,
+    pub image: Option<String>,
+    pub approved: bool,
+}
+
+impl Proof {
+    pub async fn new(
+        db_client: &Client,
+        content: Option<String>,
+        image: Option<String>,
+    ) -> Result<Self, DatabaseError> {
+        let proof = Proof::insert(db_client, content, image).await?;
+
+        Ok(proof.into())
+    }
+
+    pub async fn get(db_client: &Client, id: Uuid) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM proof WHERE id = $1";
+        let proof = db_client.query_opt(query, &[&id]).await?;
+
+        if let Some(p) = proof {
+            Ok(Some(p.into()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // records the partner's approval and marks the proof as approved once the
+    // task's quorum is reached. only approvals of accepted partners count, and
+    // without any the first approval is enough, see Quorum::met
+    pub async fn approve(
+        db_client: &Client,
+        id: Uuid,
+        user_id: i64,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "INSERT INTO
+            proof_approvals (proof_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING";
+        db_client.query_opt(query, &[&id, &user_id]).await?;
+
+        let count_query = "SELECT
+                tasks.id,
+                tasks.quorum,
+                COUNT(accountability_requests.requested_user) AS partners,
+                COUNT(proof_approvals.user_id) AS approvals
+            FROM tasks
+            LEFT JOIN accountability_requests
+                ON accountability_requests.task_id = tasks.id
+                AND accountability_requests.status = 'accepted'
+            LEFT JOIN proof_approvals
+                ON proof_approvals.proof_id = tasks.proof_id
+                AND proof_approvals.user_id = accountability_requests.requested_user
+            WHERE tasks.proof_id = $1
+            GROUP BY tasks.id";
+        let count = db_client.query_opt(count_query, &[&id]).await?;
+        let proof = Proof::get(db_client, id).await?;
+
+        if let (Some(count), Some(proof)) = (count, proof.as_ref()) {
+            let quorum: Quorum = count.get("quorum");
+
+            if !proof.approved && quorum.met(count.get("approvals"), count.get("partners")) {
+                let approve_query = "UPDATE proof SET approved = true WHERE id = $1";
+                db_client.query_opt(approve_query, &[&id]).await?;
+
+                let details = Some(json!({ "proof_id": id }));
+                TaskEvent::record(
+                    db_client,
+                    count.get("id"),
+                    TaskEventKind::ProofApproved,
+                    Some(user_id),
+                    details,
+                )
+                .await?;
+
+                return Proof::get(db_client, id).await;
+            }
+        }
+
+        Ok(proof)
+    }
+
+    // whether the proof reached its quorum while it was attached to the task.
+    // the approval is looked up on the task's timeline, so proof that was
+    // approved for another task or never approved doesn't count
+    pub async fn approved_for(
+        db_client: &Client,
+        id: Uuid,
+        task_id: Uuid,
+    ) -> Result<bool, DatabaseError> {
+        let query = "SELECT EXISTS (
+                SELECT 1 FROM task_events
+                WHERE task_id = $1
+                    AND kind = 'proof_approved'
+                    AND details->>'proof_id' = $2::uuid::text
+            ) AS approved";
+        let row = db_client.query_one(query, &[&task_id, &id]).await?;
+
+        Ok(row.get("approved"))
+    }
+
+    // the partners who approved the proof so far
+    pub async fn approvals(db_client: &Client, id: Uuid) -> Result<Vec<i64>, DatabaseError> {
+        let query = "SELECT user_id FROM proof_approvals WHERE proof_id = $1 ORDER BY created_at";
+        let result = db_client.query(query, &[&id]).await?;
+
+        Ok(result.iter().map(|row| row.get("user_id")).collect())
+    }
+
+    pub async fn delete(db_client: &Client, id: Uuid) -> Result<(), DatabaseError> {
+        let query = "DELETE FROM proof WHERE id = $1";
+        db_client.query_opt(query, &[&id]).await?;
+
+        Ok(())
+    }
+
+    async fn insert(
+        db_client: &Client,
+        content: Option<String>,
+        image: Option<String>,
+    ) -> Result<Row, DatabaseError> {
+        let query = "INSERT INTO 
+            proof (content, image)
+            VALUES ($1, $2)
+            RETURNING *";
+        db_client.query_one(query, &[&content, &image]).await
+    }
+}
+
+impl From<Row> for Proof {
+    fn from(row: Row) -> Self {
+        let id = row.get("id");
+        let content = row.get("content");
+        let image = row.get("image");
+        let approved = row.get("approved");
+
+        Proof {
+            id,
+            content,
+            image,
+            approved,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, ToSql, FromSql, PartialEq, Eq, Serialize, Deserialize)]
+#[postgres(name = "quorum")]
+#[serde(rename_all = "lowercase")]
+pub enum Quorum {
+    #[default]
+    #[postgres(name = "any")]
+    Any,
+    #[postgres(name = "majority")]
+    Majority,
+    #[postgres(name = "all")]
+    All,
+}
+
+impl Quorum {
+    // a task without partners has nothing to wait for
+    pub fn met(&self, approvals: i64, partners: i64) -> bool {
+        if partners == 0 {
+            return true;
+        }
+
+        match self {
+            Quorum::Any => approvals >= 1,
+            Quorum::Majority => approvals * 2 > partners,
+            Quorum::All => approvals >= partners,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ToSql, FromSql, PartialEq, Serialize, Deserialize)]
+#[postgres(name = "accepted")]
+pub enum RequestStatus {
+    #[postgres(name = "accepted")]
+    Accepted,
+    #[postgres(name = "pending")]
+    Pending,
+    #[postgres(name = "rejected")]
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountabilityRequest {
+    pub requesting_user: i64,
+    pub requested_user: i64,
+    pub task_id: Uuid,
+    pub status: RequestStatus,
+}
+
+impl AccountabilityRequest {
+    pub async fn new(
+        db_client: &Client,
+        requesting_user: i64,
+        requested_user: i64,
+        task_id: Uuid,
+    ) -> Result<Self, DatabaseError> {
+        let result =
+            AccountabilityRequest::insert(db_client, requesting_user, requested_user, task_id)
+                .await?;
+
+        Ok(result.into())
+    }
+
+    pub async fn get(
+        db_client: &Client,
+        task_id: Uuid,
+        requested_user: i64,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM accountability_requests
+            WHERE task_id = $1 AND requested_user = $2";
+        let result = db_client
+            .query_opt(query, &[&task_id, &requested_user])
+            .await?;
+
+        if let Some(result) = result {
+            Ok(Some(result.into()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn get_all(db_client: &Client, task_id: Uuid) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM accountability_requests WHERE task_id = $1";
+        let mut requests: Vec<AccountabilityRequest> = Vec::new();
+        let result = db_client.query(query, &[&task_id]).await?;
+
+        for row in result {
+            requests.push(row.into())
+        }
+
+        Ok(requests)
+    }
+
+    // the partners who accepted a request for the task
+    pub async fn partners(db_client: &Client, task_id: Uuid) -> Result<Vec<i64>, DatabaseError> {
+        let query = "SELECT requested_user FROM accountability_requests
+            WHERE task_id = $1 AND status = 'accepted'
+            ORDER BY requested_user";
+        let result = db_client.query(query, &[&task_id]).await?;
+
+        Ok(result.iter().map(|row| row.get("requested_user")).collect())
+    }
+
+    pub async fn update_status(
+        db_client: &Client,
+        task_id: Uuid,
+        requested_user: i64,
+        status: RequestStatus,
+    ) -> Result<(), DatabaseError> {
+        let query = "UPDATE accountability_requests SET status = $1
+            WHERE task_id = $2 AND requested_user = $3";
+        db_client
+            .query_opt(query, &[&status, &task_id, &requested_user])
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(
+        db_client: &Client,
+        task_id: Uuid,
+        requested_user: i64,
+    ) -> Result<(), DatabaseError> {
+        let query = "DELETE FROM accountability_requests
+            WHERE task_id = $1 AND requested_user = $2";
+        db_client
+            .query_opt(query, &[&task_id, &requested_user])
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_all(db_client: &Client, task_id: Uuid) -> Result<(), DatabaseError> {
+        let query = "DELETE FROM accountability_requests WHERE task_id = $1";
+        db_client.query_opt(query, &[&task_id]).await?;
+
+        Ok(())
+    }
+
+    // drops the request of `requested_user`, or every request if None. the
+    // owner dropping an accepted partner has to be approved by the partners
+    // like any other change, a partner leaving on their own doesn't
+    pub async fn delete_guarded(
+        db_client: &Client,
+        task_id: Uuid,
+        requested_user: Option<i64>,
+        actor: i64,
+    ) -> Result<Option<PendingChange>, DatabaseError> {
+        let partners = AccountabilityRequest::partners(db_client, task_id).await?;
+        let drops_partner = match requested_user {
+            Some(requested_user) => partners.contains(&requested_user),
+            None => !partners.is_empty(),
+        };
+        let leaving = requested_user == Some(actor);
+
+        match Task::get(db_client, task_id).await? {
+            Some(task) if drops_partner && !leaving => {
+                let kind = ChangeKind::Unpartner;
+                let change =
+                    PendingChange::propose(db_client, &task, actor, kind, requested_user).await?;
+
+                Ok(Some(change))
+            }
+            _ => {
+                match requested_user {
+                    Some(requested_user) => {
+                        AccountabilityRequest::delete(db_client, task_id, requested_user).await?
+                    }
+                    None => AccountabilityRequest::delete_all(db_client, task_id).await?,
+                }
+
+                Ok(None)
+            }
+        }
+    }
+
+    async fn insert(
+        db_client: &Client,
+        requesting_user: i64,
+        requested_user: i64,
+        task_id: Uuid,
+    ) -> Result<Row, DatabaseError> {
+        let query = "INSERT INTO 
+            accountability_requests (requesting_user, requested_user, task_id)
+            VALUES ($1, $2, $3)
+            RETURNING *";
+        db_client
+            .query_one(query, &[&requesting_user, &requested_user, &task_id])
+            .await
+    }
+}
+
+impl From<Row> for AccountabilityRequest {
+    fn from(row: Row) -> Self {
+        let requesting_user = row.get("requesting_user");
+        let requested_user = row.get("requested_user");
+        let task_id = row.get("task_id");
+        let status = row.get("status");
+
+        AccountabilityRequest {
+            requesting_user,
+            requested_user,
+            task_id,
+            status,
         }
     }
 }
 
-pub type TaskJobs = HashMap<JobType, Option<Uuid>>;
-
+// a standing accountability partnership for a whole list, or for all of the
+// user's tasks in a guild. exactly one of list_id and guild_id is set
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Task {
+pub struct Partnership {
     pub id: Uuid,
-    pub list_id: Uuid,
     pub user_id: i64,
-    pub guild_id: i64,
-    pub title: String,
-    pub content: Option<String>,
-    pub checked: bool,
-    pub pester: Option<i16>,
-    pub due_at: Option<i64>,
-    pub proof_id: Option<Uuid>,
-    pub pester_job: Option<Uuid>,
-    pub overdue_job: Option<Uuid>,
-    pub reminder_job: Option<Uuid>,
+    pub partner_id: i64,
+    pub list_id: Option<Uuid>,
+    pub guild_id: Option<i64>,
+    pub status: RequestStatus,
+    pub created_at: i64,
 }
 
-impl Task {
+impl Partnership {
     pub async fn new(
         db_client: &Client,
-        list_id: Uuid,
         user_id: i64,
-        guild_id: i64,
-        title: String,
-        content: Option<String>,
-        pester: Option<i16>,
-        due_at: Option<i64>,
+        partner_id: i64,
+        list_id: Option<Uuid>,
+        guild_id: Option<i64>,
     ) -> Result<Self, DatabaseError> {
-        let task =
-            Task::insert(db_client, list_id, user_id, guild_id, title, content, pester, due_at).await?;
+        let query = "INSERT INTO
+            partnerships (user_id, partner_id, list_id, guild_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *";
+        let row = db_client
+            .query_one(query, &[&user_id, &partner_id, &list_id, &guild_id])
+            .await?;
 
-        Ok(task.into())
+        Ok(row.into())
     }
 
     pub async fn get(db_client: &Client, id: Uuid) -> Result<Option<Self>, DatabaseError> {
-        let query = "SELECT * FROM tasks WHERE id = $1";
-        let task = db_client.query_opt(query, &[&id]).await?;
+        let query = "SELECT * FROM partnerships WHERE id = $1";
+        let partnership = db_client.query_opt(query, &[&id]).await?;
 
-        if let Some(t) = task {
-            Ok(Some(t.into()))
-        } else {
-            Ok(None)
-        }
+        Ok(partnership.map(|p| p.into()))
     }
 
-    pub async fn update(db_client: &Client, task: Task) -> Result<Option<Self>, DatabaseError> {
-        let query = "UPDATE tasks
-            SET
-                list_id = $1,
-                title = $2,
-                content = $3,
-                checked = $4,
-                pester = $5,
-                due_at = $6,
-                proof_id = $7
-            WHERE
-                id = $8";
-        db_client
-            .query_opt(
-                query,
-                &[
-                    &task.list_id,
-                    &task.title,
-                    &task.content,
-                    &task.checked,
-                    &task.pester,
-                    &task.due_at,
-                    &task.proof_id,
-                    &task.id,
-                ],
-            )
-            .await?;
+    // partnerships the user asked for as well as the ones they're partner in
+    pub async fn get_for_user(
+        db_client: &Client,
+        user_id: i64,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM partnerships
+            WHERE user_id = $1 OR partner_id = $1
+            ORDER BY created_at";
+        let mut partnerships: Vec<Partnership> = Vec::new();
+        let result = db_client.query(query, &[&user_id]).await?;
 
-        let slf = Task::get(db_client, task.id).await?;
+        for row in result {
+            partnerships.push(row.into())
+        }
 
-        Ok(slf)
+        Ok(partnerships)
+    }
+
+    pub async fn update_status(
+        db_client: &Client,
+        id: Uuid,
+        status: RequestStatus,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let query = "UPDATE partnerships SET status = $1 WHERE id = $2 RETURNING *";
+        let partnership = db_client.query_opt(query, &[&status, &id]).await?;
+
+        Ok(partnership.map(|p| p.into()))
     }
 
     pub async fn delete(db_client: &Client, id: Uuid) -> Result<(), DatabaseError> {
-        let query = "DELETE FROM tasks WHERE id = $1";
+        let query = "DELETE FROM partnerships WHERE id = $1";
         db_client.query_opt(query, &[&id]).await?;
 
         Ok(())
     }
 
-    pub async fn attach_job(
+    // everyone who's partner in at least one accepted partnership
+    pub async fn digest_recipients(db_client: &Client) -> Result<Vec<i64>, DatabaseError> {
+        let query = "SELECT DISTINCT partner_id FROM partnerships WHERE status = 'accepted'";
+        let result = db_client.query(query, &[]).await?;
+
+        Ok(result.iter().map(|row| row.get("partner_id")).collect())
+    }
+
+    // tasks created between since and until that the partner inherited
+    // through a partnership and that are still around, grouped by their owner
+    pub async fn digest(
         db_client: &Client,
-        task_id: Uuid,
-        job_id: Uuid,
-        job_type: JobType,
-    ) -> Result<(), DatabaseError> {
-        let query = format!(
-            "UPDATE tasks SET {}_job = $1 WHERE id = $2",
-            job_type.as_str()
-        );
+        partner_id: i64,
+        since: i64,
+        until: i64,
+    ) -> Result<Vec<Task>, DatabaseError> {
+        let query = "SELECT tasks.* FROM tasks
+            JOIN task_events
+                ON task_events.task_id = tasks.id
+                AND task_events.kind = 'created'
+            WHERE task_events.created_at >= $2
+                AND task_events.created_at < $3
+                AND tasks.deleted_at IS NULL
+                AND EXISTS (
+                    SELECT 1 FROM partnerships
+                    WHERE partnerships.partner_id = $1
+                        AND partnerships.user_id = tasks.user_id
+                        AND partnerships.status = 'accepted'
+                        AND (
+                            partnerships.list_id = tasks.list_id OR
+                            partnerships.guild_id = tasks.guild_id
+                        )
+                )
+            ORDER BY tasks.user_id, tasks.due_at, tasks.title";
+        let mut tasks: Vec<Task> = Vec::new();
+        let result = db_client
+            .query(query, &[&partner_id, &since, &until])
+            .await?;
+
+        for row in result {
+            tasks.push(row.into())
+        }
+
+        Ok(tasks)
+    }
+
+    // gives a new task an accepted request for every partnership covering it,
+    // so the partner doesn't have to be asked again
+    async fn inherit(db_client: &Client, task: &Task) -> Result<(), DatabaseError> {
+        let query = "INSERT INTO
+            accountability_requests (requesting_user, requested_user, task_id, status)
+            SELECT DISTINCT user_id, partner_id, $1::uuid, 'accepted'::accepted
+            FROM partnerships
+            WHERE user_id = $2
+                AND status = 'accepted'
+                AND (list_id = $3 OR guild_id = $4)
+            ON CONFLICT DO NOTHING";
         db_client
-            .query_opt(query.as_str(), &[&job_id, &task_id])
+            .query_opt(
+                query,
+                &[&task.id, &task.user_id, &task.list_id, &task.guild_id],
+            )
             .await?;
 
         Ok(())
     }
+}
 
-    pub async fn remove_job(
-        db_client: &Client,
-        task_id: Uuid,
-        job_id: Uuid,
-        job_type: &JobType,
-    ) -> Result<(), DatabaseError> {
-        let query = format!(
-            "UPDATE tasks SET {}_job = NULL WHERE id = $1",
-            job_type.as_str()
-        );
-        db_client.query_opt(query.as_str(), &[&task_id]).await?;
+impl From<Row> for Partnership {
+    fn from(row: Row) -> Self {
+        let id = row.get("id");
+        let user_id = row.get("user_id");
+        let partner_id = row.get("partner_id");
+        let list_id = row.get("list_id");
+        let guild_id = row.get("guild_id");
+        let status = row.get("status");
+        let created_at = row.get("created_at");
 
-        let remove_job_query = "DELETE FROM job WHERE id = $1";
-        db_client.query_opt(remove_job_query, &[&job_id]).await?;
+        Partnership {
+            id,
+            user_id,
+            partner_id,
+            list_id,
+            guild_id,
+            status,
+            created_at,
+        }
+    }
+}
 
-        Ok(())
+#[derive(Debug, Clone, Copy, ToSql, FromSql, PartialEq, Eq, Serialize, Deserialize)]
+#[postgres(name = "change_kind")]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    #[postgres(name = "update")]
+    Update,
+    #[postgres(name = "delete")]
+    Delete,
+    #[postgres(name = "unpartner")]
+    Unpartner,
+}
+
+// a change to a partnered task that waits for one of its partners. due_at,
+// pester, quorum and checked are the values the owner asked for, there's at
+// most one per task and a newer request replaces the older one. an unpartner
+// change drops partner_id, or every partner if it's None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingChange {
+    pub task_id: Uuid,
+    pub requested_by: i64,
+    pub kind: ChangeKind,
+    pub due_at: Option<i64>,
+    pub pester: Option<i16>,
+    pub quorum: Quorum,
+    pub stake: i32,
+    pub checked: bool,
+    pub partner_id: Option<i64>,
+    pub created_at: i64,
+}
+
+impl PendingChange {
+    pub async fn get(db_client: &Client, task_id: Uuid) -> Result<Option<Self>, DatabaseError> {
+        let query = "SELECT * FROM pending_changes WHERE task_id = $1";
+        let change = db_client.query_opt(query, &[&task_id]).await?;
+
+        Ok(change.map(|c| c.into()))
     }
 
-    pub async fn collect_jobs(
+    // whether going from `current` to `proposed` needs the partner's approval.
+    // `proven` is whether the proposed proof was approved for this task, see
+    // Proof::approved_for
+    pub fn needed(current: &Task, proposed: &Task, proven: bool) -> bool {
+        let checked_without_proof = proposed.checked && !current.checked && !proven;
+
+        current.due_at != proposed.due_at
+            || current.pester != proposed.pester
+            || current.quorum != proposed.quorum
+            || current.stake > proposed.stake
+            || checked_without_proof
+    }
+
+    // `task` is the task as the owner wants it, only the guarded fields are kept
+    async fn propose(
         db_client: &Client,
-        task_id: Uuid,
-    ) -> Result<TaskJobs, DatabaseError> {
-        let mut result: HashMap<JobType, Option<Uuid>> = HashMap::new();
-        let query = "SELECT pester_job, reminder_job, overdue_job FROM tasks WHERE id = $1";
-        let row = db_client.query_one(query, &[&task_id]).await?;
+        task: &Task,
+        requested_by: i64,
+        kind: ChangeKind,
+        partner_id: Option<i64>,
+    ) -> Result<Self, DatabaseError> {
+        let query = "INSERT INTO
+            pending_changes (
+                task_id, requested_by, kind, due_at, pester, quorum, stake, checked, partner_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (task_id) DO UPDATE
+            SET
+                requested_by = $2,
+                kind = $3,
+                due_at = $4,
+                pester = $5,
+                quorum = $6,
+                stake = $7,
+                checked = $8,
+                partner_id = $9,
+                created_at = extract(epoch from now())::bigint
+            RETURNING *";
+        let row = db_client
+            .query_one(
+                query,
+                &[
+                    &task.id,
+                    &requested_by,
+                    &kind,
+                    &task.due_at,
+                    &task.pester,
+                    &task.quorum.unwrap_or_default(),
+                    &task.stake.unwrap_or_default(),
+                    &task.checked,
+                    &partner_id,
+                ],
+            )
+            .await?;
 
-        let pester_job: Option<Uuid> = row.get("pester_job");
-        let reminder_job: Option<Uuid> = row.get("reminder_job");
-        let overdue_job: Option<Uuid> = row.get("overdue_job");
+        Ok(row.into())
+    }
 
-        result.insert(JobType::Pester, pester_job);
-        result.insert(JobType::Reminder, reminder_job);
-        result.insert(JobType::Overdue, overdue_job);
+    // applies the change on behalf of the partner and returns it, None if
+    // there was nothing to approve. any one of the partners can approve
+    pub async fn approve(
+        db_client: &Client,
+        task_id: Uuid,
+        partner_id: i64,
+    ) -> Result<Option<Self>, DatabaseError> {
+        let change = match PendingChange::get(db_client, task_id).await? {
+            Some(change) => change,
+            None => return Ok(None),
+        };
+
+        match change.kind {
+            ChangeKind::Delete => Task::delete(db_client, task_id, Some(partner_id)).await?,
+            ChangeKind::Unpartner => match change.partner_id {
+                Some(dropped) => AccountabilityRequest::delete(db_client, task_id, dropped).await?,
+                None => AccountabilityRequest::delete_all(db_client, task_id).await?,
+            },
+            ChangeKind::Update => {
+                if let Some(task) = Task::get(db_client, task_id).await? {
+                    let approved = Task {
+                        due_at: change.due_at,
+                        pester: change.pester,
+                        quorum: Some(change.quorum),
+                        stake: Some(change.stake),
+                        checked: change.checked,
+                        ..task
+                    };
+                    Task::update(db_client, approved, Some(partner_id)).await?;
+                }
+            }
+        }
 
-        Ok(result)
+        // deleted tasks can be restored, so the request has to go either way
+        let query = "DELETE FROM pending_changes WHERE task_id = $1";
+        db_client.query_opt(query, &[&task_id]).await?;
+
+        Ok(Some(change))
     }
 
-    pub async fn collect_all_jobs(
-        db_client: &Client,
-    ) -> Result<HashMap<Uuid, TaskJobs>, DatabaseError> {
-        let mut result: HashMap<Uuid, TaskJobs> = HashMap::new();
-        let query = "SELECT 
-            id, pester_job, reminder_job, overdue_job 
-            FROM tasks
-            WHERE pester_job IS NOT NULL OR
-                  reminder_job IS NOT NULL OR
-                  overdue_job IS NOT NULL";
-        let rows = db_client.query(query, &[]).await?;
+    // drops the change and returns it, None if there was nothing to reject
+    pub async fn reject(db_client: &Client, task_id: Uuid) -> Result<Option<Self>, DatabaseError> {
+        let query = "DELETE FROM pending_changes WHERE task_id = $1 RETURNING *";
+        let change = db_client.query_opt(query, &[&task_id]).await?;
 
-        for row in rows {
-            let pester_job: Option<Uuid> = row.get("pester_job");
-            let reminder_job: Option<Uuid> = row.get("reminder_job");
-            let overdue_job: Option<Uuid> = row.get("overdue_job");
+        Ok(change.map(|c| c.into()))
+    }
+}
 
-            let jobs = HashMap::from([
-                (JobType::Pester, pester_job),
-                (JobType::Reminder, reminder_job),
-                (JobType::Overdue, overdue_job),
-            ]);
+impl From<Row> for PendingChange {
+    fn from(row: Row) -> Self {
+        let task_id = row.get("task_id");
+        let requested_by = row.get("requested_by");
+        let kind = row.get("kind");
+        let due_at = row.get("due_at");
+        let pester = row.get("pester");
+        let quorum = row.get("quorum");
+        let stake = row.get("stake");
+        let checked = row.get("checked");
+        let partner_id = row.get("partner_id");
+        let created_at = row.get("created_at");
 
-            result.insert(row.get("id"), jobs);
+        PendingChange {
+            task_id,
+            requested_by,
+            kind,
+            due_at,
+            pester,
+            quorum,
+            stake,
+            checked,
+            partner_id,
+            created_at,
         }
-
-        Ok(result)
     }
+}
 
-    async fn insert(
+#[derive(Debug, Clone, Copy, ToSql, FromSql, PartialEq, Eq, Serialize, Deserialize)]
+#[postgres(name = "points_reason")]
+#[serde(rename_all = "snake_case")]
+pub enum PointsReason {
+    #[postgres(name = "stake_lost")]
+    StakeLost,
+    #[postgres(name = "stake_won")]
+    StakeWon,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointsEntry {
+    pub id: i64,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub task_id: Option<Uuid>,
+    pub reason: PointsReason,
+    pub amount: i32,
+    pub created_at: i64,
+}
+
+impl PointsEntry {
+    // takes the stake from the owner of an overdue task and, if the task says
+    // so, splits it between its partners. the first partners get the
+    // remainder so no points are lost. returns nothing if the task has no
+    // stake or it was settled before
+    pub async fn settle(
         db_client: &Client,
-        list_id: Uuid,
-        user_id: i64,
-        guild_id: i64,
-        title: String,
-        content: Option<String>,
-        pester: Option<i16>,
-        due_at: Option<i64>,
-    ) -> Result<Row, DatabaseError> {
+        task: &Task,
+        partners: &[i64],
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let stake = task.stake.unwrap_or_default();
+
+        if stake <= 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut user_ids = vec![task.user_id];
+        let mut reasons = vec![PointsReason::StakeLost];
+        let mut amounts = vec![-stake];
+
+        if task.stake_to_partners == Some(true) && !partners.is_empty() {
+            let share = stake / partners.len() as i32;
+            let remainder = stake as usize % partners.len();
+
+            for (i, partner_id) in partners.iter().enumerate() {
+                user_ids.push(*partner_id);
+                reasons.push(PointsReason::StakeWon);
+                amounts.push(share + (i < remainder) as i32);
+            }
+        }
+
+        // a single statement, so either the whole stake is settled or none of it
         let query = "INSERT INTO
-            tasks (list_id, user_id, guild_id, title, content, pester, due_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            points_ledger (guild_id, task_id, user_id, reason, amount)
+            SELECT $1, $2, *
+            FROM unnest($3::bigint[], $4::points_reason[], $5::integer[])
+            ON CONFLICT (task_id, user_id, reason) DO NOTHING
             RETURNING *";
-        db_client
-            .query_one(
+        let result = db_client
+            .query(
                 query,
-                &[&list_id, &user_id, &guild_id, &title, &content, &pester, &due_at],
+                &[&task.guild_id, &task.id, &user_ids, &reasons, &amounts],
             )
-            .await
+            .await?;
+
+        Ok(result.into_iter().map(|row| row.into()).collect())
+    }
+
+    // the user's most recent entries in the guild, newest first
+    pub async fn get_for_user(
+        db_client: &Client,
+        guild_id: i64,
+        user_id: i64,
+        limit: i64,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT * FROM points_ledger
+            WHERE guild_id = $1 AND user_id = $2
+            ORDER BY id DESC
+            LIMIT $3";
+        let mut entries: Vec<PointsEntry> = Vec::new();
+        let result = db_client
+            .query(query, &[&guild_id, &user_id, &limit])
+            .await?;
+
+        for row in result {
+            entries.push(row.into())
+        }
+
+        Ok(entries)
     }
 }
 
-impl From<Row> for Task {
+impl From<Row> for PointsEntry {
     fn from(row: Row) -> Self {
         let id = row.get("id");
-        let list_id = row.get("list_id");
-        let user_id = row.get("user_id");
         let guild_id = row.get("guild_id");
-        let title = row.get("title");
-        let content = row.get("content");
-        let checked = row.get("checked");
-        let pester = row.get("pester");
-        let due_at = row.get("due_at");
-        let proof_id = row.get("proof_id");
-        let pester_job = row.get("pester_job");
-        let overdue_job = row.get("overdue_job");
-        let reminder_job = row.get("reminder_job");
+        let user_id = row.get("user_id");
+        let task_id = row.get("task_id");
+        let reason = row.get("reason");
+        let amount = row.get("amount");
+        let created_at = row.get("created_at");
 
-        Task {
+        PointsEntry {
             id,
-            list_id,
-            user_id,
             guild_id,
-            title,
-            content,
-            checked,
-            pester,
-            due_at,
-            proof_id,
-            pester_job,
-            overdue_job,
-            reminder_job,
+            user_id,
+            task_id,
+            reason,
+            amount,
+            created_at,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Proof {
-    pub id: Uuid,
-    pub content: Option<String>,
-    pub image: Option<String>,
-    pub approved: bool,
+pub struct Balance {
+    pub user_id: i64,
+    pub guild_id: i64,
+    pub points: i64,
 }
 
-impl Proof {
-    pub async fn new(
+impl Balance {
+    // only users with at least one entry in the ledger, highest balance first
+    pub async fn for_guild(db_client: &Client, guild_id: i64) -> Result<Vec<Self>, DatabaseError> {
+        let query = "SELECT user_id, guild_id, SUM(amount) AS points
+            FROM points_ledger
+            WHERE guild_id = $1
+            GROUP BY user_id, guild_id
+            ORDER BY points DESC, user_id";
+        let mut balances: Vec<Balance> = Vec::new();
+        let result = db_client.query(query, &[&guild_id]).await?;
+
+        for row in result {
+            balances.push(row.into())
+        }
+
+        Ok(balances)
+    }
+
+    // zero for users who never won or lost anything
+    pub async fn get(
         db_client: &Client,
-        content: Option<String>,
-        image: Option<String>,
+        guild_id: i64,
+        user_id: i64,
     ) -> Result<Self, DatabaseError> {
-        let proof = Proof::insert(db_client, content, image).await?;
+        let query = "SELECT $1::bigint AS user_id, $2::bigint AS guild_id,
+                COALESCE(SUM(amount), 0) AS points
+            FROM points_ledger
+            WHERE user_id = $1 AND guild_id = $2";
+        let row = db_client.query_one(query, &[&user_id, &guild_id]).await?;
 
-        Ok(proof.into())
+        Ok(row.into())
     }
+}
 
-    pub async fn get(db_client: &Client, id: Uuid) -> Result<Option<Self>, DatabaseError> {
-        let query = "SELECT * FROM proof WHERE id = $1";
-        let proof = db_client.query_opt(query, &[&id]).await?;
+impl From<Row> for Balance {
+    fn from(row: Row) -> Self {
+        let user_id = row.get("user_id");
+        let guild_id = row.get("guild_id");
+        let points = row.get("points");
 
-        if let Some(p) = proof {
-            Ok(Some(p.into()))
-        } else {
-            Ok(None)
+        Balance {
+            user_id,
+            guild_id,
+            points,
         }
     }
+}
 
-    pub async fn approve(db_client: &Client, id: Uuid) -> Result<(), DatabaseError> {
-        let query = "UPDATE proof SET approved = true WHERE id = $1";
-        db_client.query_opt(query, &[&id]).await?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Task,
+    List,
+    Proof,
+}
 
-        Ok(())
-    }
+// one match of a search. for proof, task_id is the task it's attached to and
+// title is that task's title. matched words are wrapped in ** in the snippet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub title: String,
+    pub snippet: String,
+    pub rank: f32,
+}
 
-    pub async fn delete(db_client: &Client, id: Uuid) -> Result<(), DatabaseError> {
-        let query = "DELETE FROM proof WHERE id = $1";
-        db_client.query_opt(query, &[&id]).await?;
+impl SearchHit {
+    // tasks, lists and proof the user can see: their own, the ones shared
+    // with them, and the tasks (with their proof) they're an accepted partner
+    // for. deleted ones are left out, archived ones are still found. best
+    // matches first. the visibility check is repeated on tasks itself rather
+    // than put in a CTE, so the text search can go through the indexes.
+    // `terms` takes the same syntax as most search engines, quotes, or and -
+    pub async fn search(
+        db_client: &Client,
+        user_id: i64,
+        terms: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let query = "WITH
+            q AS (SELECT websearch_to_tsquery('simple', $2) AS query),
+            shared AS (
+                SELECT id FROM lists
+                WHERE deleted_at IS NULL AND (user_id = $1 OR id IN (
+                    SELECT list_id FROM list_members WHERE user_id = $1
+                ))
+            ),
+            partnered AS (
+                SELECT task_id FROM accountability_requests
+                WHERE requested_user = $1 AND status = 'accepted'
+            )
+            SELECT * FROM (
+                SELECT
+                    'task' AS kind,
+                    tasks.id,
+                    tasks.id AS task_id,
+                    tasks.title,
+                    ts_headline('simple', tasks.title || ' ' || coalesce(tasks.content, ''),
+                        q.query, 'MaxFragments=1, MaxWords=20, MinWords=5, StartSel=**, StopSel=**'
+                    ) AS snippet,
+                    ts_rank(to_tsvector('simple', tasks.title || ' ' || coalesce(tasks.content, '')),
+                        q.query) AS rank
+                FROM tasks, q
+                WHERE to_tsvector('simple', tasks.title || ' ' || coalesce(tasks.content, ''))
+                    @@ q.query
+                    AND tasks.deleted_at IS NULL
+                    AND (tasks.user_id = $1
+                        OR tasks.list_id IN (SELECT id FROM shared)
+                        OR tasks.id IN (SELECT task_id FROM partnered))
+                UNION ALL
+                SELECT
+                    'list',
+                    lists.id,
+                    NULL,
+                    lists.title,
+                    ts_headline('simple', lists.title, q.query, 'StartSel=**, StopSel=**'),
+                    ts_rank(to_tsvector('simple', lists.title), q.query)
+                FROM lists, q
+                WHERE lists.id IN (SELECT id FROM shared)
+                    AND to_tsvector('simple', lists.title) @@ q.query
+                UNION ALL
+                SELECT
+                    'proof',
+                    proof.id,
+                    tasks.id,
+                    tasks.title,
+                    ts_headline('simple', coalesce(proof.content, ''),
+                        q.query, 'MaxFragments=1, MaxWords=20, MinWords=5, StartSel=**, StopSel=**'
+                    ),
+                    ts_rank(to_tsvector('simple', coalesce(proof.content, '')), q.query)
+                FROM proof
+                JOIN tasks ON tasks.proof_id = proof.id, q
+                WHERE to_tsvector('simple', coalesce(proof.content, '')) @@ q.query
+                    AND tasks.deleted_at IS NULL
+                    AND (tasks.user_id = $1
+                        OR tasks.list_id IN (SELECT id FROM shared)
+                        OR tasks.id IN (SELECT task_id FROM partnered))
+            ) hits
+            ORDER BY rank DESC, id
+            OFFSET $3
+            LIMIT $4";
+        let mut hits: Vec<SearchHit> = Vec::new();
+        let result = db_client
+            .query(query, &[&user_id, &terms, &offset, &limit])
+            .await?;
 
-        Ok(())
-    }
+        for row in result {
+            hits.push(row.into())
+        }
 
-    async fn insert(
-        db_client: &Client,
-        content: Option<String>,
-        image: Option<String>,
-    ) -> Result<Row, DatabaseError> {
-        let query = "INSERT INTO 
-            proof (content, image)
-            VALUES ($1, $2)
-            RETURNING *";
-        db_client.query_one(query, &[&content, &image]).await
+        Ok(hits)
     }
 }
 
-impl From<Row> for Proof {
+impl From<Row> for SearchHit {
     fn from(row: Row) -> Self {
+        let kind = match row.get::<_, &str>("kind") {
+            "list" => SearchKind::List,
+            "proof" => SearchKind::Proof,
+            _ => SearchKind::Task,
+        };
         let id = row.get("id");
-        let content = row.get("content");
-        let image = row.get("image");
-        let approved = row.get("approved");
+        let task_id = row.get("task_id");
+        let title = row.get("title");
+        let snippet = row.get("snippet");
+        let rank = row.get("rank");
 
-        Proof {
+        SearchHit {
+            kind,
             id,
-            content,
-            image,
-            approved,
+            task_id,
+            title,
+            snippet,
+            rank,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, ToSql, FromSql, PartialEq, Serialize, Deserialize)]
-#[postgres(name = "accepted")]
-pub enum RequestStatus {
-    #[postgres(name = "accepted")]
-    Accepted,
-    #[postgres(name = "pending")]
-    Pending,
-    #[postgres(name = "rejected")]
-    Rejected,
-}
-
+// one page of a collection, next_cursor is None on the last one. the
+// filters and sorting have to stay the same when asking for the next page
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AccountabilityRequest {
-    pub requesting_user: i64,
-    pub requested_user: i64,
-    pub task_id: Uuid,
-    pub status: RequestStatus,
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub next_cursor: Option<String>,
 }
 
-impl AccountabilityRequest {
-    pub async fn new(
-        db_client: &Client,
-        requesting_user: i64,
-        requested_user: i64,
-        task_id: Uuid,
-    ) -> Result<Self, DatabaseError> {
-        let result =
-            AccountabilityRequest::insert(db_client, requesting_user, requested_user, task_id)
-                .await?;
-
-        Ok(result.into())
-    }
+// the sort key and id of the last item on the previous page, both kept as
+// text and cast back in the query so any key type fits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    key: String,
+    id: String,
+}
 
-    pub async fn get(db_client: &Client, task_id: Uuid) -> Result<Option<Self>, DatabaseError> {
-        let query = "SELECT * FROM accountability_requests WHERE task_id = $1";
-        let result = db_client.query_opt(query, &[&task_id]).await?;
+impl Cursor {
+    // both parts have to cast back to the types the query expects, or a
+    // tampered cursor would only fail once it reaches the database
+    fn parse(cursor: &str, key_type: &str, id_type: &str) -> Option<Self> {
+        let (key, id) = cursor.rsplit_once('|')?;
 
-        if let Some(result) = result {
-            Ok(Some(result.into()))
-        } else {
-            Ok(None)
+        if !fits(key, key_type) || !fits(id, id_type) {
+            return None;
         }
-    }
-
-    pub async fn update_status(
-        db_client: &Client,
-        task_id: Uuid,
-        status: RequestStatus,
-    ) -> Result<(), DatabaseError> {
-        let query = "UPDATE accountability_requests SET status = $1 WHERE task_id = $2";
-        db_client.query_opt(query, &[&status, &task_id]).await?;
 
-        Ok(())
+        Some(Cursor {
+            key: key.to_string(),
+            id: id.to_string(),
+        })
     }
 
-    pub async fn delete(db_client: &Client, task_id: Uuid) -> Result<(), DatabaseError> {
-        let query = "DELETE FROM accountability_requests WHERE task_id = $1";
-        db_client.query_opt(query, &[&task_id]).await?;
+    fn encode(&self) -> String {
+        format!("{}|{}", self.key, self.id)
+    }
 
-        Ok(())
+    fn split(cursor: Option<&Cursor>) -> (Option<String>, Option<String>) {
+        match cursor {
+            Some(cursor) => (Some(cursor.key.clone()), Some(cursor.id.clone())),
+            None => (None, None),
+        }
     }
+}
 
-    async fn insert(
-        db_client: &Client,
-        requesting_user: i64,
-        requested_user: i64,
-        task_id: Uuid,
-    ) -> Result<Row, DatabaseError> {
-        let query = "INSERT INTO 
-            accountability_requests (requesting_user, requested_user, task_id)
-            VALUES ($1, $2, $3)
-            RETURNING *";
-        db_client
-            .query_one(query, &[&requesting_user, &requested_user, &task_id])
-            .await
+// whether the text can be cast to the sql type
+fn fits(value: &str, sql_type: &str) -> bool {
+    match sql_type {
+        "bigint" => value.parse::<i64>().is_ok(),
+        "integer" => value.parse::<i32>().is_ok(),
+        "uuid" => Uuid::parse_str(value).is_ok(),
+        _ => true,
     }
 }
 
-impl From<Row> for AccountabilityRequest {
-    fn from(row: Row) -> Self {
-        let requesting_user = row.get("requesting_user");
-        let requested_user = row.get("requested_user");
-        let task_id = row.get("task_id");
-        let status = row.get("status");
+fn direction(descending: bool) -> (&'static str, &'static str) {
+    match descending {
+        true => ("DESC", "<"),
+        false => ("ASC", ">"),
+    }
+}
 
-        AccountabilityRequest {
-            requesting_user,
-            requested_user,
-            task_id,
-            status,
+// the query has to select sort_key and page_id and ask for one row more
+// than limit, which tells whether there's another page after this one
+async fn fetch_page<T: From<Row>>(
+    db_client: &Client,
+    query: &str,
+    params: &[&(dyn ToSql + Sync)],
+    count_query: &str,
+    count_params: &[&(dyn ToSql + Sync)],
+    limit: i64,
+) -> Result<Page<T>, DatabaseError> {
+    let mut rows = db_client.query(query, params).await?;
+    let total = db_client
+        .query_one(count_query, count_params)
+        .await?
+        .get("total");
+
+    let next_cursor = match rows.len() as i64 > limit {
+        true => {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| {
+                Cursor {
+                    key: row.get("sort_key"),
+                    id: row.get("page_id"),
+                }
+                .encode()
+            })
         }
-    }
+        false => None,
+    };
+
+    Ok(Page {
+        items: rows.into_iter().map(T::from).collect(),
+        total,
+        next_cursor,
+    })
 }
 
 pub type DatabaseConnection = Connection<PgConnectionManager<NoTls>>;
@@ -923,3 +4283,278 @@ impl From<Row> for ApiKey {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::nil(),
+            "list_id": Uuid::nil(),
+            "user_id": 1,
+            "guild_id": 2,
+            "title": "title",
+            "checked": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn change_needed_for_deadlines_and_lower_stakes() {
+        let current = Task {
+            due_at: Some(1000),
+            stake: Some(10),
+            ..task()
+        };
+
+        let later = Task {
+            due_at: Some(2000),
+            ..current.clone()
+        };
+        assert!(PendingChange::needed(&current, &later, false));
+
+        let lower = Task {
+            stake: Some(5),
+            ..current.clone()
+        };
+        assert!(PendingChange::needed(&current, &lower, false));
+
+        let higher = Task {
+            stake: Some(20),
+            title: String::from("renamed"),
+            ..current.clone()
+        };
+        assert!(!PendingChange::needed(&current, &higher, false));
+    }
+
+    #[test]
+    fn change_needed_to_check_off_without_approved_proof() {
+        let current = task();
+        let checked = Task {
+            checked: true,
+            proof_id: Some(Uuid::new_v4()),
+            ..task()
+        };
+
+        assert!(PendingChange::needed(&current, &checked, false));
+        assert!(!PendingChange::needed(&current, &checked, true));
+    }
+
+    // an older client leaves the newer fields out, the update keeps them
+    #[test]
+    fn task_leaves_missing_fields_unset() {
+        let task = task();
+
+        assert_eq!(task.quorum, None);
+        assert_eq!(task.stake, None);
+        assert_eq!(task.stake_to_partners, None);
+        assert_eq!(task.auto_check, None);
+        assert_eq!(task.priority, None);
+        assert_eq!(task.tags, None);
+    }
+
+    #[test]
+    fn new_task_defaults_missing_fields() {
+        let new_task = NewTask::from(&task());
+
+        assert_eq!(new_task.quorum, Quorum::default());
+        assert_eq!(new_task.stake, 0);
+        assert!(!new_task.stake_to_partners);
+        assert!(!new_task.auto_check);
+        assert_eq!(new_task.priority, Priority::Normal);
+        assert!(new_task.tags.is_empty());
+    }
+
+    #[test]
+    fn new_task_from_task() {
+        let task = Task {
+            stake: Some(5),
+            priority: Some(Priority::High),
+            tags: Some(vec![String::from("Chores")]),
+            penalty: Some(String::new()),
+            ..task()
+        };
+
+        let new_task = NewTask::from(&task);
+        assert_eq!(new_task.stake, 5);
+        assert_eq!(new_task.priority, Priority::High);
+        assert_eq!(new_task.tags, vec![String::from("Chores")]);
+        assert_eq!(new_task.penalty, None);
+    }
+
+    #[test]
+    fn change_not_needed_for_missing_fields() {
+        let current = Task {
+            quorum: Some(Quorum::All),
+            stake: Some(10),
+            ..task()
+        };
+        // what update_guarded sends on for an older client
+        let proposed = Task {
+            quorum: task().quorum.or(current.quorum),
+            stake: task().stake.or(current.stake),
+            ..task()
+        };
+
+        assert!(!PendingChange::needed(&current, &proposed, false));
+    }
+
+    #[test]
+    fn quorum_any() {
+        assert!(!Quorum::Any.met(0, 3));
+        assert!(Quorum::Any.met(1, 3));
+    }
+
+    #[test]
+    fn quorum_majority() {
+        assert!(!Quorum::Majority.met(1, 2));
+        assert!(Quorum::Majority.met(2, 3));
+        assert!(!Quorum::Majority.met(2, 4));
+    }
+
+    #[test]
+    fn quorum_all() {
+        assert!(!Quorum::All.met(2, 3));
+        assert!(Quorum::All.met(3, 3));
+    }
+
+    #[test]
+    fn quorum_without_partners() {
+        assert!(Quorum::Any.met(0, 0));
+        assert!(Quorum::Majority.met(0, 0));
+        assert!(Quorum::All.met(0, 0));
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let id = Uuid::new_v4();
+        let cursor = Cursor::parse(&format!("a|b|{}", id), "text", "uuid").unwrap();
+        assert_eq!(cursor.key, "a|b");
+        let encoded = cursor.encode();
+        assert_eq!(Cursor::parse(&encoded, "text", "uuid"), Some(cursor));
+    }
+
+    #[test]
+    fn cursor_rejects_tampering() {
+        let id = Uuid::new_v4();
+        assert_eq!(Cursor::parse("no separator", "text", "uuid"), None);
+        assert_eq!(Cursor::parse("key|", "text", "uuid"), None);
+        assert_eq!(Cursor::parse("key|42", "text", "uuid"), None);
+        assert!(Cursor::parse(&format!("soon|{}", id), "bigint", "uuid").is_none());
+        assert_eq!(Cursor::parse("99999999999|1", "integer", "bigint"), None);
+        assert!(Cursor::parse(&format!("-5|{}", id), "bigint", "uuid").is_some());
+    }
+
+    #[test]
+    fn cursor_follows_the_sort() {
+        let id = Uuid::new_v4();
+        let filter = TaskFilter {
+            sort: TaskSort::Due,
+            ..TaskFilter::default()
+        };
+        assert!(filter.cursor(&format!("1700000000|{}", id)).is_some());
+        assert!(filter.cursor(&format!("chores|{}", id)).is_none());
+
+        let filter = TaskFilter {
+            sort: TaskSort::Tag,
+            ..TaskFilter::default()
+        };
+        assert!(filter.cursor(&format!("chores|{}", id)).is_some());
+
+        let filter = UserFilter::default();
+        assert!(filter.cursor("name|1234").is_some());
+        assert!(filter.cursor(&format!("name|{}", id)).is_none());
+        assert!(List::cursor(&format!("groceries|{}", id)).is_some());
+    }
+
+    #[test]
+    fn habit_stats_streaks() {
+        let stats = HabitStats::from_outcomes([
+            (true, Some(-60)),
+            (true, Some(0)),
+            (true, Some(120)),
+            (true, Some(-30)),
+            (false, None),
+            (true, Some(-10)),
+            (true, Some(-20)),
+        ]);
+
+        assert_eq!(stats.on_time, 5);
+        assert_eq!(stats.late, 1);
+        assert_eq!(stats.missed, 1);
+        assert_eq!(stats.current_streak, 2);
+        assert_eq!(stats.longest_streak, 2);
+        assert_eq!(stats.average_lateness, Some(0));
+    }
+
+    #[test]
+    fn habit_stats_skip_unrecorded_completions() {
+        let stats = HabitStats::from_outcomes([(true, None), (true, Some(-90))]);
+
+        assert_eq!(stats.on_time, 1);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.average_lateness, Some(-90));
+    }
+
+    #[test]
+    fn habit_stats_without_completions() {
+        let stats = HabitStats::from_outcomes([(false, None)]);
+
+        assert_eq!(stats.missed, 1);
+        assert_eq!(stats.average_lateness, None);
+    }
+
+    // wednesday, 2024-03-06 12:00 UTC
+    const NOW: i64 = 1709726400;
+    const TODAY: i64 = 1709683200;
+    const MONDAY: i64 = 1709510400;
+
+    #[test]
+    fn within_overdue() {
+        let filter = TaskFilter::default().within(DueWindow::Overdue, NOW);
+
+        assert_eq!(filter.checked, Some(false));
+        assert_eq!(filter.due_after, None);
+        assert_eq!(filter.due_before, Some(NOW - 1));
+    }
+
+    #[test]
+    fn within_today() {
+        let filter = TaskFilter::default().within(DueWindow::Today, NOW);
+
+        assert_eq!(filter.due_after, Some(TODAY - 1));
+        assert_eq!(filter.due_before, Some(TODAY + DAY - 1));
+    }
+
+    #[test]
+    fn within_week_starts_on_monday() {
+        let filter = TaskFilter::default().within(DueWindow::Week, NOW);
+        assert_eq!(filter.due_after, Some(MONDAY - 1));
+        assert_eq!(filter.due_before, Some(MONDAY + 7 * DAY - 1));
+
+        let filter = TaskFilter::default().within(DueWindow::Week, MONDAY);
+        assert_eq!(filter.due_after, Some(MONDAY - 1));
+    }
+
+    #[test]
+    fn within_keeps_the_narrower_bounds() {
+        let filter = TaskFilter {
+            due_after: Some(TODAY + 60),
+            due_before: Some(TODAY + 120),
+            checked: Some(true),
+            ..TaskFilter::default()
+        }
+        .within(DueWindow::Week, NOW);
+
+        assert_eq!(filter.checked, Some(false));
+        assert_eq!(filter.due_after, Some(TODAY + 60));
+        assert_eq!(filter.due_before, Some(TODAY + 120));
+    }
+
+    #[test]
+    fn normalize_tags_sorts_and_dedups() {
+        let tags = ["  Work", "chores", "work", ""].map(String::from);
+        assert_eq!(normalize_tags(&tags), vec!["chores", "work"]);
+    }
+}
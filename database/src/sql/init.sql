@@ -17,12 +17,49 @@ CREATE TABLE IF NOT EXISTS job (
 );
 
 
+CREATE TYPE personality AS ENUM (
+    'default',
+    'gentle_coach',
+    'drill_sergeant',
+    'passive_aggressive'
+);
+
 -- minimal reference to a Discord server (aka guild)
 CREATE TABLE IF NOT EXISTS guilds (
     id      BIGINT PRIMARY KEY, -- should accomodate Discord snowflakes
     name    VARCHAR(100) NOT NULL,
     icon    TEXT,
-    send_to BIGINT -- the channel which the bot will send messages to
+    send_to BIGINT, -- the channel which the bot will send messages to
+    shame_role      BIGINT, -- role given to users with overdue tasks, disabled if null
+    shame_prefix    VARCHAR(16), -- prepended to the nickname of shamed users, disabled if null
+    task_threads    BOOLEAN DEFAULT false, -- whether each task gets its own thread in send_to
+    personality     personality NOT NULL DEFAULT 'default', -- preset used for messages without a custom template
+    locale          VARCHAR(10) -- language tag for messages, taken from Discord if not set explicitly
+);
+
+CREATE TYPE notification_kind AS ENUM (
+    'reminder',
+    'pester',
+    'overdue',
+    'completion',
+    'leaderboard',
+    'accountability'
+);
+
+-- per-guild overrides of send_to for each kind of message the bot sends
+CREATE TABLE IF NOT EXISTS guild_channels (
+    guild_id    BIGINT REFERENCES guilds (id) ON DELETE CASCADE,
+    kind        notification_kind NOT NULL,
+    channel_id  BIGINT NOT NULL,
+    CONSTRAINT guild_channels_pkey PRIMARY KEY (guild_id, kind)
+);
+
+-- per-guild wording for the messages the bot sends, overriding the personality preset
+CREATE TABLE IF NOT EXISTS message_templates (
+    guild_id    BIGINT REFERENCES guilds (id) ON DELETE CASCADE,
+    kind        notification_kind NOT NULL,
+    template    TEXT NOT NULL,
+    CONSTRAINT message_templates_pkey PRIMARY KEY (guild_id, kind)
 );
 
 -- minimal reference to a Discord user (which function as Shamebot users)
@@ -30,7 +67,18 @@ CREATE TABLE IF NOT EXISTS users (
     id              BIGINT PRIMARY KEY,
     username        VARCHAR(32) NOT NULL,
     discriminator   VARCHAR(4) NOT NULL,
-    avatar_hash     TEXT NOT NULL
+    avatar_hash     TEXT NOT NULL,
+    locale          VARCHAR(10) -- language tag for messages, taken from Discord if not set explicitly
+);
+
+CREATE TYPE delivery AS ENUM ('channel', 'dm', 'both');
+
+-- where users want each kind of message about their tasks, the guild channel if missing
+CREATE TABLE IF NOT EXISTS delivery_preferences (
+    user_id     BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    kind        notification_kind NOT NULL,
+    delivery    delivery NOT NULL,
+    CONSTRAINT delivery_preferences_pkey PRIMARY KEY (user_id, kind)
 );
 
 -- many-to-many relationship between users and guilds
@@ -69,9 +117,53 @@ CREATE TABLE IF NOT EXISTS proof (
 CREATE TABLE IF NOT EXISTS lists (
     id          uuid PRIMARY KEY DEFAULT gen_random_uuid (),
     title       VARCHAR(80) NOT NULL,
-    user_id     BIGINT REFERENCES users (id) ON DELETE CASCADE
+    user_id     BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    archived_at BIGINT, -- hidden from collections unless asked for
+    deleted_at  BIGINT -- gone for everything but statistics until it's purged
+);
+
+CREATE TYPE list_role AS ENUM ('owner', 'editor', 'viewer');
+
+-- who else a list is shared with. lists.user_id is always an owner and has
+-- no row here, tasks of a shared list can be assigned to any of its members
+CREATE TABLE IF NOT EXISTS list_members (
+    list_id     uuid REFERENCES lists (id) ON DELETE CASCADE,
+    user_id     BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    role        list_role NOT NULL DEFAULT 'viewer',
+    added_at    BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint,
+    PRIMARY KEY (list_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS list_members_user_idx ON list_members (user_id);
+
+-- reusable lists. instantiating one creates a list with a task for every
+-- template task, due at the chosen start date plus its offset
+CREATE TABLE IF NOT EXISTS list_templates (
+    id          uuid PRIMARY KEY DEFAULT gen_random_uuid (),
+    title       VARCHAR(80) NOT NULL,
+    user_id     BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    pester      SMALLINT, -- for template tasks that don't set their own
+    created_at  BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint
+);
+
+CREATE TABLE IF NOT EXISTS template_tasks (
+    id          uuid PRIMARY KEY DEFAULT gen_random_uuid (),
+    template_id uuid REFERENCES list_templates (id) ON DELETE CASCADE,
+    title       VARCHAR(80) NOT NULL,
+    content     TEXT,
+    due_offset  BIGINT CHECK (due_offset >= 0), -- seconds after the start date, NULL for no due date
+    pester      SMALLINT,
+    position    INTEGER NOT NULL
 );
 
+CREATE INDEX IF NOT EXISTS template_tasks_template_idx ON template_tasks (template_id, position);
+
+-- how many of a task's accepted partners have to approve its proof
+CREATE TYPE quorum AS ENUM ('any', 'majority', 'all');
+
+-- in increasing order, so that sorting by priority puts urgent tasks last
+CREATE TYPE priority AS ENUM ('low', 'normal', 'high', 'urgent');
+
 CREATE TABLE IF NOT EXISTS tasks (
     id              uuid PRIMARY KEY DEFAULT gen_random_uuid (),
     list_id         uuid REFERENCES lists (id) ON DELETE CASCADE,
@@ -85,9 +177,79 @@ CREATE TABLE IF NOT EXISTS tasks (
     proof_id        uuid REFERENCES proof (id),
     pester_job      uuid REFERENCES job(id),
     overdue_job     uuid REFERENCES job(id),
-    reminder_job    uuid REFERENCES job(id)
+    reminder_job    uuid REFERENCES job(id),
+    thread_id       BIGINT, -- created on the first message if the guild has task_threads enabled
+    checked_at      BIGINT, -- UNIX timestamp of the last time the task was checked off
+    quorum          quorum NOT NULL DEFAULT 'any',
+    stake           INTEGER NOT NULL DEFAULT 0 CHECK (stake >= 0), -- points lost if the task goes overdue
+    penalty         TEXT, -- pledged by the owner, shown when the task goes overdue
+    stake_to_partners   BOOLEAN NOT NULL DEFAULT false, -- whether the partners win the stake
+    auto_check      BOOLEAN NOT NULL DEFAULT false, -- checked off once all of its items are
+    priority        priority NOT NULL DEFAULT 'normal',
+    tags            TEXT[] NOT NULL DEFAULT '{}', -- lowercase and sorted, see Task::update
+    archived_at     BIGINT, -- like for lists
+    deleted_at      BIGINT, -- like for lists, set to the list's when the whole list is deleted
+    -- seconds between the due date and checking the task off, negative if early
+    lateness        BIGINT GENERATED ALWAYS AS (
+        CASE WHEN due_at > 0 THEN checked_at - due_at END
+    ) STORED
 );
 
+CREATE INDEX IF NOT EXISTS tasks_tags_idx ON tasks USING GIN (tags);
+
+-- checklist of a task, in order of position
+CREATE TABLE IF NOT EXISTS task_items (
+    id              uuid PRIMARY KEY DEFAULT gen_random_uuid (),
+    task_id         uuid REFERENCES tasks (id) ON DELETE CASCADE,
+    title           VARCHAR(80) NOT NULL,
+    position        INTEGER NOT NULL,
+    checked         BOOLEAN NOT NULL DEFAULT false,
+    checked_at      BIGINT -- UNIX timestamp of the last time the item was checked off
+);
+
+CREATE INDEX IF NOT EXISTS task_items_task_idx ON task_items (task_id, position);
+
+-- task_id can't be worked on before depends_on is done. cycles are refused
+-- when an edge is added, a task is blocked while any of its edges lead to a
+-- task that isn't checked and has no approved proof
+CREATE TABLE IF NOT EXISTS task_dependencies (
+    task_id         uuid REFERENCES tasks (id) ON DELETE CASCADE,
+    depends_on      uuid REFERENCES tasks (id) ON DELETE CASCADE,
+    created_at      BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint,
+    CONSTRAINT task_dependency_pk PRIMARY KEY (task_id, depends_on),
+    CONSTRAINT task_dependency_self CHECK (task_id <> depends_on)
+);
+
+CREATE INDEX IF NOT EXISTS task_dependencies_depends_on_idx ON task_dependencies (depends_on);
+
+CREATE TYPE task_event_kind AS ENUM (
+    'created',
+    'edited',
+    'checked',
+    'unchecked',
+    'due_changed',
+    'proof_attached',
+    'proof_approved',
+    'job_fired',
+    'assigned',
+    'archived',
+    'unarchived',
+    'deleted',
+    'restored'
+);
+
+-- append-only, rows are never updated and only go away with their task
+CREATE TABLE IF NOT EXISTS task_events (
+    id              BIGSERIAL PRIMARY KEY,
+    task_id         uuid REFERENCES tasks (id) ON DELETE CASCADE,
+    kind            task_event_kind NOT NULL,
+    actor           BIGINT REFERENCES users (id) ON DELETE SET NULL, -- NULL for the bot itself
+    details         JSONB, -- changed fields as {"field": [old, new]}, the job that fired, or the list
+    created_at      BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint
+);
+
+CREATE INDEX IF NOT EXISTS task_events_task_idx ON task_events (task_id, id);
+
 CREATE TYPE accepted AS ENUM ('accepted', 'pending', 'rejected');
 
 CREATE TABLE IF NOT EXISTS accountability_requests (
@@ -97,3 +259,84 @@ CREATE TABLE IF NOT EXISTS accountability_requests (
     status              accepted DEFAULT 'pending',
     CONSTRAINT accountability_request_pk PRIMARY KEY (requested_user, task_id)
 );
+
+-- a standing partnership covers every task of one list, or every task of the
+-- user in one guild. tasks created while it's accepted get an accepted
+-- accountability request for the partner, who hears about them in a digest
+CREATE TABLE IF NOT EXISTS partnerships (
+    id              uuid PRIMARY KEY DEFAULT gen_random_uuid (),
+    user_id         BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    partner_id      BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    list_id         uuid REFERENCES lists (id) ON DELETE CASCADE,
+    guild_id        BIGINT REFERENCES guilds (id) ON DELETE CASCADE,
+    status          accepted NOT NULL DEFAULT 'pending',
+    created_at      BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint,
+    CONSTRAINT partnership_scope CHECK ((list_id IS NULL) <> (guild_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS partnerships_list_idx
+    ON partnerships (user_id, partner_id, list_id) WHERE list_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS partnerships_guild_idx
+    ON partnerships (user_id, partner_id, guild_id) WHERE guild_id IS NOT NULL;
+
+-- one row per partner who approved the proof, the proof itself is marked as
+-- approved once the task's quorum is reached
+CREATE TABLE IF NOT EXISTS proof_approvals (
+    proof_id        uuid REFERENCES proof (id) ON DELETE CASCADE,
+    user_id         BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    created_at      BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint,
+    CONSTRAINT proof_approval_pk PRIMARY KEY (proof_id, user_id)
+);
+
+CREATE TYPE change_kind AS ENUM ('update', 'delete', 'unpartner');
+
+-- changes to a task with accepted partners that wait for one of them to
+-- approve. due_at, pester, quorum, stake and checked hold the requested values
+CREATE TABLE IF NOT EXISTS pending_changes (
+    task_id         uuid PRIMARY KEY REFERENCES tasks (id) ON DELETE CASCADE,
+    requested_by    BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    kind            change_kind NOT NULL,
+    due_at          BIGINT,
+    pester          SMALLINT,
+    quorum          quorum NOT NULL DEFAULT 'any',
+    stake           INTEGER NOT NULL DEFAULT 0,
+    checked         BOOLEAN NOT NULL DEFAULT false,
+    partner_id      BIGINT REFERENCES users (id) ON DELETE CASCADE, -- dropped by an unpartner change, all of them if null
+    created_at      BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint
+);
+
+CREATE TYPE points_reason AS ENUM ('stake_lost', 'stake_won');
+
+-- every change to a user's points in a guild, balances are the sum of the
+-- amounts. a task's stake is settled once, rows outlive the task
+CREATE TABLE IF NOT EXISTS points_ledger (
+    id              BIGSERIAL PRIMARY KEY,
+    guild_id        BIGINT REFERENCES guilds (id) ON DELETE CASCADE,
+    user_id         BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    task_id         uuid REFERENCES tasks (id) ON DELETE SET NULL,
+    reason          points_reason NOT NULL,
+    amount          INTEGER NOT NULL, -- negative for lost stakes
+    created_at      BIGINT NOT NULL DEFAULT extract(epoch from now())::bigint
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS points_ledger_task_idx ON points_ledger (task_id, user_id, reason);
+CREATE INDEX IF NOT EXISTS points_ledger_guild_idx ON points_ledger (guild_id, user_id);
+
+-- full-text search, see SearchHit::search. queries have to use the exact same
+-- expressions for the indexes to be picked up. the simple configuration
+-- doesn't stem, since tasks are written in whatever language their owner uses
+CREATE INDEX IF NOT EXISTS tasks_search_idx ON tasks USING GIN (
+    to_tsvector('simple', title || ' ' || coalesce(content, ''))
+);
+CREATE INDEX IF NOT EXISTS lists_search_idx ON lists USING GIN (
+    to_tsvector('simple', title)
+);
+CREATE INDEX IF NOT EXISTS proof_search_idx ON proof USING GIN (
+    to_tsvector('simple', coalesce(content, ''))
+);
+-- proof matches are joined back to their task
+CREATE INDEX IF NOT EXISTS tasks_proof_idx ON tasks (proof_id);
+
+-- for the retention job, see List::purge and Task::purge
+CREATE INDEX IF NOT EXISTS lists_deleted_idx ON lists (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS tasks_deleted_idx ON tasks (deleted_at) WHERE deleted_at IS NOT NULL;
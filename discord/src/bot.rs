@@ -1,73 +1,132 @@
 // the bot will not always be listening, but is the only way to
 // hit Discord's API
+use std::sync::Arc;
+
+use chrono::Utc;
 use database::prelude::{
-    AccountabilityRequest, Client as DbClient, Guild, List, RequestStatus, Task,
+    AccountabilityRequest, ChangeKind, Client as DbClient, Delivery, DeliveryPreference, Guild,
+    HabitStats, LeaderboardEntry, List, NotificationKind, Page, Partnership, PendingChange,
+    PointsEntry, PointsReason, Progress, Proof, Quorum, Task, TaskDependency, TaskFilter, TaskItem,
 };
-use log::{error, info};
+use log::{debug, error, info};
 pub use serenity::{
     async_trait,
+    http::Http,
     model::{
+        application::{component::ButtonStyle, interaction::Interaction},
         prelude::{ChannelId, ChannelType, GuildId, GuildChannel, Member, PrivateChannel, Ready, UserId},
         user::User,
     },
     prelude::*,
+    CacheAndHttp,
 };
 use uuid::Uuid;
 
+use crate::channels::notification_channel;
+use crate::commands::{
+    self, ACCEPT_PARTNERSHIP, APPROVE_CHANGE, REJECT_CHANGE, REJECT_PARTNERSHIP,
+};
 use crate::environment::Env;
+use crate::locale::{self, Locale, Text};
+use crate::shame;
+use crate::templates::{self, Placeholders};
+use crate::threads;
+
+const LEADERBOARD_SIZE: usize = 10;
+// keeps the digest well within the embed description limit
+const DIGEST_SIZE: usize = 25;
 
-struct Handler;
+struct Handler {
+    db_client: DbClient,
+    env: Env,
+}
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn ready(&self, _: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         info!("connected as {}", ready.user.name);
+
+        commands::register(&ctx).await;
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        commands::handle(&ctx, interaction, &self.db_client, &self.env).await;
     }
 }
 
 pub struct Bot {
-    client: Client,
+    cache_and_http: Arc<CacheAndHttp>,
     db_client: DbClient,
     env: Env,
 }
 
 impl Bot {
     pub async fn new() -> Self {
+        let (bot, _) = Bot::connect(DbClient::new().await).await;
+        bot
+    }
+
+    // the returned client is the bot's gateway connection, it only has to be
+    // started where slash commands and other interactions should come in
+    pub async fn connect(db_client: DbClient) -> (Self, Client) {
         let env = Env::new();
         let intents = GatewayIntents::GUILD_WEBHOOKS
             | GatewayIntents::GUILD_MESSAGES
             | GatewayIntents::DIRECT_MESSAGES;
+        let handler = Handler {
+            db_client: db_client.clone(),
+            env: env.clone(),
+        };
         let client = Client::builder(&env.discord_token, intents)
-            .event_handler(Handler)
+            .event_handler(handler)
             .await
             .map_err(|e| error!("{:?}", e))
             .unwrap();
 
-        let db_client = DbClient::new().await;
-
-        Bot {
-            client,
+        let bot = Bot {
+            cache_and_http: Arc::clone(&client.cache_and_http),
             db_client,
             env,
-        }
-    }
+        };
 
-    pub async fn start(&mut self) {
-        if let Err(err) = self.client.start().await {
-            error!("{:?}", err);
-        }
+        (bot, client)
     }
 
-    pub async fn get_guild_members(&self, guild_id: u64) -> Result<Option<Vec<Member>>, SerenityError> {
-        let http = self.client.cache_and_http.http.as_ref();
-        GuildId(guild_id)
-            .members(http, None, None)
-            .await
-            .map(Some)
+    // Discord hands members out ordered by user id, so the last id on a page
+    // is the cursor for the next one. the total is only approximate
+    pub async fn get_guild_members(
+        &self,
+        guild_id: u64,
+        limit: u64,
+        after: Option<u64>,
+    ) -> Result<Option<Page<Member>>, SerenityError> {
+        let http = self.cache_and_http.http.as_ref();
+        let mut members = GuildId(guild_id)
+            .members(http, Some(limit + 1), after.map(UserId))
+            .await?;
+        let total = GuildId(guild_id)
+            .to_partial_guild_with_counts(http)
+            .await?
+            .approximate_member_count
+            .unwrap_or_default() as i64;
+
+        let next_cursor = match members.len() as u64 > limit {
+            true => {
+                members.truncate(limit as usize);
+                members.last().map(|member| member.user.id.0.to_string())
+            }
+            false => None,
+        };
+
+        Ok(Some(Page {
+            items: members,
+            total,
+            next_cursor,
+        }))
     }
 
     pub async fn get_text_channels(&self, guild_id: u64) -> Result<Option<Vec<GuildChannel>>, SerenityError> {
-        let http = self.client.cache_and_http.http.as_ref();
+        let http = self.cache_and_http.http.as_ref();
         let all_channels = GuildId(guild_id)
             .channels(http)
             .await
@@ -88,7 +147,7 @@ impl Bot {
     }
 
     pub async fn get_user(&self, user_id: u64) -> Option<User> {
-        let http = self.client.cache_and_http.http.as_ref();
+        let http = self.cache_and_http.http.as_ref();
         UserId(user_id)
             .to_user(http)
             .await
@@ -97,7 +156,7 @@ impl Bot {
     }
 
     pub async fn create_dm(&self, user_id: u64) -> Option<PrivateChannel> {
-        let http = self.client.cache_and_http.http.as_ref();
+        let http = self.cache_and_http.http.as_ref();
         UserId(user_id)
             .create_dm_channel(http)
             .await
@@ -106,21 +165,28 @@ impl Bot {
     }
 
     pub async fn send_dm(&self, user_id: u64, message: String) {
-        let http = self.client.cache_and_http.http.as_ref();
-        let channel = self.create_dm(user_id).await;
+        let http = self.cache_and_http.http.as_ref();
+        send_dm(http, user_id, message).await;
+    }
 
-        if let Some(channel) = channel {
-            channel
-                .send_message(http, |m| m.content(message))
-                .await
-                .map_err(|e| error!("{:?}", e))
-                .ok();
-        }
+    pub async fn check_shame_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+        prefix: bool,
+    ) -> Result<(), String> {
+        let http = self.cache_and_http.http.as_ref();
+        shame::check_role(http, guild_id, role_id, prefix).await
+    }
+
+    pub async fn lift_shame(&self, user_id: i64, guild_id: i64) {
+        let http = self.cache_and_http.http.as_ref();
+        shame::lift(http, &self.db_client, user_id, guild_id).await;
     }
 
     pub async fn send_accountability_request(&self, request: &Option<AccountabilityRequest>) {
         let r = request.as_ref().unwrap();
-        let http = self.client.cache_and_http.http.as_ref();
+        let http = self.cache_and_http.http.as_ref();
         let channel = self.create_dm(r.requested_user as u64).await;
 
         let task = Task::get(&self.db_client, r.task_id)
@@ -130,15 +196,18 @@ impl Bot {
             .unwrap();
 
         if let (Some(task), Some(channel)) = (task, channel) {
+            let locale = locale::for_user(&self.db_client, r.requested_user, None).await;
+            let requesting_user = format!("<@{:?}>", r.requesting_user);
             channel
                 .send_message(http, |m| {
                     m.embed(|emb| {
-                        emb.title("Accountability Request")
-                            .description(format!(
-                                "<@{:?}> has requested you as an accountability partner.",
-                                r.requesting_user
+                        emb.title(locale::text(locale, Text::AccountabilityRequestTitle))
+                            .description(locale::format(
+                                locale,
+                                Text::AccountabilityRequestDescription,
+                                &[("user", &requesting_user)],
                             ))
-                            .field("Task", task.title, false)
+                            .field(locale::text(locale, Text::TaskField), task.title, false)
                             .url(format!(
                                 "{}/accountability?task={:?}",
                                 self.env.shamebot_url, task.id,
@@ -151,36 +220,132 @@ impl Bot {
         }
     }
 
-    pub async fn send_task(&self, task_id: Uuid, guild_id: i64) {
-        let task = Task::get(&self.db_client, task_id)
+    pub async fn send_change_request(&self, change: &PendingChange) {
+        post_change_request(self.cache_and_http.http.as_ref(), &self.db_client, change).await;
+    }
+
+    pub async fn send_partnership_request(&self, partnership: &Partnership) {
+        let http = self.cache_and_http.http.as_ref();
+        let channel = match self.create_dm(partnership.partner_id as u64).await {
+            Some(channel) => channel,
+            None => return,
+        };
+
+        let locale = locale::for_user(&self.db_client, partnership.partner_id, None).await;
+        let user = format!("<@{:?}>", partnership.user_id);
+
+        // the scope is named by the list's title or the guild's name
+        let description = match (partnership.list_id, partnership.guild_id) {
+            (Some(list_id), _) => List::get(&self.db_client, list_id)
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .ok()
+                .flatten()
+                .map(|list| {
+                    locale::format(
+                        locale,
+                        Text::PartnershipListDescription,
+                        &[("user", &user), ("list", &list.title)],
+                    )
+                }),
+            (None, Some(guild_id)) => Guild::get(&self.db_client, guild_id)
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .ok()
+                .flatten()
+                .map(|guild| {
+                    locale::format(
+                        locale,
+                        Text::PartnershipGuildDescription,
+                        &[("user", &user), ("guild", &guild.name)],
+                    )
+                }),
+            (None, None) => None,
+        };
+
+        let description = match description {
+            Some(description) => description,
+            None => return,
+        };
+
+        channel
+            .send_message(http, |m| {
+                m.embed(|emb| {
+                    emb.title(locale::text(locale, Text::PartnershipRequestTitle))
+                        .description(description)
+                })
+                .components(|c| {
+                    c.create_action_row(|row| {
+                        row.create_button(|b| {
+                            b.custom_id(format!("{}:{}", ACCEPT_PARTNERSHIP, partnership.id))
+                                .label(locale::text(locale, Text::AcceptButton))
+                                .style(ButtonStyle::Success)
+                        })
+                        .create_button(|b| {
+                            b.custom_id(format!("{}:{}", REJECT_PARTNERSHIP, partnership.id))
+                                .label(locale::text(locale, Text::RejectButton))
+                                .style(ButtonStyle::Danger)
+                        })
+                    })
+                })
+            })
             .await
             .map_err(|e| error!("{:?}", e))
-            .ok()
-            .unwrap();
+            .ok();
+    }
 
-        let guild = Guild::get(&self.db_client, guild_id)
+    // one DM with every task the partner inherited between since and until,
+    // nothing is sent if there weren't any
+    pub async fn send_partner_digest(&self, partner_id: i64, since: i64, until: i64) {
+        let tasks = Partnership::digest(&self.db_client, partner_id, since, until)
             .await
             .map_err(|e| error!("{:?}", e))
-            .ok()
-            .unwrap();
+            .unwrap_or_default();
 
-        if let (Some(task), Some(guild)) = (task, guild) {
-            let checkbox = match task.checked {
-                true => ":white_check_mark:",
-                false => ":white_large_square:",
-            };
-            let owner = format!("for <@{:?}>", task.user_id);
-            let mut desc = String::new();
+        if tasks.is_empty() {
+            return;
+        }
 
-            if let Some(content) = task.content {
-                desc = format!("{}\n", content);
+        let locale = locale::for_user(&self.db_client, partner_id, None).await;
+        let mut digest = String::new();
+        let mut owner = None;
+
+        // tasks come sorted by owner, each owner gets a heading
+        for task in tasks.iter().take(DIGEST_SIZE) {
+            if owner != Some(task.user_id) {
+                owner = Some(task.user_id);
+                digest = format!("{}\n<@{:?}>\n", digest, task.user_id);
             }
-            desc = format!("{}Finished: {}\n\n{}", desc, &checkbox, &owner);
-            let url = format!("{}/tasks/{}", self.env.shamebot_url, task.id);
-            let channel_id = guild.send_to.unwrap_or_default();
-            ChannelId(channel_id as u64)
-                .send_message(self.client.cache_and_http.http.as_ref(), |m| {
-                    m.embed(|emb| emb.title(task.title).description(desc).url(url))
+
+            let line = match task.due_at {
+                Some(due_at) if due_at > 0 => locale::format(
+                    locale,
+                    Text::DigestEntry,
+                    &[("task", &task.title), ("due", &format!("<t:{:?}>", due_at))],
+                ),
+                _ => locale::format(locale, Text::DigestEntryNoDue, &[("task", &task.title)]),
+            };
+
+            digest = format!("{}{}\n", digest, line);
+        }
+
+        if tasks.len() > DIGEST_SIZE {
+            let more = (tasks.len() - DIGEST_SIZE).to_string();
+            digest = format!(
+                "{}\n{}",
+                digest,
+                locale::format(locale, Text::DigestMore, &[("n", &more)])
+            );
+        }
+
+        if let Some(channel) = self.create_dm(partner_id as u64).await {
+            let http = self.cache_and_http.http.as_ref();
+            channel
+                .send_message(http, |m| {
+                    m.embed(|emb| {
+                        emb.title(locale::text(locale, Text::DigestTitle))
+                            .description(digest.trim())
+                    })
                 })
                 .await
                 .map_err(|e| error!("{:?}", e))
@@ -188,59 +353,165 @@ impl Bot {
         }
     }
 
+    pub async fn send_task(&self, task_id: Uuid, guild_id: i64, kind: Option<NotificationKind>) {
+        post_task(
+            self.cache_and_http.http.as_ref(),
+            &self.db_client,
+            &self.env.shamebot_url,
+            task_id,
+            guild_id,
+            kind,
+        )
+        .await;
+    }
+
     pub async fn send_list(&self, list_id: Uuid, guild_id: i64) {
-        let list = List::get(&self.db_client, list_id)
+        post_list(
+            self.cache_and_http.http.as_ref(),
+            &self.db_client,
+            &self.env.shamebot_url,
+            list_id,
+            guild_id,
+        )
+        .await;
+    }
+
+    pub async fn send_completion(&self, task_id: Uuid) {
+        post_completion(self.cache_and_http.http.as_ref(), &self.db_client, task_id).await;
+    }
+
+    pub async fn send_proof(&self, task_id: Uuid) {
+        let task = Task::get(&self.db_client, task_id)
             .await
             .map_err(|e| error!("{:?}", e))
             .ok()
-            .unwrap();
+            .flatten();
 
-        let tasks = List::get_tasks(&self.db_client, list_id)
-            .await
-            .map_err(|e| error!("{:?}", e))
-            .ok();
+        let task = match task {
+            Some(task) => task,
+            None => return,
+        };
 
-        let guild = Guild::get(&self.db_client, guild_id)
+        let proof = match task.proof_id {
+            Some(proof_id) => Proof::get(&self.db_client, proof_id)
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .ok()
+                .flatten(),
+            None => None,
+        };
+
+        let guild = Guild::get(&self.db_client, task.guild_id)
             .await
             .map_err(|e| error!("{:?}", e))
             .ok()
-            .unwrap();
+            .flatten();
 
-        if let (Some(list), Some(tasks), Some(guild)) = (list, tasks, guild) {
-            let owner = format!("for <@{:?}>", list.user_id);
-            let url = format!("{}/lists/{}", self.env.shamebot_url, list.id);
-            let channel_id = guild.send_to.unwrap_or_default();
-            ChannelId(channel_id as u64)
-                .send_message(self.client.cache_and_http.http.as_ref(), |m| {
+        if let (Some(proof), Some(guild)) = (proof, guild) {
+            let http = self.cache_and_http.http.as_ref();
+            let kind = Some(NotificationKind::Accountability);
+            let locale = locale::for_user(&self.db_client, task.user_id, Some(&guild)).await;
+            let owner = format!("<@{:?}>", task.user_id);
+            let content = proof.content.unwrap_or_default();
+            let channel_id =
+                threads::task_channel(http, &self.db_client, &guild, &task, kind).await;
+            channel_id
+                .send_message(http, |m| {
                     m.embed(|emb| {
-                        emb.title(list.title);
+                        emb.title(locale::format(
+                            locale,
+                            Text::ProofTitle,
+                            &[("task", &task.title)],
+                        ))
+                        .description(locale::format(
+                            locale,
+                            Text::ProofDescription,
+                            &[("user", &owner), ("content", &content)],
+                        ));
 
-                        for task in tasks {
-                            let checkbox = match task.checked {
-                                true => ":white_check_mark:",
-                                false => ":white_large_square:",
-                            };
+                        if let Some(image) = proof.image {
+                            emb.image(image);
+                        }
 
-                            let mut desc = String::new();
+                        emb
+                    })
+                })
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .ok();
+        }
+    }
 
-                            if let Some(content) = task.content {
-                                desc = format!("{}\n", content);
-                            }
+    pub async fn send_comment(&self, task_id: Uuid, user_id: i64, content: String) {
+        let task = Task::get(&self.db_client, task_id)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok()
+            .flatten();
 
-                            desc = format!("{}Finished: {}", desc, checkbox);
+        let task = match task {
+            Some(task) => task,
+            None => return,
+        };
 
-                            emb.field(task.title, desc, false);
-                        }
+        let guild = Guild::get(&self.db_client, task.guild_id)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok()
+            .flatten();
 
-                        emb.field("Owner", owner, false).url(url)
-                    })
-                })
+        if let Some(guild) = guild {
+            let http = self.cache_and_http.http.as_ref();
+            let kind = Some(NotificationKind::Accountability);
+            let locale = locale::for_user(&self.db_client, task.user_id, Some(&guild)).await;
+            let message = locale::format(
+                locale,
+                Text::Comment,
+                &[
+                    ("user", &format!("<@{:?}>", user_id)),
+                    ("content", &content),
+                ],
+            );
+            let channel_id =
+                threads::task_channel(http, &self.db_client, &guild, &task, kind).await;
+            channel_id
+                .send_message(http, |m| m.content(message))
                 .await
                 .map_err(|e| error!("{:?}", e))
                 .ok();
         }
     }
 
+    // lets the assignee know, wherever they want their reminders
+    pub async fn send_assignment(&self, task: &Task, actor: i64) {
+        let guild = Guild::get(&self.db_client, task.guild_id)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok()
+            .flatten();
+        let list = List::get(&self.db_client, task.list_id)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok()
+            .flatten();
+
+        if let (Some(guild), Some(list)) = (guild, list) {
+            let locale = locale::for_user(&self.db_client, task.user_id, Some(&guild)).await;
+            let message = locale::format(
+                locale,
+                Text::Assigned,
+                &[
+                    ("user", &format!("<@{:?}>", task.user_id)),
+                    ("actor", &format!("<@{:?}>", actor)),
+                    ("task", &task.title),
+                    ("list", &list.title),
+                ],
+            );
+            self.deliver(&guild, task, NotificationKind::Reminder, message)
+                .await;
+        }
+    }
+
     pub async fn send_reminder(&self, task_id: Uuid, guild_id: i64) {
         let task = Task::get(&self.db_client, task_id)
             .await
@@ -255,21 +526,17 @@ impl Bot {
             .unwrap();
 
         if let (Some(task), Some(guild)) = (task, guild) {
-            if task.checked {
+            if task.checked || task.archived_at.is_some() {
                 return;
             }
 
-            let channel_id = guild.send_to.unwrap_or_default();
-            ChannelId(channel_id as u64)
-                .send_message(self.client.cache_and_http.http.as_ref(), |m| {
-                    m.content(format!(
-                        "hey <@{:?}>! you have _one hour_ to finish the following task:\n",
-                        task.user_id,
-                    ))
-                })
-                .await
-                .map_err(|e| error!("{:?}", e))
-                .ok();
+            let kind = NotificationKind::Reminder;
+            let locale = locale::for_user(&self.db_client, task.user_id, Some(&guild)).await;
+            let placeholders =
+                Placeholders::new(task.user_id, &task.title).due(task.due_at, locale);
+            let message =
+                templates::message(&self.db_client, &guild, kind, locale, &placeholders).await;
+            self.deliver(&guild, &task, kind, message).await;
         }
     }
 
@@ -280,9 +547,11 @@ impl Bot {
             .ok()
             .unwrap();
 
-        let request = AccountabilityRequest::get(&self.db_client, task_id)
+        // only partners who accepted the request get dragged into this
+        let partners = AccountabilityRequest::partners(&self.db_client, task_id)
             .await
-            .map_err(|e| error!("{:?}", e));
+            .map_err(|e| error!("{:?}", e))
+            .unwrap_or_default();
 
         let guild = Guild::get(&self.db_client, guild_id)
             .await
@@ -291,30 +560,33 @@ impl Bot {
             .unwrap();
 
         if let (Some(task), Some(guild)) = (task, guild) {
-            if task.checked {
+            if task.checked || task.archived_at.is_some() {
                 return;
             }
 
-            let channel_id = guild.send_to.unwrap_or_default();
-            ChannelId(channel_id as u64)
-                .send_message(self.client.cache_and_http.http.as_ref(), |m| {
-                    let mut message = format!(
-                        "your time to complete {} is up, <@{:?}>. i am very disappointed in you.",
-                        task.title, task.user_id,
-                    );
-
-                    if let Ok(Some(request)) = request {
-                        message = format!(
-                            "{}\n\n<@{:?}>, how could you let this happen?",
-                            message, request.requested_user,
-                        );
-                    }
+            // a job left over from before the due date moved mustn't settle
+            // the stake early
+            let now = Utc::now().timestamp();
+            if task.due_at.is_none_or(|due_at| due_at > now) {
+                return;
+            }
 
-                    m.content(message)
-                })
-                .await
-                .map_err(|e| error!("{:?}", e))
-                .ok();
+            let http = self.cache_and_http.http.as_ref();
+            let kind = NotificationKind::Overdue;
+            let locale = locale::for_user(&self.db_client, task.user_id, Some(&guild)).await;
+            let placeholders = Placeholders::new(task.user_id, &task.title)
+                .due(task.due_at, locale)
+                .partners(&partners);
+            let message =
+                templates::message(&self.db_client, &guild, kind, locale, &placeholders).await;
+            let stakes = self.settle_stake(&task, &partners, locale).await;
+            let message = match stakes.is_empty() {
+                true => message,
+                false => format!("{}\n\n{}", message, stakes.join("\n")),
+            };
+            self.deliver(&guild, &task, kind, message).await;
+
+            shame::apply(http, &self.db_client, &guild, task.user_id).await;
         }
     }
 
@@ -325,9 +597,10 @@ impl Bot {
             .ok()
             .unwrap();
 
-        let request = AccountabilityRequest::get(&self.db_client, task_id)
+        let partners = AccountabilityRequest::partners(&self.db_client, task_id)
             .await
-            .map_err(|e| error!("{:?}", e));
+            .map_err(|e| error!("{:?}", e))
+            .unwrap_or_default();
 
         let guild = Guild::get(&self.db_client, guild_id)
             .await
@@ -336,42 +609,637 @@ impl Bot {
             .unwrap();
 
         if let (Some(task), Some(guild)) = (task, guild) {
-            if task.checked {
+            if task.checked || task.archived_at.is_some() {
                 return;
             }
 
-            let channel_id = guild.send_to.unwrap_or_default();
-            ChannelId(channel_id as u64)
-                .send_message(self.client.cache_and_http.http.as_ref(), |m| {
-                    let mut message = format!(
-                        "hey <@{:?}>! {} still isn't finished yet >:c",
-                        task.user_id,
-                        task.title,
-                    );
-
-                    if let Ok(Some(request)) = request {
-                        if request.status == RequestStatus::Accepted {
-                            message = format!(
-                                "{}\n<@{:?}> would be _very_ upset with you if you didn't finish on time.",
-                                message,
-                                request.requested_user,
-                            );
-                        }
-                    }
+            // there's no point in pestering about a task that can't be worked on yet
+            let blocked = TaskDependency::is_blocked(&self.db_client, task.id)
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .unwrap_or_default();
 
-                    if let Some(due_at) = task.due_at {
-                        message = format!(
-                            "{}\n\nyou have until <t:{:?}>. use your time wisely.",
-                            message,
-                            due_at,
-                        );
+            if blocked {
+                debug!("not pestering about {}, it's blocked", task.id);
+                return;
+            }
+
+            let kind = NotificationKind::Pester;
+            let locale = locale::for_user(&self.db_client, task.user_id, Some(&guild)).await;
+            let placeholders = Placeholders::new(task.user_id, &task.title)
+                .due(task.due_at, locale)
+                .partners(&partners);
+            let mut message =
+                templates::message(&self.db_client, &guild, kind, locale, &placeholders).await;
+
+            // whatever is left on the checklist, in order
+            let remaining = TaskItem::get_for_task(&self.db_client, task.id)
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|item| !item.checked)
+                .map(|item| format!("- {}", item.title))
+                .collect::<Vec<String>>();
+
+            if !remaining.is_empty() {
+                let heading = locale::text(locale, Text::RemainingItems);
+                message = format!("{}\n\n{}\n{}", message, heading, remaining.join("\n"));
+            }
+
+            self.deliver(&guild, &task, kind, message).await;
+        }
+    }
+
+    // posts how everyone did on the tasks that came due between since and
+    // until, nothing is posted if no tasks came due
+    pub async fn send_leaderboard(&self, guild_id: i64, since: i64, until: i64) {
+        let guild = Guild::get(&self.db_client, guild_id)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok()
+            .flatten();
+
+        let entries = LeaderboardEntry::for_guild(&self.db_client, guild_id, since, until)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .unwrap_or_default();
+
+        let guild = match guild {
+            Some(guild) if !entries.is_empty() => guild,
+            _ => return,
+        };
+
+        let locale = Locale::resolve(None, guild.locale.as_deref());
+        let mut ranking = String::new();
+
+        // embed descriptions are capped, so only the top of the board is shown
+        for (rank, entry) in entries.iter().take(LEADERBOARD_SIZE).enumerate() {
+            let mut line = locale::format(
+                locale,
+                Text::LeaderboardEntry,
+                &[
+                    ("rank", &(rank + 1).to_string()),
+                    ("user", &format!("<@{:?}>", entry.user_id)),
+                    ("completed", &entry.completed.to_string()),
+                    ("total", &(entry.completed + entry.overdue).to_string()),
+                    ("rate", &format!("{:.0}", entry.completion_rate * 100.0)),
+                ],
+            );
+
+            if entry.streak > 1 {
+                line.push_str(&locale::format(
+                    locale,
+                    Text::LeaderboardStreak,
+                    &[("streak", &entry.streak.to_string())],
+                ));
+            }
+
+            ranking = format!("{}{}\n", ranking, line);
+        }
+
+        let mut shamed: Vec<&LeaderboardEntry> = entries.iter().filter(|e| e.overdue > 0).collect();
+        shamed.sort_by_key(|e| std::cmp::Reverse(e.overdue));
+
+        let shame = shamed
+            .iter()
+            .take(LEADERBOARD_SIZE)
+            .map(|entry| {
+                locale::format(
+                    locale,
+                    Text::ShameEntry,
+                    &[
+                        ("user", &format!("<@{:?}>", entry.user_id)),
+                        ("overdue", &entry.overdue.to_string()),
+                    ],
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let http = self.cache_and_http.http.as_ref();
+        notification_channel(&self.db_client, &guild, Some(NotificationKind::Leaderboard))
+            .await
+            .send_message(http, |m| {
+                m.embed(|emb| {
+                    emb.title(locale::text(locale, Text::LeaderboardTitle))
+                        .description(ranking);
+
+                    if !shame.is_empty() {
+                        emb.field(locale::text(locale, Text::HallOfShame), shame, false);
                     }
 
-                    m.content(message)
+                    emb
                 })
+            })
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+    }
+
+    // settles the stake of an overdue task and spells out who lost and won
+    // what, along with the penalty the owner pledged. a stake that was
+    // already settled isn't mentioned again
+    async fn settle_stake(&self, task: &Task, partners: &[i64], locale: Locale) -> Vec<String> {
+        let user = format!("<@{:?}>", task.user_id);
+        let settled = PointsEntry::settle(&self.db_client, task, partners)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .unwrap_or_default();
+        let mut lines = Vec::new();
+
+        if let Some(lost) = settled.iter().find(|e| e.reason == PointsReason::StakeLost) {
+            lines.push(locale::format(
+                locale,
+                Text::StakeLost,
+                &[("user", &user), ("points", &(-lost.amount).to_string())],
+            ));
+        }
+
+        let winners = settled
+            .iter()
+            .filter(|e| e.reason == PointsReason::StakeWon)
+            .map(|e| format!("<@{:?}>", e.user_id))
+            .collect::<Vec<String>>();
+
+        if !winners.is_empty() {
+            lines.push(locale::format(
+                locale,
+                Text::StakeWon,
+                &[("partners", &winners.join(", "))],
+            ));
+        }
+
+        if let Some(penalty) = task.penalty.as_deref().filter(|p| !p.trim().is_empty()) {
+            lines.push(locale::format(
+                locale,
+                Text::Penalty,
+                &[("user", &user), ("penalty", penalty)],
+            ));
+        }
+
+        lines
+    }
+
+    async fn deliver(&self, guild: &Guild, task: &Task, kind: NotificationKind, message: String) {
+        let http = self.cache_and_http.http.as_ref();
+        deliver(http, &self.db_client, guild, task, kind, message).await;
+    }
+}
+
+// sends a message about a task to the guild, the task owner's DMs, or both,
+// depending on how the owner wants that kind of message delivered
+pub(crate) async fn deliver(
+    http: &Http,
+    db_client: &DbClient,
+    guild: &Guild,
+    task: &Task,
+    kind: NotificationKind,
+    message: String,
+) {
+    let delivery = DeliveryPreference::resolve(db_client, task.user_id, kind)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .unwrap_or_default();
+
+    if delivery != Delivery::Dm {
+        let channel_id = threads::task_channel(http, db_client, guild, task, Some(kind)).await;
+        channel_id
+            .send_message(http, |m| m.content(&message))
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+    }
+
+    if delivery != Delivery::Channel {
+        send_dm(http, task.user_id as u64, message).await;
+    }
+}
+
+// like Bot::send_dm, for callers which only have the http client
+pub(crate) async fn send_dm(http: &Http, user_id: u64, message: String) {
+    let channel = UserId(user_id)
+        .create_dm_channel(http)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    if let Some(channel) = channel {
+        channel
+            .send_message(http, |m| m.content(message))
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+    }
+}
+
+// shared between the bot and the interaction handler, which only has access
+// to the http client through its context
+pub(crate) async fn post_task(
+    http: &Http,
+    db_client: &DbClient,
+    shamebot_url: &str,
+    task_id: Uuid,
+    guild_id: i64,
+    kind: Option<NotificationKind>,
+) {
+    let task = Task::get(db_client, task_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .unwrap();
+
+    let guild = Guild::get(db_client, guild_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .unwrap();
+
+    if let (Some(task), Some(guild)) = (task, guild) {
+        let channel_id = threads::task_channel(http, db_client, &guild, &task, kind).await;
+        let locale = locale::for_user(db_client, task.user_id, Some(&guild)).await;
+        let checkbox = match task.checked {
+            true => ":white_check_mark:",
+            false => ":white_large_square:",
+        };
+        let mut finished = locale::format(locale, Text::Finished, &[("checkbox", checkbox)]);
+        let owner = locale::format(
+            locale,
+            Text::TaskOwner,
+            &[("user", &format!("<@{:?}>", task.user_id))],
+        );
+        let mut desc = String::new();
+
+        let progress = Progress::for_task(db_client, task.id)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .unwrap_or_default();
+
+        if progress.total > 0 {
+            let checklist = locale::format(
+                locale,
+                Text::ChecklistProgress,
+                &[
+                    ("done", &progress.done.to_string()),
+                    ("total", &progress.total.to_string()),
+                ],
+            );
+            finished = format!("{}\n{}", finished, checklist);
+        }
+
+        if let Some(content) = task.content {
+            desc = format!("{}\n", content);
+        }
+        desc = format!("{}{}\n\n{}", desc, &finished, &owner);
+        let url = format!("{}/tasks/{}", shamebot_url, task.id);
+        channel_id
+            .send_message(http, |m| {
+                m.embed(|emb| emb.title(task.title).description(desc).url(url))
+            })
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+    }
+}
+
+pub(crate) async fn post_completion(http: &Http, db_client: &DbClient, task_id: Uuid) {
+    let task = Task::get(db_client, task_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .flatten();
+
+    let task = match task {
+        Some(task) => task,
+        None => return,
+    };
+
+    let guild = Guild::get(db_client, task.guild_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .flatten();
+
+    if let Some(guild) = guild {
+        let kind = NotificationKind::Completion;
+        let locale = locale::for_user(db_client, task.user_id, Some(&guild)).await;
+        let placeholders = Placeholders::new(task.user_id, &task.title).due(task.due_at, locale);
+        let mut message = templates::message(db_client, &guild, kind, locale, &placeholders).await;
+
+        // only worth mentioning if this task kept the streak going
+        if task.lateness.is_some_and(|lateness| lateness <= 0) {
+            let stats = HabitStats::for_user(db_client, task.user_id)
                 .await
                 .map_err(|e| error!("{:?}", e))
-                .ok();
+                .unwrap_or_default();
+
+            if stats.current_streak > 1 {
+                let streak = locale::format(
+                    locale,
+                    Text::OnTimeStreak,
+                    &[("streak", &stats.current_streak.to_string())],
+                );
+                message = format!("{}\n{}", message, streak);
+            }
+        }
+
+        let channel_id = threads::task_channel(http, db_client, &guild, &task, Some(kind)).await;
+        channel_id
+            .send_message(http, |m| m.content(message))
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+
+        threads::archive(http, channel_id).await;
+        post_list_progress(http, db_client, &guild, &task).await;
+    }
+
+    post_unblocked(http, db_client, &task).await;
+}
+
+// keeps the creator of a shared list up to date when someone else finishes
+// one of its tasks
+async fn post_list_progress(http: &Http, db_client: &DbClient, guild: &Guild, task: &Task) {
+    let list = List::get(db_client, task.list_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .flatten();
+
+    let list = match list {
+        Some(list) if list.user_id != task.user_id => list,
+        _ => return,
+    };
+
+    let progress = Progress::for_list(db_client, list.id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .unwrap_or_default();
+    let locale = locale::for_user(db_client, list.user_id, Some(guild)).await;
+    let message = locale::format(
+        locale,
+        Text::ListProgress,
+        &[
+            ("user", &format!("<@{:?}>", task.user_id)),
+            ("task", &task.title),
+            ("list", &list.title),
+            ("done", &progress.done.to_string()),
+            ("total", &progress.total.to_string()),
+        ],
+    );
+
+    send_dm(http, list.user_id as u64, message).await;
+}
+
+// nudges the owners of tasks that were only waiting for `blocker`. sent like
+// a reminder, wherever the owner wants those
+async fn post_unblocked(http: &Http, db_client: &DbClient, blocker: &Task) {
+    let unblocked = TaskDependency::unblocked(db_client, blocker.id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .unwrap_or_default();
+
+    for task in unblocked {
+        let guild = Guild::get(db_client, task.guild_id)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok()
+            .flatten();
+
+        if let Some(guild) = guild {
+            let locale = locale::for_user(db_client, task.user_id, Some(&guild)).await;
+            let message = locale::format(
+                locale,
+                Text::Unblocked,
+                &[
+                    ("user", &format!("<@{:?}>", task.user_id)),
+                    ("blocker", &blocker.title),
+                    ("task", &task.title),
+                ],
+            );
+            let kind = NotificationKind::Reminder;
+            deliver(http, db_client, &guild, &task, kind, message).await;
+        }
+    }
+}
+
+pub(crate) async fn post_list(
+    http: &Http,
+    db_client: &DbClient,
+    shamebot_url: &str,
+    list_id: Uuid,
+    guild_id: i64,
+) {
+    let list = List::get(db_client, list_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .unwrap();
+
+    let tasks = List::get_tasks(db_client, list_id, &TaskFilter::default())
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    let guild = Guild::get(db_client, guild_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .unwrap();
+
+    if let (Some(list), Some(tasks), Some(guild)) = (list, tasks, guild) {
+        let locale = locale::for_user(db_client, list.user_id, Some(&guild)).await;
+        let owner = locale::format(
+            locale,
+            Text::TaskOwner,
+            &[("user", &format!("<@{:?}>", list.user_id))],
+        );
+        let url = format!("{}/lists/{}", shamebot_url, list.id);
+        let channel_id = guild.send_to.unwrap_or_default();
+        ChannelId(channel_id as u64)
+            .send_message(http, |m| {
+                m.embed(|emb| {
+                    emb.title(list.title);
+
+                    for task in tasks {
+                        let checkbox = match task.checked {
+                            true => ":white_check_mark:",
+                            false => ":white_large_square:",
+                        };
+
+                        let mut desc = String::new();
+
+                        if let Some(content) = task.content {
+                            desc = format!("{}\n", content);
+                        }
+
+                        desc = format!(
+                            "{}{}",
+                            desc,
+                            locale::format(locale, Text::Finished, &[("checkbox", checkbox)]),
+                        );
+
+                        emb.field(task.title, desc, false);
+                    }
+
+                    emb.field(locale::text(locale, Text::ListOwner), owner, false)
+                        .url(url)
+                })
+            })
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+    }
+}
+
+// asks every partner to approve a change to a task they keep the owner
+// accountable for, the first answer counts. the buttons are answered in
+// commands
+pub(crate) async fn post_change_request(http: &Http, db_client: &DbClient, change: &PendingChange) {
+    let task = Task::get(db_client, change.task_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .flatten();
+
+    let task = match task {
+        Some(task) => task,
+        None => return,
+    };
+
+    let partners = AccountabilityRequest::partners(db_client, change.task_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .unwrap_or_default();
+
+    for partner in partners {
+        let channel = UserId(partner as u64)
+            .create_dm_channel(http)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+
+        let channel = match channel {
+            Some(channel) => channel,
+            None => continue,
+        };
+
+        let locale = locale::for_user(db_client, partner, None).await;
+        let requested_by = format!("<@{:?}>", change.requested_by);
+        let changes = describe_change(locale, &task, change);
+        channel
+            .send_message(http, |m| {
+                m.embed(|emb| {
+                    emb.title(locale::text(locale, Text::ChangeRequestTitle))
+                        .description(locale::format(
+                            locale,
+                            Text::ChangeRequestDescription,
+                            &[("user", &requested_by)],
+                        ))
+                        .field(locale::text(locale, Text::TaskField), &task.title, false)
+                        .field(locale::text(locale, Text::ChangesField), changes, false)
+                })
+                .components(|c| {
+                    c.create_action_row(|row| {
+                        row.create_button(|b| {
+                            b.custom_id(format!("{}:{}", APPROVE_CHANGE, task.id))
+                                .label(locale::text(locale, Text::ApproveButton))
+                                .style(ButtonStyle::Success)
+                        })
+                        .create_button(|b| {
+                            b.custom_id(format!("{}:{}", REJECT_CHANGE, task.id))
+                                .label(locale::text(locale, Text::RejectButton))
+                                .style(ButtonStyle::Danger)
+                        })
+                    })
+                })
+            })
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+    }
+}
+
+fn describe_change(locale: Locale, task: &Task, change: &PendingChange) -> String {
+    match (change.kind, change.partner_id) {
+        (ChangeKind::Delete, _) => return locale::text(locale, Text::ChangeDelete).to_string(),
+        (ChangeKind::Unpartner, Some(partner_id)) => {
+            let partner = format!("<@{:?}>", partner_id);
+            return locale::format(locale, Text::ChangeUnpartner, &[("partner", &partner)]);
         }
+        (ChangeKind::Unpartner, None) => {
+            return locale::text(locale, Text::ChangeUnpartnerAll).to_string()
+        }
+        (ChangeKind::Update, _) => {}
+    }
+
+    let due = |due_at: Option<i64>| match due_at {
+        Some(due_at) if due_at > 0 => format!("<t:{:?}>", due_at),
+        _ => String::from("-"),
+    };
+    let pester = |pester: Option<i16>| match pester {
+        Some(pester) if pester > 0 => pester.to_string(),
+        _ => String::from("-"),
+    };
+    let mut lines = Vec::new();
+
+    if change.due_at != task.due_at {
+        lines.push(locale::format(
+            locale,
+            Text::ChangeDue,
+            &[("from", &due(task.due_at)), ("to", &due(change.due_at))],
+        ));
+    }
+
+    if change.pester != task.pester {
+        lines.push(locale::format(
+            locale,
+            Text::ChangePester,
+            &[
+                ("from", &pester(task.pester)),
+                ("to", &pester(change.pester)),
+            ],
+        ));
     }
+
+    let current_quorum = task.quorum.unwrap_or_default();
+    let current_stake = task.stake.unwrap_or_default();
+
+    if change.quorum != current_quorum {
+        let quorum = |quorum: Quorum| match quorum {
+            Quorum::Any => locale::text(locale, Text::QuorumAny),
+            Quorum::Majority => locale::text(locale, Text::QuorumMajority),
+            Quorum::All => locale::text(locale, Text::QuorumAll),
+        };
+
+        lines.push(locale::format(
+            locale,
+            Text::ChangeQuorum,
+            &[
+                ("from", quorum(current_quorum)),
+                ("to", quorum(change.quorum)),
+            ],
+        ));
+    }
+
+    if change.stake < current_stake {
+        lines.push(locale::format(
+            locale,
+            Text::ChangeStake,
+            &[
+                ("from", &current_stake.to_string()),
+                ("to", &change.stake.to_string()),
+            ],
+        ));
+    }
+
+    if change.checked && !task.checked {
+        lines.push(locale::text(locale, Text::ChangeCheckOff).to_string());
+    }
+
+    lines
+        .iter()
+        .map(|line| format!("- {}", line))
+        .collect::<Vec<String>>()
+        .join("\n")
 }
@@ -0,0 +1,21 @@
+// guilds can route each kind of notification to its own channel, anything
+// without an override (or without a kind at all) goes to send_to
+use database::prelude::{Client as DbClient, Guild, NotificationChannel, NotificationKind};
+use log::error;
+use serenity::model::prelude::ChannelId;
+
+pub(crate) async fn notification_channel(
+    db_client: &DbClient,
+    guild: &Guild,
+    kind: Option<NotificationKind>,
+) -> ChannelId {
+    let channel_id = match kind {
+        Some(kind) => NotificationChannel::resolve(db_client, guild, kind)
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .unwrap_or(guild.send_to),
+        None => guild.send_to,
+    };
+
+    ChannelId(channel_id.unwrap_or_default() as u64)
+}
@@ -0,0 +1,1150 @@
+// slash commands, context menu commands and the modals they open. the
+// handler is only invoked on the bot instance which has been started
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use database::prelude::{
+    AccountabilityRequest, Balance, Client as DbClient, Guild, List, NewTask, Partnership,
+    PendingChange, Priority, RequestStatus, Task, User as DbUser, MAX_TAG,
+};
+use log::{debug, error};
+use serenity::{
+    builder::{CreateApplicationCommand, CreateApplicationCommandOption},
+    model::{
+        application::{
+            command::{Command, CommandOptionType, CommandType},
+            component::{ActionRowComponent, InputTextStyle},
+            interaction::{
+                application_command::{
+                    ApplicationCommandInteraction, CommandDataOption, ResolvedTarget,
+                },
+                autocomplete::AutocompleteInteraction,
+                message_component::MessageComponentInteraction,
+                modal::ModalSubmitInteraction,
+                Interaction, InteractionResponseType,
+            },
+        },
+        user::User,
+    },
+    prelude::*,
+};
+use uuid::Uuid;
+
+use crate::bot::{post_change_request, post_completion, post_list, post_task};
+use crate::environment::Env;
+use crate::jobs;
+use crate::locale::{self, Locale, Text};
+use crate::shame;
+
+const TASK_MODAL: &str = "task_modal";
+// message commands are matched by name, so this has to stay in english.
+// discord shows the localized names to users
+const MESSAGE_TO_TASK: &str = "Turn into task";
+// buttons carry the id of the task or partnership after a colon
+pub(crate) const APPROVE_CHANGE: &str = "approve_change";
+pub(crate) const REJECT_CHANGE: &str = "reject_change";
+pub(crate) const ACCEPT_PARTNERSHIP: &str = "accept_partnership";
+pub(crate) const REJECT_PARTNERSHIP: &str = "reject_partnership";
+const POINTS_SIZE: usize = 10;
+// discord refuses a modal whose text inputs hold more than this
+const MAX_INPUT: usize = 4000;
+
+#[derive(Debug, Default)]
+struct TaskDraft {
+    title: String,
+    content: String,
+}
+
+pub(crate) async fn register(ctx: &Context) {
+    let commands = Command::set_global_application_commands(&ctx.http, |commands| {
+        commands
+            .create_application_command(|command| {
+                describe_command(command.name("task"), Text::TaskCommand)
+                    .dm_permission(false)
+                    .create_option(|option| {
+                        describe_option(option.name("new"), Text::TaskNewCommand)
+                            .kind(CommandOptionType::SubCommand)
+                    })
+                    .create_option(|option| {
+                        describe_option(option.name("done"), Text::TaskDoneCommand)
+                            .kind(CommandOptionType::SubCommand)
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("task"), Text::TaskDoneOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true)
+                                    .set_autocomplete(true)
+                            })
+                    })
+                    .create_option(|option| {
+                        describe_option(option.name("priority"), Text::TaskPriorityCommand)
+                            .kind(CommandOptionType::SubCommand)
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("task"), Text::TaskOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true)
+                                    .set_autocomplete(true)
+                            })
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("level"), Text::TaskPriorityOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true);
+
+                                for priority in Priority::ALL {
+                                    let text = priority_text(priority);
+                                    sub.add_string_choice_localized(
+                                        locale::text(Locale::English, text),
+                                        priority.name(),
+                                        translations().map(|locale| {
+                                            (locale.tag(), locale::text(locale, text))
+                                        }),
+                                    );
+                                }
+
+                                sub
+                            })
+                    })
+                    .create_option(|option| {
+                        describe_option(option.name("tag"), Text::TaskTagCommand)
+                            .kind(CommandOptionType::SubCommand)
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("task"), Text::TaskOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true)
+                                    .set_autocomplete(true)
+                            })
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("tag"), Text::TaskTagOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true)
+                            })
+                    })
+                    .create_option(|option| {
+                        describe_option(option.name("untag"), Text::TaskUntagCommand)
+                            .kind(CommandOptionType::SubCommand)
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("task"), Text::TaskOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true)
+                                    .set_autocomplete(true)
+                            })
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("tag"), Text::TaskTagOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true)
+                            })
+                    })
+            })
+            .create_application_command(|command| {
+                describe_command(command.name("list"), Text::ListCommand)
+                    .dm_permission(false)
+                    .create_option(|option| {
+                        describe_option(option.name("show"), Text::ListShowCommand)
+                            .kind(CommandOptionType::SubCommand)
+                            .create_sub_option(|sub| {
+                                describe_option(sub.name("list"), Text::ListShowOption)
+                                    .kind(CommandOptionType::String)
+                                    .required(true)
+                                    .set_autocomplete(true)
+                            })
+                    })
+            })
+            .create_application_command(|command| {
+                describe_command(command.name("points"), Text::PointsCommand).dm_permission(false)
+            })
+            .create_application_command(|command| {
+                command
+                    .name(MESSAGE_TO_TASK)
+                    .kind(CommandType::Message)
+                    .dm_permission(false);
+
+                for locale in translations() {
+                    command.name_localized(
+                        locale.tag(),
+                        locale::text(locale, Text::MessageToTaskCommand),
+                    );
+                }
+
+                command
+            })
+    })
+    .await;
+
+    match commands {
+        Ok(commands) => debug!("registered {} application commands", commands.len()),
+        Err(e) => error!("{:?}", e),
+    }
+}
+
+fn translations() -> impl Iterator<Item = Locale> {
+    Locale::ALL
+        .into_iter()
+        .filter(|locale| *locale != Locale::English)
+}
+
+fn priority_text(priority: Priority) -> Text {
+    match priority {
+        Priority::Low => Text::PriorityLow,
+        Priority::Normal => Text::PriorityNormal,
+        Priority::High => Text::PriorityHigh,
+        Priority::Urgent => Text::PriorityUrgent,
+    }
+}
+
+fn describe_command(
+    command: &mut CreateApplicationCommand,
+    text: Text,
+) -> &mut CreateApplicationCommand {
+    command.description(locale::text(Locale::English, text));
+
+    for locale in translations() {
+        command.description_localized(locale.tag(), locale::text(locale, text));
+    }
+
+    command
+}
+
+fn describe_option(
+    option: &mut CreateApplicationCommandOption,
+    text: Text,
+) -> &mut CreateApplicationCommandOption {
+    option.description(locale::text(Locale::English, text));
+
+    for locale in translations() {
+        option.description_localized(locale.tag(), locale::text(locale, text));
+    }
+
+    option
+}
+
+pub(crate) async fn handle(
+    ctx: &Context,
+    interaction: Interaction,
+    db_client: &DbClient,
+    env: &Env,
+) {
+    match interaction {
+        Interaction::ApplicationCommand(command) => {
+            let locale = Locale::resolve(Some(&command.locale), command.guild_locale.as_deref());
+
+            match (command.data.kind, command.data.name.as_str()) {
+                (CommandType::ChatInput, "task") => {
+                    handle_task_command(ctx, &command, db_client, locale).await
+                }
+                (CommandType::ChatInput, "list") => {
+                    handle_list_command(ctx, &command, db_client, env, locale).await
+                }
+                (CommandType::ChatInput, "points") => {
+                    handle_points_command(ctx, &command, db_client, locale).await
+                }
+                (CommandType::Message, MESSAGE_TO_TASK) => {
+                    handle_message_to_task(ctx, &command, locale).await
+                }
+                _ => debug!("ignoring unknown command {}", command.data.name),
+            }
+
+            locale::remember(
+                db_client,
+                *command.user.id.as_u64() as i64,
+                &command.locale,
+                command.guild_id.map(|id| *id.as_u64() as i64),
+                command.guild_locale.as_deref(),
+            )
+            .await;
+        }
+        Interaction::Autocomplete(autocomplete) => {
+            handle_autocomplete(ctx, &autocomplete, db_client).await;
+        }
+        Interaction::ModalSubmit(modal) => {
+            let locale = Locale::resolve(Some(&modal.locale), modal.guild_locale.as_deref());
+
+            if modal.data.custom_id == TASK_MODAL {
+                handle_task_modal(ctx, &modal, db_client, env, locale).await;
+            }
+        }
+        Interaction::MessageComponent(component) => {
+            let locale =
+                Locale::resolve(Some(&component.locale), component.guild_locale.as_deref());
+
+            let (action, id) = component.data.custom_id.split_once(':').unwrap_or_default();
+            let id = match Uuid::parse_str(id) {
+                Ok(id) => id,
+                Err(_) => {
+                    debug!("ignoring unknown component {}", component.data.custom_id);
+                    return;
+                }
+            };
+
+            match action {
+                APPROVE_CHANGE | REJECT_CHANGE => {
+                    let approve = action == APPROVE_CHANGE;
+                    handle_change_button(ctx, &component, db_client, approve, id, locale).await
+                }
+                ACCEPT_PARTNERSHIP | REJECT_PARTNERSHIP => {
+                    let accept = action == ACCEPT_PARTNERSHIP;
+                    handle_partnership_button(ctx, &component, db_client, accept, id, locale).await
+                }
+                _ => debug!("ignoring unknown component {}", component.data.custom_id),
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn handle_task_command(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    db_client: &DbClient,
+    locale: Locale,
+) {
+    let subcommand = command.data.options.first();
+
+    match subcommand.map(|o| o.name.as_str()) {
+        Some("new") => open_task_modal(ctx, command, TaskDraft::default(), locale).await,
+        Some("done") => {
+            let task_id = subcommand.and_then(|o| option_value(&o.options, "task"));
+            let checked = check_off_task(db_client, command, task_id, locale).await;
+
+            let reply = match checked.as_ref() {
+                Ok((task, None)) => {
+                    locale::format(locale, Text::TaskDone, &[("task", &task.title)])
+                }
+                Ok((task, Some(_))) => {
+                    let partners = AccountabilityRequest::partners(db_client, task.id)
+                        .await
+                        .map_err(|e| error!("{}", e))
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|partner| format!("<@{:?}>", partner))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+
+                    locale::format(locale, Text::AwaitingPartner, &[("partners", &partners)])
+                }
+                Err(message) => message.clone(),
+            };
+
+            respond(ctx, command, &reply).await;
+
+            match checked {
+                Ok((task, None)) => {
+                    post_completion(&ctx.http, db_client, task.id).await;
+
+                    shame::lift(&ctx.http, db_client, task.user_id, task.guild_id).await;
+                }
+                Ok((_, Some(change))) => post_change_request(&ctx.http, db_client, &change).await,
+                Err(_) => {}
+            }
+        }
+        Some(name @ ("priority" | "tag" | "untag")) => {
+            let options = subcommand.map(|o| o.options.as_slice()).unwrap_or_default();
+            let reply = match edit_task(db_client, command, name, options, locale).await {
+                Ok(reply) => reply,
+                Err(message) => message,
+            };
+
+            respond(ctx, command, &reply).await;
+        }
+        _ => {}
+    }
+}
+
+// sets the priority or adds or removes a tag, neither needs the partners'
+// approval. returns the reply either way
+async fn edit_task(
+    db_client: &DbClient,
+    command: &ApplicationCommandInteraction,
+    subcommand: &str,
+    options: &[CommandDataOption],
+    locale: Locale,
+) -> Result<String, String> {
+    let mut task = find_own_task(db_client, command, option_value(options, "task"), locale).await?;
+    let title = task.title.clone();
+
+    let reply = match subcommand {
+        "priority" => {
+            let priority = option_value(options, "level")
+                .and_then(Priority::from_name)
+                .unwrap_or_default();
+            task.priority = Some(priority);
+
+            locale::format(
+                locale,
+                Text::PrioritySet,
+                &[
+                    ("task", &title),
+                    ("priority", locale::text(locale, priority_text(priority))),
+                ],
+            )
+        }
+        _ => {
+            let tag = option_value(options, "tag")
+                .unwrap_or_default()
+                .trim()
+                .to_lowercase();
+
+            if tag.is_empty() || tag.chars().count() > MAX_TAG {
+                return Err(locale::format(
+                    locale,
+                    Text::InvalidTag,
+                    &[("max", &MAX_TAG.to_string())],
+                ));
+            }
+
+            let args = [("task", title.as_str()), ("tag", tag.as_str())];
+
+            if subcommand == "tag" {
+                task.tags.get_or_insert_with(Vec::new).push(tag.clone());
+                locale::format(locale, Text::TagAdded, &args)
+            } else {
+                if let Some(tags) = task.tags.as_mut() {
+                    tags.retain(|t| *t != tag);
+                }
+                locale::format(locale, Text::TagRemoved, &args)
+            }
+        }
+    };
+
+    let user_id = task.user_id;
+
+    match Task::update(db_client, task, Some(user_id)).await {
+        Ok(Some(_)) => Ok(reply),
+        Ok(None) => Err(locale::text(locale, Text::TaskNotFound).to_string()),
+        Err(e) => {
+            error!("{}", e);
+            Err(locale::text(locale, Text::UpdateFailed).to_string())
+        }
+    }
+}
+
+// only the invoking user's own tasks can be found
+async fn find_own_task(
+    db_client: &DbClient,
+    command: &ApplicationCommandInteraction,
+    task_id: Option<&str>,
+    locale: Locale,
+) -> Result<Task, String> {
+    let not_found = locale::text(locale, Text::TaskNotFound).to_string();
+    let task_id = task_id
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| not_found.clone())?;
+
+    let task = Task::get(db_client, task_id)
+        .await
+        .map_err(|e| error!("{}", e))
+        .ok()
+        .flatten();
+
+    match task {
+        Some(task) if task.user_id == *command.user.id.as_u64() as i64 => Ok(task),
+        _ => Err(not_found),
+    }
+}
+
+// returns a user-facing message on failure. without proof, checking off a
+// partnered task only creates a change request for its partners
+async fn check_off_task(
+    db_client: &DbClient,
+    command: &ApplicationCommandInteraction,
+    task_id: Option<&str>,
+    locale: Locale,
+) -> Result<(Task, Option<PendingChange>), String> {
+    let task = find_own_task(db_client, command, task_id, locale).await?;
+    let updated = Task::update_guarded(
+        db_client,
+        Task {
+            checked: true,
+            ..task
+        },
+        Some(task.user_id),
+    )
+    .await;
+
+    match updated {
+        Ok((Some(task), change)) => Ok((task, change)),
+        Ok((None, _)) => Err(locale::text(locale, Text::TaskNotFound).to_string()),
+        Err(e) => {
+            error!("{}", e);
+            Err(locale::text(locale, Text::CheckOffFailed).to_string())
+        }
+    }
+}
+
+async fn handle_change_button(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    db_client: &DbClient,
+    approve: bool,
+    task_id: Uuid,
+    locale: Locale,
+) {
+    let user_id = *component.user.id.as_u64() as i64;
+
+    let partners = AccountabilityRequest::partners(db_client, task_id)
+        .await
+        .map_err(|e| error!("{}", e))
+        .unwrap_or_default();
+
+    if !partners.contains(&user_id) {
+        respond_component(ctx, component, locale::text(locale, Text::NotThePartner)).await;
+        return;
+    }
+
+    let task = Task::get(db_client, task_id)
+        .await
+        .map_err(|e| error!("{}", e))
+        .ok()
+        .flatten();
+
+    let answered = match approve {
+        true => PendingChange::approve(db_client, task_id, user_id).await,
+        false => PendingChange::reject(db_client, task_id).await,
+    }
+    .map_err(|e| error!("{}", e))
+    .ok()
+    .flatten();
+
+    let title = task.as_ref().map(|t| t.title.as_str()).unwrap_or_default();
+    let reply = match (&answered, approve) {
+        (None, _) => locale::text(locale, Text::ChangeAnswered).to_string(),
+        (Some(_), true) => locale::format(locale, Text::ChangeApproved, &[("task", title)]),
+        (Some(_), false) => locale::format(locale, Text::ChangeRejected, &[("task", title)]),
+    };
+
+    answer_component(ctx, component, &reply).await;
+
+    if let (Some(change), Some(task)) = (answered, task) {
+        if approve {
+            jobs::approved(&change, &task).await;
+        }
+
+        if approve && change.checked && !task.checked {
+            post_completion(&ctx.http, db_client, task.id).await;
+
+            shame::lift(&ctx.http, db_client, task.user_id, task.guild_id).await;
+        }
+    }
+}
+
+async fn handle_partnership_button(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    db_client: &DbClient,
+    accept: bool,
+    partnership_id: Uuid,
+    locale: Locale,
+) {
+    let partnership = Partnership::get(db_client, partnership_id)
+        .await
+        .map_err(|e| error!("{}", e))
+        .ok()
+        .flatten();
+
+    let partnership = match partnership {
+        Some(p) if p.partner_id == *component.user.id.as_u64() as i64 => p,
+        _ => {
+            respond_component(ctx, component, locale::text(locale, Text::NotThePartner)).await;
+            return;
+        }
+    };
+
+    if partnership.status != RequestStatus::Pending {
+        answer_component(ctx, component, locale::text(locale, Text::ChangeAnswered)).await;
+        return;
+    }
+
+    let status = match accept {
+        true => RequestStatus::Accepted,
+        false => RequestStatus::Rejected,
+    };
+    let updated = Partnership::update_status(db_client, partnership_id, status)
+        .await
+        .map_err(|e| error!("{}", e))
+        .ok()
+        .flatten();
+
+    let user = format!("<@{:?}>", partnership.user_id);
+    let reply = match (updated, accept) {
+        (None, _) => locale::text(locale, Text::ChangeAnswered).to_string(),
+        (Some(_), true) => locale::format(locale, Text::PartnershipAccepted, &[("user", &user)]),
+        (Some(_), false) => locale::format(locale, Text::PartnershipRejected, &[("user", &user)]),
+    };
+
+    answer_component(ctx, component, &reply).await;
+}
+
+async fn handle_message_to_task(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    locale: Locale,
+) {
+    if let Some(ResolvedTarget::Message(message)) = command.data.target() {
+        let title = message
+            .content
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(80)
+            .collect();
+        let content = draft_content(&message.content, &message.link());
+
+        open_task_modal(ctx, command, TaskDraft { title, content }, locale).await;
+    }
+}
+
+// the message with a link back to it, cut short so both fit in the modal
+fn draft_content(content: &str, link: &str) -> String {
+    let room = MAX_INPUT.saturating_sub(link.chars().count() + 2);
+    let content: String = content.chars().take(room).collect();
+
+    format!("{}\n\n{}", content, link)
+}
+
+async fn open_task_modal(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    draft: TaskDraft,
+    locale: Locale,
+) {
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::Modal)
+                .interaction_response_data(|d| {
+                    d.custom_id(TASK_MODAL)
+                        .title(locale::text(locale, Text::ModalTitle))
+                        .components(|c| {
+                            c.create_action_row(|row| {
+                                row.create_input_text(|t| {
+                                    t.custom_id("title")
+                                        .label(locale::text(locale, Text::TitleLabel))
+                                        .style(InputTextStyle::Short)
+                                        .max_length(80)
+                                        .required(true)
+                                        .value(draft.title)
+                                })
+                            })
+                            .create_action_row(|row| {
+                                row.create_input_text(|t| {
+                                    t.custom_id("content")
+                                        .label(locale::text(locale, Text::DescriptionLabel))
+                                        .style(InputTextStyle::Paragraph)
+                                        .required(false)
+                                        .value(draft.content)
+                                })
+                            })
+                            .create_action_row(|row| {
+                                row.create_input_text(|t| {
+                                    t.custom_id("due_at")
+                                        .label(locale::text(locale, Text::DueLabel))
+                                        .placeholder("YYYY-MM-DD HH:MM")
+                                        .style(InputTextStyle::Short)
+                                        .required(false)
+                                })
+                            })
+                            .create_action_row(|row| {
+                                row.create_input_text(|t| {
+                                    t.custom_id("pester")
+                                        .label(locale::text(locale, Text::PesterLabel))
+                                        .style(InputTextStyle::Short)
+                                        .max_length(3)
+                                        .required(false)
+                                })
+                            })
+                            .create_action_row(|row| {
+                                row.create_input_text(|t| {
+                                    t.custom_id("list")
+                                        .label(locale::text(locale, Text::ListLabel))
+                                        .placeholder(locale::text(locale, Text::ListPlaceholder))
+                                        .style(InputTextStyle::Short)
+                                        .max_length(80)
+                                        .required(true)
+                                })
+                            })
+                        })
+                })
+        })
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+}
+
+async fn handle_task_modal(
+    ctx: &Context,
+    modal: &ModalSubmitInteraction,
+    db_client: &DbClient,
+    env: &Env,
+    locale: Locale,
+) {
+    let result = create_task_from_modal(modal, db_client, locale).await;
+
+    let reply = match result.as_ref() {
+        Ok((task, _)) => locale::format(locale, Text::TaskCreated, &[("task", &task.title)]),
+        Err(message) => message.clone(),
+    };
+
+    // interactions have to be answered within three seconds, so the reply
+    // goes out before the jobs are registered
+    modal
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| d.content(reply).ephemeral(true))
+        })
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    locale::remember(
+        db_client,
+        *modal.user.id.as_u64() as i64,
+        &modal.locale,
+        modal.guild_id.map(|id| *id.as_u64() as i64),
+        modal.guild_locale.as_deref(),
+    )
+    .await;
+
+    if let Ok((task, guild_id)) = result {
+        jobs::register(task.id)
+            .await
+            .map_err(|e| error!("{}", e))
+            .ok();
+
+        post_task(
+            &ctx.http,
+            db_client,
+            &env.shamebot_url,
+            task.id,
+            guild_id,
+            None,
+        )
+        .await;
+    }
+}
+
+// returns a user-facing message on failure
+async fn create_task_from_modal(
+    modal: &ModalSubmitInteraction,
+    db_client: &DbClient,
+    locale: Locale,
+) -> Result<(Task, i64), String> {
+    let values = modal_values(modal);
+
+    let guild_id = match modal.guild_id {
+        Some(guild_id) => *guild_id.as_u64() as i64,
+        None => return Err(locale::text(locale, Text::GuildOnly).to_string()),
+    };
+
+    let guild = Guild::get(db_client, guild_id)
+        .await
+        .map_err(|e| error!("{}", e))
+        .ok()
+        .flatten();
+
+    if guild.is_none() {
+        return Err(locale::text(locale, Text::GuildNotSetUp).to_string());
+    }
+
+    let now = Utc::now().timestamp();
+    let due_at = parse_due_at(input_value(&values, "due_at"), now, locale)?;
+    let pester = parse_pester(input_value(&values, "pester"), locale)?;
+    let content = match input_value(&values, "content") {
+        "" => None,
+        content => Some(content.to_string()),
+    };
+
+    let user = upsert_user(db_client, &modal.user, locale).await?;
+    let list =
+        find_or_create_list(db_client, user.id, input_value(&values, "list"), locale).await?;
+
+    let new_task = NewTask {
+        list_id: list.id,
+        user_id: user.id,
+        guild_id,
+        title: input_value(&values, "title").to_string(),
+        content,
+        pester,
+        due_at,
+        ..NewTask::default()
+    };
+
+    Task::new(db_client, new_task, Some(user.id))
+        .await
+        .map(|task| (task, guild_id))
+        .map_err(|e| {
+            error!("{}", e);
+            locale::text(locale, Text::CreateFailed).to_string()
+        })
+}
+
+fn modal_values(modal: &ModalSubmitInteraction) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+
+    for row in &modal.data.components {
+        for component in &row.components {
+            if let ActionRowComponent::InputText(input) = component {
+                values.insert(input.custom_id.clone(), input.value.clone());
+            }
+        }
+    }
+
+    values
+}
+
+fn input_value<'a>(values: &'a HashMap<String, String>, key: &str) -> &'a str {
+    values.get(key).map(|v| v.trim()).unwrap_or_default()
+}
+
+// accepts a UNIX timestamp, a UTC date and time, or a bare date (end of day),
+// as long as it's after `now`
+fn parse_due_at(value: &str, now: i64, locale: Locale) -> Result<Option<i64>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let timestamp = value
+        .parse::<i64>()
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+                .ok()
+                .map(|datetime| datetime.and_utc().timestamp())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(23, 59, 0))
+                .map(|datetime| datetime.and_utc().timestamp())
+        })
+        .ok_or_else(|| locale::format(locale, Text::InvalidDueDate, &[("value", value)]))?;
+
+    if timestamp <= now {
+        return Err(locale::format(
+            locale,
+            Text::PastDueDate,
+            &[("value", value)],
+        ));
+    }
+
+    Ok(Some(timestamp))
+}
+
+fn parse_pester(value: &str, locale: Locale) -> Result<Option<i16>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    match value.parse::<i16>() {
+        Ok(hours) if hours > 0 => Ok(Some(hours)),
+        _ => Err(locale::format(
+            locale,
+            Text::InvalidPester,
+            &[("value", value)],
+        )),
+    }
+}
+
+async fn upsert_user(db_client: &DbClient, user: &User, locale: Locale) -> Result<DbUser, String> {
+    DbUser::new(
+        db_client,
+        *user.id.as_u64() as i64,
+        user.name.clone(),
+        user.discriminator.to_string(),
+        user.avatar.clone().unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| {
+        error!("{}", e);
+        locale::text(locale, Text::UserLookupFailed).to_string()
+    })
+}
+
+// the modal has no room for a real picker, so lists are matched by title
+// and created if the user doesn't have one with that name yet
+async fn find_or_create_list(
+    db_client: &DbClient,
+    user_id: i64,
+    title: &str,
+    locale: Locale,
+) -> Result<List, String> {
+    let existing = List::get_by_title(db_client, user_id, title).await;
+
+    let list = match existing {
+        Ok(Some(list)) => Ok(list),
+        Ok(None) => List::new(db_client, title.to_string(), user_id).await,
+        Err(e) => Err(e),
+    };
+
+    list.map_err(|e| {
+        error!("{}", e);
+        locale::text(locale, Text::ListLookupFailed).to_string()
+    })
+}
+
+async fn handle_list_command(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    db_client: &DbClient,
+    env: &Env,
+    locale: Locale,
+) {
+    let subcommand = command.data.options.first();
+
+    if subcommand.map(|o| o.name.as_str()) != Some("show") {
+        return;
+    }
+
+    let list_id = subcommand
+        .and_then(|o| option_value(&o.options, "list"))
+        .and_then(|id| Uuid::parse_str(id).ok());
+
+    let list = match list_id {
+        Some(list_id) => List::get(db_client, list_id)
+            .await
+            .map_err(|e| error!("{}", e))
+            .ok()
+            .flatten(),
+        None => None,
+    };
+
+    match (list, command.guild_id) {
+        (Some(list), Some(guild_id)) if list.user_id == *command.user.id.as_u64() as i64 => {
+            let reply = locale::format(locale, Text::PostingList, &[("list", &list.title)]);
+            respond(ctx, command, &reply).await;
+            post_list(
+                &ctx.http,
+                db_client,
+                &env.shamebot_url,
+                list.id,
+                *guild_id.as_u64() as i64,
+            )
+            .await;
+        }
+        _ => respond(ctx, command, locale::text(locale, Text::ListNotFound)).await,
+    }
+}
+
+// the guild's top balances, followed by the invoking user's own
+async fn handle_points_command(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    db_client: &DbClient,
+    locale: Locale,
+) {
+    let guild_id = match command.guild_id {
+        Some(guild_id) => *guild_id.as_u64() as i64,
+        None => {
+            respond(ctx, command, locale::text(locale, Text::GuildOnly)).await;
+            return;
+        }
+    };
+    let user_id = *command.user.id.as_u64() as i64;
+
+    let balances = Balance::for_guild(db_client, guild_id)
+        .await
+        .map_err(|e| error!("{}", e))
+        .unwrap_or_default();
+
+    if balances.is_empty() {
+        respond(ctx, command, locale::text(locale, Text::PointsEmpty)).await;
+        return;
+    }
+
+    let mut lines = vec![locale::text(locale, Text::PointsTitle).to_string()];
+
+    for (rank, balance) in balances.iter().take(POINTS_SIZE).enumerate() {
+        lines.push(locale::format(
+            locale,
+            Text::PointsEntry,
+            &[
+                ("rank", &(rank + 1).to_string()),
+                ("user", &format!("<@{:?}>", balance.user_id)),
+                ("points", &balance.points.to_string()),
+            ],
+        ));
+    }
+
+    let own = balances
+        .iter()
+        .find(|balance| balance.user_id == user_id)
+        .map_or(0, |balance| balance.points);
+
+    lines.push(String::new());
+    lines.push(locale::format(
+        locale,
+        Text::PointsOwn,
+        &[("points", &own.to_string())],
+    ));
+
+    respond(ctx, command, &lines.join("\n")).await;
+}
+
+// suggestions are limited to the invoking user's own lists and unchecked
+// tasks in the current guild, with the id as the value that gets submitted
+async fn handle_autocomplete(
+    ctx: &Context,
+    autocomplete: &AutocompleteInteraction,
+    db_client: &DbClient,
+) {
+    let guild_id = match autocomplete.guild_id {
+        Some(guild_id) => *guild_id.as_u64() as i64,
+        None => return,
+    };
+    let user_id = *autocomplete.user.id.as_u64() as i64;
+
+    let focused = autocomplete
+        .data
+        .options
+        .iter()
+        .flat_map(|o| o.options.iter())
+        .find(|o| o.focused);
+
+    let focused = match focused {
+        Some(focused) => focused,
+        None => return,
+    };
+    let prefix = focused
+        .value
+        .as_ref()
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+
+    let choices: Vec<(String, String)> = match focused.name.as_str() {
+        "task" => Task::search_unchecked(db_client, user_id, guild_id, prefix)
+            .await
+            .map(|tasks| {
+                tasks
+                    .into_iter()
+                    .map(|t| (t.title, t.id.to_string()))
+                    .collect()
+            }),
+        "list" => List::search_titles(db_client, user_id, guild_id, prefix)
+            .await
+            .map(|lists| {
+                lists
+                    .into_iter()
+                    .map(|l| (l.title, l.id.to_string()))
+                    .collect()
+            }),
+        _ => Ok(Vec::new()),
+    }
+    .map_err(|e| error!("{}", e))
+    .unwrap_or_default();
+
+    autocomplete
+        .create_autocomplete_response(&ctx.http, |r| {
+            for (name, value) in choices {
+                r.add_string_choice(name, value);
+            }
+
+            r
+        })
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+}
+
+async fn respond(ctx: &Context, command: &ApplicationCommandInteraction, message: &str) {
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| d.content(message).ephemeral(true))
+        })
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+}
+
+async fn respond_component(ctx: &Context, component: &MessageComponentInteraction, message: &str) {
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| d.content(message).ephemeral(true))
+        })
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+}
+
+// replaces the message and removes its buttons, so a request can't be
+// answered twice
+async fn answer_component(ctx: &Context, component: &MessageComponentInteraction, message: &str) {
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| d.content(message).components(|c| c))
+        })
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+}
+
+fn option_value<'a>(options: &'a [CommandDataOption], name: &str) -> Option<&'a str> {
+    options
+        .iter()
+        .find(|o| o.name == name)
+        .and_then(|o| o.value.as_ref())
+        .and_then(|v| v.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draft_content_keeps_short_messages() {
+        let content = draft_content("buy milk", "https://discord.com/channels/1/2/3");
+        assert_eq!(content, "buy milk\n\nhttps://discord.com/channels/1/2/3");
+    }
+
+    #[test]
+    fn draft_content_fits_the_modal() {
+        let link = "https://discord.com/channels/1/2/3";
+        let content = draft_content(&"é".repeat(MAX_INPUT), link);
+        assert_eq!(content.chars().count(), MAX_INPUT);
+        assert!(content.ends_with(link));
+    }
+
+    #[test]
+    fn due_at_formats() {
+        let (now, locale) = (1700000000, Locale::English);
+        assert_eq!(parse_due_at("", now, locale), Ok(None));
+        assert_eq!(
+            parse_due_at("1700000060", now, locale),
+            Ok(Some(1700000060))
+        );
+        assert_eq!(
+            parse_due_at("2024-03-01 18:30", now, locale),
+            Ok(Some(1709317800))
+        );
+        // a date alone is due at the end of that day
+        assert_eq!(
+            parse_due_at("2024-03-01", now, locale),
+            Ok(Some(1709337540))
+        );
+    }
+
+    #[test]
+    fn due_at_rejects_anything_else() {
+        let (now, locale) = (1700000000, Locale::English);
+        assert!(parse_due_at("tomorrow", now, locale).is_err());
+        assert!(parse_due_at("2024-13-01", now, locale).is_err());
+    }
+
+    #[test]
+    fn due_at_rejects_the_past() {
+        let (now, locale) = (1700000000, Locale::English);
+        assert!(parse_due_at("5", now, locale).is_err());
+        assert!(parse_due_at("1700000000", now, locale).is_err());
+        assert!(parse_due_at("2023-01-01 12:00", now, locale).is_err());
+        assert!(parse_due_at("2023-01-01", now, locale).is_err());
+    }
+
+    #[test]
+    fn pester_hours() {
+        let locale = Locale::English;
+        assert_eq!(parse_pester("", locale), Ok(None));
+        assert_eq!(parse_pester("4", locale), Ok(Some(4)));
+        assert!(parse_pester("0", locale).is_err());
+        assert!(parse_pester("-2", locale).is_err());
+        assert!(parse_pester("often", locale).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use log::warn;
 use std::env;
 
+#[derive(Clone)]
 pub struct Env {
     pub discord_token: String,
     pub shamebot_url: String,
@@ -0,0 +1,52 @@
+// the pester, reminder and overdue jobs of a task live in the cron service,
+// which schedules them from the task's due date and pester interval
+use database::prelude::{ChangeKind, PendingChange, Task};
+use log::error;
+use uuid::Uuid;
+
+const JOBS_URL: &str = "http://cron:8080/jobs";
+
+pub(crate) async fn register(task_id: Uuid) -> Result<(), reqwest::Error> {
+    reqwest::Client::new()
+        .post(format!("{}/{:?}", JOBS_URL, task_id))
+        .send()
+        .await?
+        .error_for_status()
+        .map(|_| ())
+}
+
+// deleted tasks don't keep their jobs, they're registered again on restore
+pub(crate) async fn stop(task_id: Uuid) -> Result<(), reqwest::Error> {
+    reqwest::Client::new()
+        .delete(JOBS_URL)
+        .json(&[task_id])
+        .send()
+        .await?
+        .error_for_status()
+        .map(|_| ())
+}
+
+// the old jobs keep firing on the old schedule until they're stopped
+pub(crate) async fn reschedule(task_id: Uuid) -> Result<(), reqwest::Error> {
+    stop(task_id).await?;
+    register(task_id).await
+}
+
+// whether going from `previous` to `task` moves its jobs
+pub fn rescheduled(previous: &Task, task: &Task) -> bool {
+    task.due_at != previous.due_at || task.pester != previous.pester
+}
+
+// keeps the jobs in step with a change a partner approved, `task` is the task
+// from before the approval. used by the api and the change buttons alike
+pub async fn approved(change: &PendingChange, task: &Task) {
+    let moved = change.due_at != task.due_at || change.pester != task.pester;
+
+    let result = match change.kind {
+        ChangeKind::Delete => stop(task.id).await,
+        ChangeKind::Update if moved => reschedule(task.id).await,
+        _ => Ok(()),
+    };
+
+    result.map_err(|e| error!("{}", e)).ok();
+}
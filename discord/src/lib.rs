@@ -1,2 +1,9 @@
 pub mod bot;
+mod channels;
+mod commands;
 mod environment;
+pub mod jobs;
+pub mod locale;
+mod shame;
+pub mod templates;
+mod threads;
@@ -0,0 +1,685 @@
+// message catalogs for everything the bot says outside of templates.
+// discord sends the user's client locale (and the community locale of the
+// guild) with every interaction, which is remembered for users and guilds
+// that haven't picked a language themselves.
+//
+// catalog entries use the same {name} placeholders as templates
+use database::prelude::{Client as DbClient, Guild, User as DbUser};
+use log::error;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    French,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::English, Locale::German, Locale::French];
+
+    // discord uses tags like en-US, en-GB, de and fr, regional variants all
+    // share one catalog
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split('-').next().unwrap_or_default();
+
+        match language.to_lowercase().as_str() {
+            "en" => Some(Locale::English),
+            "de" => Some(Locale::German),
+            "fr" => Some(Locale::French),
+            _ => None,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::English => "en-US",
+            Locale::German => "de",
+            Locale::French => "fr",
+        }
+    }
+
+    // the first of the user's and the guild's locales that has a catalog
+    pub fn resolve(user: Option<&str>, guild: Option<&str>) -> Self {
+        user.and_then(Locale::from_tag)
+            .or_else(|| guild.and_then(Locale::from_tag))
+            .unwrap_or_default()
+    }
+}
+
+// the locale messages addressed to a user should be written in
+pub(crate) async fn for_user(db_client: &DbClient, user_id: i64, guild: Option<&Guild>) -> Locale {
+    let user = DbUser::get(db_client, user_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .flatten();
+
+    Locale::resolve(
+        user.as_ref().and_then(|u| u.locale.as_deref()),
+        guild.and_then(|g| g.locale.as_deref()),
+    )
+}
+
+// remembers the locales discord reports for an interaction, without
+// overriding anything that was set explicitly
+pub(crate) async fn remember(
+    db_client: &DbClient,
+    user_id: i64,
+    locale: &str,
+    guild_id: Option<i64>,
+    guild_locale: Option<&str>,
+) {
+    DbUser::default_locale(db_client, user_id, locale.to_string())
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    if let (Some(guild_id), Some(guild_locale)) = (guild_id, guild_locale) {
+        Guild::default_locale(db_client, guild_id, guild_locale.to_string())
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Text {
+    // command descriptions
+    TaskCommand,
+    TaskNewCommand,
+    TaskDoneCommand,
+    TaskDoneOption,
+    TaskPriorityCommand,
+    TaskTagCommand,
+    TaskUntagCommand,
+    TaskOption,
+    TaskPriorityOption,
+    TaskTagOption,
+    PriorityLow,
+    PriorityNormal,
+    PriorityHigh,
+    PriorityUrgent,
+    ListCommand,
+    ListShowCommand,
+    ListShowOption,
+    MessageToTaskCommand,
+    PointsCommand,
+    // command replies
+    TaskDone,
+    TaskNotFound,
+    CheckOffFailed,
+    PrioritySet,
+    TagAdded,
+    TagRemoved,
+    InvalidTag,
+    UpdateFailed,
+    TaskCreated,
+    GuildOnly,
+    GuildNotSetUp,
+    InvalidDueDate,
+    PastDueDate,
+    InvalidPester,
+    CreateFailed,
+    UserLookupFailed,
+    ListLookupFailed,
+    PostingList,
+    ListNotFound,
+    PointsTitle,
+    PointsEntry,
+    PointsOwn,
+    PointsEmpty,
+    // task modal
+    ModalTitle,
+    TitleLabel,
+    DescriptionLabel,
+    DueLabel,
+    PesterLabel,
+    ListLabel,
+    ListPlaceholder,
+    // embeds and notices
+    AccountabilityRequestTitle,
+    AccountabilityRequestDescription,
+    TaskField,
+    ProofTitle,
+    ProofDescription,
+    Comment,
+    Finished,
+    ChecklistProgress,
+    RemainingItems,
+    Unblocked,
+    Assigned,
+    ListProgress,
+    TaskOwner,
+    ListOwner,
+    ThreadStarter,
+    ShameFailed,
+    StakeLost,
+    StakeWon,
+    Penalty,
+    // changes that need the partner's approval
+    AwaitingPartner,
+    ChangeRequestTitle,
+    ChangeRequestDescription,
+    ChangesField,
+    ChangeDelete,
+    ChangeUnpartner,
+    ChangeUnpartnerAll,
+    ChangeDue,
+    ChangePester,
+    ChangeQuorum,
+    ChangeStake,
+    QuorumAny,
+    QuorumMajority,
+    QuorumAll,
+    ChangeCheckOff,
+    ApproveButton,
+    RejectButton,
+    ChangeApproved,
+    ChangeRejected,
+    ChangeAnswered,
+    NotThePartner,
+    // standing partnerships
+    PartnershipRequestTitle,
+    PartnershipListDescription,
+    PartnershipGuildDescription,
+    AcceptButton,
+    PartnershipAccepted,
+    PartnershipRejected,
+    DigestTitle,
+    DigestEntry,
+    DigestEntryNoDue,
+    DigestMore,
+    // leaderboard
+    LeaderboardTitle,
+    LeaderboardEntry,
+    LeaderboardStreak,
+    HallOfShame,
+    ShameEntry,
+    OnTimeStreak,
+    // relative time
+    Minute,
+    Minutes,
+    Hour,
+    Hours,
+    Day,
+    Days,
+}
+
+pub fn text(locale: Locale, text: Text) -> &'static str {
+    match locale {
+        Locale::English => english(text),
+        Locale::German => german(text),
+        Locale::French => french(text),
+    }
+}
+
+pub fn format(locale: Locale, text: Text, args: &[(&str, &str)]) -> String {
+    let mut message = self::text(locale, text).to_string();
+
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+
+    message
+}
+
+// phrases a span of time the way it would follow "you have ...", rounded
+// down to the largest unit that fits
+pub fn duration(locale: Locale, seconds: i64) -> String {
+    let minutes = (seconds / 60).max(1);
+    let hours = minutes / 60;
+    let days = hours / 24;
+
+    let (one, many, n) = if days > 0 {
+        (Text::Day, Text::Days, days)
+    } else if hours > 0 {
+        (Text::Hour, Text::Hours, hours)
+    } else {
+        (Text::Minute, Text::Minutes, minutes)
+    };
+
+    if n == 1 {
+        text(locale, one).to_string()
+    } else {
+        format(locale, many, &[("n", &n.to_string())])
+    }
+}
+
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::TaskCommand => "manage your tasks",
+        Text::TaskNewCommand => "create a new task",
+        Text::TaskDoneCommand => "check off one of your tasks",
+        Text::TaskDoneOption => "the task you finished",
+        Text::TaskPriorityCommand => "Set how urgent one of your tasks is",
+        Text::TaskTagCommand => "Tag one of your tasks",
+        Text::TaskUntagCommand => "Remove a tag from one of your tasks",
+        Text::TaskOption => "one of your unfinished tasks",
+        Text::TaskPriorityOption => "how urgent the task is",
+        Text::TaskTagOption => "the tag",
+        Text::PriorityLow => "low",
+        Text::PriorityNormal => "normal",
+        Text::PriorityHigh => "high",
+        Text::PriorityUrgent => "urgent",
+        Text::ListCommand => "manage your lists",
+        Text::ListShowCommand => "post one of your lists to this server's channel",
+        Text::ListShowOption => "the list to show",
+        Text::MessageToTaskCommand => "Turn into task",
+        Text::PointsCommand => "Show the shame points in this server",
+        Text::TaskDone => "nice work, **{task}** is done!",
+        Text::TaskNotFound => "couldn't find that task, pick one from the suggestions.",
+        Text::CheckOffFailed => "something went wrong checking off your task.",
+        Text::PrioritySet => "**{task}** now has {priority} priority.",
+        Text::TagAdded => "tagged **{task}** with `{tag}`.",
+        Text::TagRemoved => "removed `{tag}` from **{task}**.",
+        Text::InvalidTag => "tags can't be empty or longer than {max} characters.",
+        Text::UpdateFailed => "something went wrong updating your task.",
+        Text::TaskCreated => "created **{task}**",
+        Text::GuildOnly => "tasks can only be created in a server.",
+        Text::GuildNotSetUp => "this server hasn't been set up with shamebot yet.",
+        Text::InvalidDueDate => "couldn't understand the due date `{value}`.",
+        Text::PastDueDate => "the due date `{value}` has already passed.",
+        Text::InvalidPester => "`{value}` isn't a valid number of hours.",
+        Text::CreateFailed => "something went wrong creating your task.",
+        Text::UserLookupFailed => "something went wrong looking you up.",
+        Text::ListLookupFailed => "something went wrong finding that list.",
+        Text::PostingList => "posting **{list}**",
+        Text::ListNotFound => "couldn't find that list, pick one from the suggestions.",
+        Text::PointsTitle => "**Shame points**",
+        Text::PointsEntry => "{rank}. {user}: {points}",
+        Text::PointsOwn => "you have {points} points.",
+        Text::PointsEmpty => "nobody has won or lost any points here yet.",
+        Text::ModalTitle => "New task",
+        Text::TitleLabel => "Title",
+        Text::DescriptionLabel => "Description",
+        Text::DueLabel => "Due (UTC)",
+        Text::PesterLabel => "Pester every (hours)",
+        Text::ListLabel => "List",
+        Text::ListPlaceholder => "created if it doesn't exist yet",
+        Text::AccountabilityRequestTitle => "Accountability Request",
+        Text::AccountabilityRequestDescription => {
+            "{user} has requested you as an accountability partner."
+        }
+        Text::TaskField => "Task",
+        Text::ProofTitle => "Proof for {task}",
+        Text::ProofDescription => "{user} says they're done.\n\n{content}",
+        Text::Comment => "{user} commented:\n> {content}",
+        Text::Finished => "Finished: {checkbox}",
+        Text::ChecklistProgress => "Checklist: {done}/{total} done",
+        Text::RemainingItems => "still to do:",
+        Text::Unblocked => {
+            "{user}, **{blocker}** is done, so nothing is holding up **{task}** anymore."
+        }
+        Text::Assigned => "{user}, {actor} handed **{task}** from **{list}** to you.",
+        Text::ListProgress => {
+            "{user} finished **{task}**. **{list}** is at {done}/{total} tasks done."
+        }
+        Text::TaskOwner => "for {user}",
+        Text::ListOwner => "Owner",
+        Text::ThreadStarter => "**{task}** for {user}",
+        Text::ShameFailed => {
+            "i tried to shame someone but couldn't: {reason}. please check the shame settings."
+        }
+        Text::StakeLost => "{user} loses {points} points.",
+        Text::StakeWon => "the stake goes to {partners}.",
+        Text::Penalty => "{user} pledged: _{penalty}_",
+        Text::AwaitingPartner => "a partner has to approve this first, i've asked {partners}.",
+        Text::ChangeRequestTitle => "Change request",
+        Text::ChangeRequestDescription => {
+            "{user} wants to change a task you're keeping them accountable for."
+        }
+        Text::ChangesField => "Requested changes",
+        Text::ChangeDelete => "delete the task",
+        Text::ChangeUnpartner => "remove {partner} as a partner",
+        Text::ChangeUnpartnerAll => "remove every partner",
+        Text::ChangeDue => "move the due date from {from} to {to}",
+        Text::ChangePester => "pester every {to} hours instead of {from}",
+        Text::ChangeQuorum => "have proof approved by {to} instead of {from}",
+        Text::ChangeStake => "lower the stake from {from} to {to} points",
+        Text::QuorumAny => "any partner",
+        Text::QuorumMajority => "a majority of partners",
+        Text::QuorumAll => "all partners",
+        Text::ChangeCheckOff => "check it off without proof",
+        Text::ApproveButton => "Approve",
+        Text::RejectButton => "Reject",
+        Text::ChangeApproved => "you approved the change to **{task}**.",
+        Text::ChangeRejected => "you rejected the change to **{task}**.",
+        Text::ChangeAnswered => "this request has already been answered.",
+        Text::NotThePartner => "only the accountability partner can answer this request.",
+        Text::PartnershipRequestTitle => "Partnership request",
+        Text::PartnershipListDescription => {
+            "{user} would like you as their accountability partner for every task on **{list}**."
+        }
+        Text::PartnershipGuildDescription => {
+            "{user} would like you as their accountability partner for all of their tasks in **{guild}**."
+        }
+        Text::AcceptButton => "Accept",
+        Text::PartnershipAccepted => {
+            "you're now {user}'s partner, their new tasks will show up in your daily digest."
+        }
+        Text::PartnershipRejected => "you turned down {user}'s request.",
+        Text::DigestTitle => "New tasks from the people you keep accountable",
+        Text::DigestEntry => "- **{task}**, due {due}",
+        Text::DigestEntryNoDue => "- **{task}**",
+        Text::DigestMore => "and {n} more",
+        Text::LeaderboardTitle => "Weekly leaderboard",
+        Text::LeaderboardEntry => "{rank}. {user}: {completed}/{total} done ({rate}%)",
+        Text::LeaderboardStreak => ", {streak} in a row",
+        Text::HallOfShame => "Hall of shame",
+        Text::ShameEntry => "{user}: {overdue} overdue",
+        Text::OnTimeStreak => "{streak} on time in a row!",
+        Text::Minute => "one minute",
+        Text::Minutes => "{n} minutes",
+        Text::Hour => "one hour",
+        Text::Hours => "{n} hours",
+        Text::Day => "one day",
+        Text::Days => "{n} days",
+    }
+}
+
+fn german(text: Text) -> &'static str {
+    match text {
+        Text::TaskCommand => "verwalte deine Aufgaben",
+        Text::TaskNewCommand => "erstelle eine neue Aufgabe",
+        Text::TaskDoneCommand => "hake eine deiner Aufgaben ab",
+        Text::TaskDoneOption => "die Aufgabe, die du erledigt hast",
+        Text::TaskPriorityCommand => "Lege fest, wie dringend eine deiner Aufgaben ist",
+        Text::TaskTagCommand => "Versieh eine deiner Aufgaben mit einem Tag",
+        Text::TaskUntagCommand => "Entferne einen Tag von einer deiner Aufgaben",
+        Text::TaskOption => "eine deiner offenen Aufgaben",
+        Text::TaskPriorityOption => "wie dringend die Aufgabe ist",
+        Text::TaskTagOption => "der Tag",
+        Text::PriorityLow => "niedrig",
+        Text::PriorityNormal => "normal",
+        Text::PriorityHigh => "hoch",
+        Text::PriorityUrgent => "dringend",
+        Text::ListCommand => "verwalte deine Listen",
+        Text::ListShowCommand => "poste eine deiner Listen im Kanal dieses Servers",
+        Text::ListShowOption => "die Liste, die gezeigt werden soll",
+        Text::MessageToTaskCommand => "In Aufgabe umwandeln",
+        Text::PointsCommand => "Zeigt die Schandpunkte auf diesem Server",
+        Text::TaskDone => "gut gemacht, **{task}** ist erledigt!",
+        Text::TaskNotFound => {
+            "diese Aufgabe wurde nicht gefunden, wähle eine aus den Vorschlägen."
+        }
+        Text::CheckOffFailed => "beim Abhaken deiner Aufgabe ist etwas schiefgelaufen.",
+        Text::PrioritySet => "**{task}** hat jetzt die Priorität {priority}.",
+        Text::TagAdded => "**{task}** hat jetzt den Tag `{tag}`.",
+        Text::TagRemoved => "`{tag}` wurde von **{task}** entfernt.",
+        Text::InvalidTag => "Tags dürfen nicht leer oder länger als {max} Zeichen sein.",
+        Text::UpdateFailed => "beim Aktualisieren deiner Aufgabe ist etwas schiefgelaufen.",
+        Text::TaskCreated => "**{task}** wurde erstellt",
+        Text::GuildOnly => "Aufgaben können nur auf einem Server erstellt werden.",
+        Text::GuildNotSetUp => "dieser Server wurde noch nicht mit shamebot eingerichtet.",
+        Text::InvalidDueDate => "das Fälligkeitsdatum `{value}` ist unverständlich.",
+        Text::PastDueDate => "das Fälligkeitsdatum `{value}` ist schon vorbei.",
+        Text::InvalidPester => "`{value}` ist keine gültige Anzahl an Stunden.",
+        Text::CreateFailed => "beim Erstellen deiner Aufgabe ist etwas schiefgelaufen.",
+        Text::UserLookupFailed => "beim Nachschlagen deines Kontos ist etwas schiefgelaufen.",
+        Text::ListLookupFailed => "beim Suchen der Liste ist etwas schiefgelaufen.",
+        Text::PostingList => "**{list}** wird gepostet",
+        Text::ListNotFound => "diese Liste wurde nicht gefunden, wähle eine aus den Vorschlägen.",
+        Text::PointsTitle => "**Schandpunkte**",
+        Text::PointsEntry => "{rank}. {user}: {points}",
+        Text::PointsOwn => "du hast {points} Punkte.",
+        Text::PointsEmpty => "hier hat noch niemand Punkte gewonnen oder verloren.",
+        Text::ModalTitle => "Neue Aufgabe",
+        Text::TitleLabel => "Titel",
+        Text::DescriptionLabel => "Beschreibung",
+        Text::DueLabel => "Fällig (UTC)",
+        Text::PesterLabel => "Erinnern alle (Stunden)",
+        Text::ListLabel => "Liste",
+        Text::ListPlaceholder => "wird erstellt, falls sie noch nicht existiert",
+        Text::AccountabilityRequestTitle => "Anfrage als Verantwortungspartner",
+        Text::AccountabilityRequestDescription => {
+            "{user} möchte dich als Verantwortungspartner."
+        }
+        Text::TaskField => "Aufgabe",
+        Text::ProofTitle => "Beweis für {task}",
+        Text::ProofDescription => "{user} sagt, die Aufgabe ist erledigt.\n\n{content}",
+        Text::Comment => "{user} hat kommentiert:\n> {content}",
+        Text::Finished => "Erledigt: {checkbox}",
+        Text::ChecklistProgress => "Checkliste: {done}/{total} erledigt",
+        Text::RemainingItems => "noch offen:",
+        Text::Unblocked => {
+            "{user}, **{blocker}** ist erledigt, **{task}** steht jetzt nichts mehr im Weg."
+        }
+        Text::Assigned => "{user}, {actor} hat dir **{task}** aus **{list}** zugeteilt.",
+        Text::ListProgress => {
+            "{user} hat **{task}** erledigt. In **{list}** sind {done}/{total} Aufgaben erledigt."
+        }
+        Text::TaskOwner => "für {user}",
+        Text::ListOwner => "Besitzer",
+        Text::ThreadStarter => "**{task}** für {user}",
+        Text::ShameFailed => {
+            "ich wollte jemanden bloßstellen, konnte aber nicht: {reason}. bitte prüft die Einstellungen."
+        }
+        Text::StakeLost => "{user} verliert {points} Punkte.",
+        Text::StakeWon => "der Einsatz geht an {partners}.",
+        Text::Penalty => "{user} hat versprochen: _{penalty}_",
+        Text::AwaitingPartner => "das muss erst ein Partner bestätigen, ich habe {partners} gefragt.",
+        Text::ChangeRequestTitle => "Änderungsanfrage",
+        Text::ChangeRequestDescription => {
+            "{user} möchte eine Aufgabe ändern, für die du Verantwortungspartner bist."
+        }
+        Text::ChangesField => "Gewünschte Änderungen",
+        Text::ChangeDelete => "die Aufgabe löschen",
+        Text::ChangeUnpartner => "{partner} als Partner entfernen",
+        Text::ChangeUnpartnerAll => "alle Partner entfernen",
+        Text::ChangeDue => "das Fälligkeitsdatum von {from} auf {to} verschieben",
+        Text::ChangePester => "alle {to} statt alle {from} Stunden erinnern",
+        Text::ChangeQuorum => "Beweise von {to} statt von {from} bestätigen lassen",
+        Text::ChangeStake => "den Einsatz von {from} auf {to} Punkte senken",
+        Text::QuorumAny => "einem beliebigen Partner",
+        Text::QuorumMajority => "der Mehrheit der Partner",
+        Text::QuorumAll => "allen Partnern",
+        Text::ChangeCheckOff => "ohne Beweis abhaken",
+        Text::ApproveButton => "Bestätigen",
+        Text::RejectButton => "Ablehnen",
+        Text::ChangeApproved => "du hast die Änderung an **{task}** bestätigt.",
+        Text::ChangeRejected => "du hast die Änderung an **{task}** abgelehnt.",
+        Text::ChangeAnswered => "diese Anfrage wurde bereits beantwortet.",
+        Text::NotThePartner => "nur der Verantwortungspartner kann diese Anfrage beantworten.",
+        Text::PartnershipRequestTitle => "Partnerschaftsanfrage",
+        Text::PartnershipListDescription => {
+            "{user} möchte dich als Verantwortungspartner für alle Aufgaben auf **{list}**."
+        }
+        Text::PartnershipGuildDescription => {
+            "{user} möchte dich als Verantwortungspartner für alle Aufgaben auf **{guild}**."
+        }
+        Text::AcceptButton => "Annehmen",
+        Text::PartnershipAccepted => {
+            "du bist jetzt Partner von {user}, neue Aufgaben erscheinen in deiner täglichen Übersicht."
+        }
+        Text::PartnershipRejected => "du hast die Anfrage von {user} abgelehnt.",
+        Text::DigestTitle => "Neue Aufgaben der Leute, für die du Partner bist",
+        Text::DigestEntry => "- **{task}**, fällig {due}",
+        Text::DigestEntryNoDue => "- **{task}**",
+        Text::DigestMore => "und {n} weitere",
+        Text::LeaderboardTitle => "Wochenrangliste",
+        Text::LeaderboardEntry => "{rank}. {user}: {completed}/{total} erledigt ({rate}%)",
+        Text::LeaderboardStreak => ", {streak} in Folge",
+        Text::HallOfShame => "Ruhmeshalle der Schande",
+        Text::ShameEntry => "{user}: {overdue} überfällig",
+        Text::OnTimeStreak => "{streak} pünktlich in Folge!",
+        Text::Minute => "eine Minute",
+        Text::Minutes => "{n} Minuten",
+        Text::Hour => "eine Stunde",
+        Text::Hours => "{n} Stunden",
+        Text::Day => "einen Tag",
+        Text::Days => "{n} Tage",
+    }
+}
+
+fn french(text: Text) -> &'static str {
+    match text {
+        Text::TaskCommand => "gère tes tâches",
+        Text::TaskNewCommand => "crée une nouvelle tâche",
+        Text::TaskDoneCommand => "coche une de tes tâches",
+        Text::TaskDoneOption => "la tâche que tu as terminée",
+        Text::TaskPriorityCommand => "Définis l'urgence d'une de tes tâches",
+        Text::TaskTagCommand => "Ajoute un tag à une de tes tâches",
+        Text::TaskUntagCommand => "Retire un tag d'une de tes tâches",
+        Text::TaskOption => "une de tes tâches en cours",
+        Text::TaskPriorityOption => "l'urgence de la tâche",
+        Text::TaskTagOption => "le tag",
+        Text::PriorityLow => "basse",
+        Text::PriorityNormal => "normale",
+        Text::PriorityHigh => "haute",
+        Text::PriorityUrgent => "urgente",
+        Text::ListCommand => "gère tes listes",
+        Text::ListShowCommand => "publie une de tes listes dans le salon de ce serveur",
+        Text::ListShowOption => "la liste à afficher",
+        Text::MessageToTaskCommand => "Transformer en tâche",
+        Text::PointsCommand => "Affiche les points de honte de ce serveur",
+        Text::TaskDone => "bien joué, **{task}** est terminée !",
+        Text::TaskNotFound => "tâche introuvable, choisis-en une parmi les suggestions.",
+        Text::CheckOffFailed => "un problème est survenu en cochant ta tâche.",
+        Text::PrioritySet => "**{task}** a maintenant une priorité {priority}.",
+        Text::TagAdded => "**{task}** a maintenant le tag `{tag}`.",
+        Text::TagRemoved => "`{tag}` a été retiré de **{task}**.",
+        Text::InvalidTag => "les tags ne peuvent pas être vides ni dépasser {max} caractères.",
+        Text::UpdateFailed => "un problème est survenu en modifiant ta tâche.",
+        Text::TaskCreated => "**{task}** a été créée",
+        Text::GuildOnly => "les tâches ne peuvent être créées que sur un serveur.",
+        Text::GuildNotSetUp => "ce serveur n'a pas encore été configuré avec shamebot.",
+        Text::InvalidDueDate => "impossible de comprendre l'échéance `{value}`.",
+        Text::PastDueDate => "l'échéance `{value}` est déjà passée.",
+        Text::InvalidPester => "`{value}` n'est pas un nombre d'heures valide.",
+        Text::CreateFailed => "un problème est survenu en créant ta tâche.",
+        Text::UserLookupFailed => "un problème est survenu en cherchant ton compte.",
+        Text::ListLookupFailed => "un problème est survenu en cherchant cette liste.",
+        Text::PostingList => "publication de **{list}**",
+        Text::ListNotFound => "liste introuvable, choisis-en une parmi les suggestions.",
+        Text::PointsTitle => "**Points de honte**",
+        Text::PointsEntry => "{rank}. {user} : {points}",
+        Text::PointsOwn => "tu as {points} points.",
+        Text::PointsEmpty => "personne n'a encore gagné ou perdu de points ici.",
+        Text::ModalTitle => "Nouvelle tâche",
+        Text::TitleLabel => "Titre",
+        Text::DescriptionLabel => "Description",
+        Text::DueLabel => "Échéance (UTC)",
+        Text::PesterLabel => "Relancer toutes les (heures)",
+        Text::ListLabel => "Liste",
+        Text::ListPlaceholder => "créée si elle n'existe pas encore",
+        Text::AccountabilityRequestTitle => "Demande de partenaire de responsabilité",
+        Text::AccountabilityRequestDescription => {
+            "{user} t'a demandé d'être son partenaire de responsabilité."
+        }
+        Text::TaskField => "Tâche",
+        Text::ProofTitle => "Preuve pour {task}",
+        Text::ProofDescription => "{user} dit avoir terminé.\n\n{content}",
+        Text::Comment => "{user} a commenté :\n> {content}",
+        Text::Finished => "Terminée : {checkbox}",
+        Text::ChecklistProgress => "Liste : {done}/{total} faits",
+        Text::RemainingItems => "reste à faire :",
+        Text::Unblocked => "{user}, **{blocker}** est terminée, plus rien ne bloque **{task}**.",
+        Text::Assigned => "{user}, {actor} t'a confié **{task}** de **{list}**.",
+        Text::ListProgress => {
+            "{user} a terminé **{task}**. **{list}** en est à {done}/{total} tâches terminées."
+        }
+        Text::TaskOwner => "pour {user}",
+        Text::ListOwner => "Propriétaire",
+        Text::ThreadStarter => "**{task}** pour {user}",
+        Text::ShameFailed => {
+            "j'ai voulu afficher quelqu'un mais je n'ai pas pu : {reason}. vérifiez les paramètres."
+        }
+        Text::StakeLost => "{user} perd {points} points.",
+        Text::StakeWon => "la mise revient à {partners}.",
+        Text::Penalty => "{user} s'est engagé·e à : _{penalty}_",
+        Text::AwaitingPartner => {
+            "un·e partenaire doit d'abord approuver, j'ai demandé à {partners}."
+        }
+        Text::ChangeRequestTitle => "Demande de modification",
+        Text::ChangeRequestDescription => {
+            "{user} veut modifier une tâche dont tu es le ou la partenaire de responsabilité."
+        }
+        Text::ChangesField => "Modifications demandées",
+        Text::ChangeDelete => "supprimer la tâche",
+        Text::ChangeUnpartner => "retirer {partner} des partenaires",
+        Text::ChangeUnpartnerAll => "retirer tous les partenaires",
+        Text::ChangeDue => "déplacer l'échéance de {from} à {to}",
+        Text::ChangePester => "relancer toutes les {to} heures au lieu de {from}",
+        Text::ChangeQuorum => "faire approuver les preuves par {to} au lieu de {from}",
+        Text::ChangeStake => "baisser la mise de {from} à {to} points",
+        Text::QuorumAny => "n'importe quel·le partenaire",
+        Text::QuorumMajority => "la majorité des partenaires",
+        Text::QuorumAll => "tous les partenaires",
+        Text::ChangeCheckOff => "la cocher sans preuve",
+        Text::ApproveButton => "Approuver",
+        Text::RejectButton => "Refuser",
+        Text::ChangeApproved => "tu as approuvé la modification de **{task}**.",
+        Text::ChangeRejected => "tu as refusé la modification de **{task}**.",
+        Text::ChangeAnswered => "cette demande a déjà reçu une réponse.",
+        Text::NotThePartner => {
+            "seul·e le ou la partenaire de responsabilité peut répondre à cette demande."
+        }
+        Text::PartnershipRequestTitle => "Demande de partenariat",
+        Text::PartnershipListDescription => {
+            "{user} aimerait que tu sois son ou sa partenaire pour toutes les tâches de **{list}**."
+        }
+        Text::PartnershipGuildDescription => {
+            "{user} aimerait que tu sois son ou sa partenaire pour toutes ses tâches sur **{guild}**."
+        }
+        Text::AcceptButton => "Accepter",
+        Text::PartnershipAccepted => {
+            "tu es maintenant partenaire de {user}, ses nouvelles tâches apparaîtront dans ton résumé quotidien."
+        }
+        Text::PartnershipRejected => "tu as refusé la demande de {user}.",
+        Text::DigestTitle => "Nouvelles tâches des personnes que tu accompagnes",
+        Text::DigestEntry => "- **{task}**, pour {due}",
+        Text::DigestEntryNoDue => "- **{task}**",
+        Text::DigestMore => "et {n} de plus",
+        Text::LeaderboardTitle => "Classement de la semaine",
+        Text::LeaderboardEntry => "{rank}. {user} : {completed}/{total} terminées ({rate} %)",
+        Text::LeaderboardStreak => ", {streak} d'affilée",
+        Text::HallOfShame => "Mur de la honte",
+        Text::ShameEntry => "{user} : {overdue} en retard",
+        Text::OnTimeStreak => "{streak} à l'heure d'affilée !",
+        Text::Minute => "une minute",
+        Text::Minutes => "{n} minutes",
+        Text::Hour => "une heure",
+        Text::Hours => "{n} heures",
+        Text::Day => "un jour",
+        Text::Days => "{n} jours",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_tag_ignores_region_and_case() {
+        assert_eq!(Locale::from_tag("en-US"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("en-GB"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("DE"), Some(Locale::German));
+        assert_eq!(Locale::from_tag("fr"), Some(Locale::French));
+        assert_eq!(Locale::from_tag("pt-BR"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn resolve_prefers_the_user() {
+        assert_eq!(Locale::resolve(Some("fr"), Some("de")), Locale::French);
+        assert_eq!(Locale::resolve(Some("pt-BR"), Some("de")), Locale::German);
+        assert_eq!(Locale::resolve(None, None), Locale::English);
+    }
+
+    #[test]
+    fn duration_rounds_down() {
+        let locale = Locale::English;
+        assert_eq!(duration(locale, 0), "one minute");
+        assert_eq!(duration(locale, 59 * 60), "59 minutes");
+        assert_eq!(duration(locale, 90 * 60), "one hour");
+        assert_eq!(duration(locale, 47 * 60 * 60), "one day");
+        assert_eq!(duration(locale, 3 * 24 * 60 * 60 + 5), "3 days");
+    }
+
+    #[test]
+    fn duration_is_localized() {
+        assert_eq!(duration(Locale::German, 2 * 60 * 60), "2 Stunden");
+        assert_eq!(duration(Locale::French, 60), "une minute");
+    }
+}
@@ -0,0 +1,211 @@
+// optional penalties for users with overdue tasks: a configurable role and
+// a nickname prefix, both lifted once nothing is overdue anymore
+use database::prelude::{Client as DbClient, Guild, NotificationKind, Task};
+use log::{error, info};
+use serenity::{
+    http::Http,
+    model::prelude::{GuildId, Member, Permissions, RoleId, UserId},
+};
+
+use crate::channels::notification_channel;
+use crate::locale::{self, Locale, Text};
+
+// discord caps nicknames at 32 characters
+const MAX_NICKNAME: usize = 32;
+
+// checks that the bot is able to hand out the shame role (and change
+// nicknames if a prefix is configured), returns a user-facing reason otherwise
+pub(crate) async fn check_role(
+    http: &Http,
+    guild_id: u64,
+    role_id: u64,
+    prefix: bool,
+) -> Result<(), String> {
+    let roles = GuildId(guild_id)
+        .roles(http)
+        .await
+        .map_err(|e| format!("couldn't fetch the server's roles: {}", e))?;
+
+    let shame_role = roles
+        .get(&RoleId(role_id))
+        .ok_or_else(|| String::from("the shame role doesn't exist in this server"))?;
+
+    let me = http
+        .get_current_user()
+        .await
+        .map_err(|e| format!("couldn't fetch the bot user: {}", e))?;
+    let member = GuildId(guild_id)
+        .member(http, me.id)
+        .await
+        .map_err(|e| format!("couldn't fetch the bot's membership: {}", e))?;
+
+    // @everyone shares its id with the guild and applies to every member
+    let mut permissions = roles
+        .get(&RoleId(guild_id))
+        .map(|r| r.permissions)
+        .unwrap_or_else(Permissions::empty);
+    let mut highest = 0;
+
+    for role in member.roles.iter().filter_map(|id| roles.get(id)) {
+        permissions |= role.permissions;
+        highest = highest.max(role.position);
+    }
+
+    let admin = permissions.contains(Permissions::ADMINISTRATOR);
+
+    if !admin && !permissions.contains(Permissions::MANAGE_ROLES) {
+        return Err(String::from(
+            "the bot is missing the Manage Roles permission",
+        ));
+    }
+
+    if prefix && !admin && !permissions.contains(Permissions::MANAGE_NICKNAMES) {
+        return Err(String::from(
+            "the bot is missing the Manage Nicknames permission",
+        ));
+    }
+
+    if highest <= shame_role.position {
+        return Err(format!(
+            "the bot's highest role has to be above {} in the role list",
+            shame_role.name,
+        ));
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn apply(http: &Http, db_client: &DbClient, guild: &Guild, user_id: i64) {
+    let role_id = match guild.shame_role {
+        Some(role_id) => role_id as u64,
+        None => return,
+    };
+    let guild_id = guild.id as u64;
+
+    if let Err(reason) = check_role(http, guild_id, role_id, guild.shame_prefix.is_some()).await {
+        error!(
+            "unable to shame user {} in guild {}: {}",
+            user_id, guild_id, reason
+        );
+        report(http, db_client, guild, &reason).await;
+        return;
+    }
+
+    http.add_member_role(guild_id, user_id as u64, role_id, Some("overdue tasks"))
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    if let Some(prefix) = guild.shame_prefix.as_ref() {
+        let member = GuildId(guild_id)
+            .member(http, UserId(user_id as u64))
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+
+        if let Some(member) = member {
+            let current = display_name(&member);
+
+            if !current.starts_with(prefix.as_str()) {
+                let nickname: String = format!("{}{}", prefix, current)
+                    .chars()
+                    .take(MAX_NICKNAME)
+                    .collect();
+
+                set_nickname(http, guild_id, user_id as u64, &nickname).await;
+            }
+        }
+    }
+
+    info!("shamed user {} in guild {}", user_id, guild_id);
+}
+
+pub(crate) async fn lift(http: &Http, db_client: &DbClient, user_id: i64, guild_id: i64) {
+    let guild = Guild::get(db_client, guild_id)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .flatten();
+
+    let guild = match guild {
+        Some(guild) if guild.shame_role.is_some() => guild,
+        _ => return,
+    };
+
+    let overdue = Task::count_overdue(db_client, user_id, guild_id)
+        .await
+        .map_err(|e| error!("{:?}", e));
+
+    if overdue != Ok(0) {
+        return;
+    }
+
+    let member = GuildId(guild_id as u64)
+        .member(http, UserId(user_id as u64))
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    let member = match member {
+        Some(member) => member,
+        None => return,
+    };
+
+    if let Some(role_id) = guild.shame_role {
+        if member.roles.contains(&RoleId(role_id as u64)) {
+            http.remove_member_role(
+                guild_id as u64,
+                user_id as u64,
+                role_id as u64,
+                Some("overdue tasks completed"),
+            )
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok();
+        }
+    }
+
+    if let (Some(prefix), Some(nick)) = (guild.shame_prefix.as_ref(), member.nick.as_ref()) {
+        if let Some(original) = nick.strip_prefix(prefix.as_str()) {
+            // an empty nickname resets it back to the username
+            let original = if original == member.user.name {
+                ""
+            } else {
+                original
+            };
+
+            set_nickname(http, guild_id as u64, user_id as u64, original).await;
+        }
+    }
+
+    info!("lifted shame for user {} in guild {}", user_id, guild_id);
+}
+
+fn display_name(member: &Member) -> String {
+    member
+        .nick
+        .clone()
+        .unwrap_or_else(|| member.user.name.clone())
+}
+
+async fn set_nickname(http: &Http, guild_id: u64, user_id: u64, nickname: &str) {
+    GuildId(guild_id)
+        .edit_member(http, UserId(user_id), |m| m.nickname(nickname))
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+}
+
+// the reasons come from check_role and are only available in english, since
+// they're shown on the dashboard as well
+async fn report(http: &Http, db_client: &DbClient, guild: &Guild, reason: &str) {
+    let locale = Locale::resolve(None, guild.locale.as_deref());
+    let message = locale::format(locale, Text::ShameFailed, &[("reason", reason)]);
+
+    notification_channel(db_client, guild, Some(NotificationKind::Overdue))
+        .await
+        .send_message(http, |m| m.content(message))
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+}
@@ -0,0 +1,362 @@
+// the wording of reminders, pester messages, overdue notices and completions
+// comes from the guild's own template for that kind of message, or from the
+// preset of its personality otherwise.
+//
+// templates can contain {user}, {task}, {due}, {left} and {partner}, which
+// mentions every accepted accountability partner. lines with a placeholder
+// that has no value (no due date, no partners) are dropped, so optional
+// sentences go on their own line
+use chrono::Utc;
+use database::prelude::{
+    Client as DbClient, Guild, MessageTemplate, NotificationKind, Personality,
+};
+use log::error;
+
+use crate::locale::{self, Locale};
+
+pub const PLACEHOLDERS: [&str; 5] = ["user", "task", "due", "left", "partner"];
+
+// leaves room for whatever the placeholders expand to, discord caps messages
+// at 2000 characters
+const MAX_TEMPLATE: usize = 1000;
+
+pub struct Placeholders {
+    pub user: String,
+    pub task: String,
+    pub due: Option<String>,
+    pub left: Option<String>,
+    pub partner: Option<String>,
+}
+
+impl Placeholders {
+    pub fn new(user_id: i64, task: &str) -> Self {
+        Placeholders {
+            user: format!("<@{:?}>", user_id),
+            task: task.to_string(),
+            due: None,
+            left: None,
+            partner: None,
+        }
+    }
+
+    // discord renders the timestamp in the reader's own timezone and language,
+    // the time that's left is spelled out in the recipient's locale
+    pub fn due(mut self, due_at: Option<i64>, locale: Locale) -> Self {
+        let now = Utc::now().timestamp();
+
+        self.due = due_at.map(|due_at| format!("<t:{:?}>", due_at));
+        self.left = due_at
+            .filter(|due_at| *due_at > now)
+            .map(|due_at| locale::duration(locale, due_at - now));
+        self
+    }
+
+    pub fn partners(mut self, partner_ids: &[i64]) -> Self {
+        self.partner = match partner_ids.is_empty() {
+            true => None,
+            false => Some(
+                partner_ids
+                    .iter()
+                    .map(|partner_id| format!("<@{:?}>", partner_id))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ),
+        };
+        self
+    }
+
+    // stand-ins used when previewing a template from the dashboard
+    pub fn sample() -> Self {
+        Placeholders {
+            user: String::from("@you"),
+            task: String::from("Water the plants"),
+            due: Some(String::from("tomorrow at 18:00")),
+            left: Some(String::from("one hour")),
+            partner: Some(String::from("@partner")),
+        }
+    }
+}
+
+// the other personalities only exist in english for now
+pub fn preset(
+    personality: Personality,
+    kind: NotificationKind,
+    locale: Locale,
+) -> Option<&'static str> {
+    match (personality, locale) {
+        (Personality::Default, Locale::German) => german(kind),
+        (Personality::Default, Locale::French) => french(kind),
+        _ => english(personality, kind),
+    }
+}
+
+fn english(personality: Personality, kind: NotificationKind) -> Option<&'static str> {
+    let template = match (personality, kind) {
+        (Personality::Default, NotificationKind::Reminder) => {
+            "hey {user}! you have _{left}_ to finish {task}."
+        }
+        (Personality::Default, NotificationKind::Pester) => {
+            "hey {user}! {task} still isn't finished yet >:c\n\
+            {partner} would be _very_ upset with you if you didn't finish on time.\n\
+            \n\
+            you have until {due}. use your time wisely."
+        }
+        (Personality::Default, NotificationKind::Overdue) => {
+            "your time to complete {task} is up, {user}. i am very disappointed in you.\n\
+            \n\
+            {partner}, how could you let this happen?"
+        }
+        (Personality::Default, NotificationKind::Completion) => {
+            "{user} finished **{task}**! :tada:"
+        }
+        (Personality::GentleCoach, NotificationKind::Reminder) => {
+            "hi {user}, just a friendly heads-up: {task} is due in {left}. you've got this!"
+        }
+        (Personality::GentleCoach, NotificationKind::Pester) => {
+            "hey {user}, how is {task} coming along? one small step at a time.\n\
+            {partner} is cheering you on!\n\
+            \n\
+            it's due {due}, there's still time."
+        }
+        (Personality::GentleCoach, NotificationKind::Overdue) => {
+            "{task} didn't get finished in time, {user}. that's okay, tomorrow is a new day.\n\
+            \n\
+            {partner}, maybe check in on them?"
+        }
+        (Personality::GentleCoach, NotificationKind::Completion) => {
+            "well done, {user}! **{task}** is finished. :seedling:"
+        }
+        (Personality::DrillSergeant, NotificationKind::Reminder) => {
+            "LISTEN UP {user}! {left} LEFT ON {task}. MOVE IT!"
+        }
+        (Personality::DrillSergeant, NotificationKind::Pester) => {
+            "{user}! {task} IS STILL NOT DONE! DROP AND GIVE ME TWENTY!\n\
+            {partner} IS WATCHING YOU, RECRUIT.\n\
+            \n\
+            DEADLINE IS {due}. NO EXCUSES."
+        }
+        (Personality::DrillSergeant, NotificationKind::Overdue) => {
+            "{user}, YOU FAILED TO COMPLETE {task}. THAT IS UNACCEPTABLE.\n\
+            \n\
+            {partner}, YOUR RECRUIT HAS LET YOU DOWN."
+        }
+        (Personality::DrillSergeant, NotificationKind::Completion) => {
+            "{user} COMPLETED **{task}**. ADEQUATE. DISMISSED."
+        }
+        (Personality::PassiveAggressive, NotificationKind::Reminder) => {
+            "oh {user}, no rush or anything, but {task} is due in {left}. just thought you'd want to know."
+        }
+        (Personality::PassiveAggressive, NotificationKind::Pester) => {
+            "so {user}, {task} still isn't done. which is fine. totally fine.\n\
+            {partner} is also fine with it. probably.\n\
+            \n\
+            it's only due {due}, after all."
+        }
+        (Personality::PassiveAggressive, NotificationKind::Overdue) => {
+            "{task} is overdue, {user}. not that anyone expected anything different.\n\
+            \n\
+            {partner}, i'm sure you did your best."
+        }
+        (Personality::PassiveAggressive, NotificationKind::Completion) => {
+            "wow, {user} actually finished **{task}**. who would've thought."
+        }
+        _ => return None,
+    };
+
+    Some(template)
+}
+
+fn german(kind: NotificationKind) -> Option<&'static str> {
+    let template = match kind {
+        NotificationKind::Reminder => "hey {user}! du hast noch _{left}_, um {task} zu erledigen.",
+        NotificationKind::Pester => {
+            "hey {user}! {task} ist immer noch nicht erledigt >:c\n\
+            {partner} wäre _sehr_ enttäuscht, wenn du nicht rechtzeitig fertig wirst.\n\
+            \n\
+            du hast bis {due}. nutze deine Zeit weise."
+        }
+        NotificationKind::Overdue => {
+            "deine Zeit für {task} ist abgelaufen, {user}. ich bin sehr enttäuscht von dir.\n\
+            \n\
+            {partner}, wie konntest du das zulassen?"
+        }
+        NotificationKind::Completion => "{user} hat **{task}** erledigt! :tada:",
+        _ => return None,
+    };
+
+    Some(template)
+}
+
+fn french(kind: NotificationKind) -> Option<&'static str> {
+    let template = match kind {
+        NotificationKind::Reminder => "hé {user} ! il te reste _{left}_ pour terminer {task}.",
+        NotificationKind::Pester => {
+            "hé {user} ! {task} n'est toujours pas terminée >:c\n\
+            {partner} serait _très_ déçu·e si tu ne finissais pas à temps.\n\
+            \n\
+            tu as jusqu'à {due}. utilise bien ton temps."
+        }
+        NotificationKind::Overdue => {
+            "le temps pour terminer {task} est écoulé, {user}. je suis très déçu de toi.\n\
+            \n\
+            {partner}, comment as-tu pu laisser faire ça ?"
+        }
+        NotificationKind::Completion => "{user} a terminé **{task}** ! :tada:",
+        _ => return None,
+    };
+
+    Some(template)
+}
+
+// returns a user-facing reason if the template can't be used for the kind
+pub fn validate(kind: NotificationKind, template: &str) -> Result<(), String> {
+    if english(Personality::Default, kind).is_none() {
+        return Err(String::from("this kind of message can't be customized"));
+    }
+
+    if template.trim().is_empty() {
+        return Err(String::from("the template can't be empty"));
+    }
+
+    if template.chars().count() > MAX_TEMPLATE {
+        return Err(format!(
+            "the template can't be longer than {} characters",
+            MAX_TEMPLATE
+        ));
+    }
+
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| String::from("the template has an unclosed {"))?;
+        let name = &rest[start + 1..start + end];
+
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "{{{}}} isn't a placeholder, use one of {{user}}, {{task}}, {{due}}, {{left}} or {{partner}}",
+                name
+            ));
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+
+    Ok(())
+}
+
+pub fn render(template: &str, placeholders: &Placeholders) -> String {
+    let due = placeholders.due.as_deref();
+    let left = placeholders.left.as_deref();
+    let partner = placeholders.partner.as_deref();
+
+    template
+        .lines()
+        .filter(|line| !(line.contains("{due}") && due.is_none()))
+        .filter(|line| !(line.contains("{left}") && left.is_none()))
+        .filter(|line| !(line.contains("{partner}") && partner.is_none()))
+        .map(|line| {
+            line.replace("{user}", &placeholders.user)
+                .replace("{task}", &placeholders.task)
+                .replace("{due}", due.unwrap_or_default())
+                .replace("{left}", left.unwrap_or_default())
+                .replace("{partner}", partner.unwrap_or_default())
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+// the guild's template for the kind, falling back to its personality in the
+// recipient's locale. custom templates are used as written
+pub async fn resolve(
+    db_client: &DbClient,
+    guild: &Guild,
+    kind: NotificationKind,
+    locale: Locale,
+) -> String {
+    let custom = MessageTemplate::get(db_client, guild.id, kind)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok()
+        .flatten()
+        .and_then(|t| t.template);
+
+    custom
+        .or_else(|| preset(guild.personality, kind, locale).map(String::from))
+        .unwrap_or_default()
+}
+
+pub(crate) async fn message(
+    db_client: &DbClient,
+    guild: &Guild,
+    kind: NotificationKind,
+    locale: Locale,
+    placeholders: &Placeholders,
+) -> String {
+    let template = resolve(db_client, guild, kind, locale).await;
+
+    render(&template, placeholders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_accepts_known_placeholders() {
+        let template = "{user}, {task} is due {due} ({left} left)\n{partner}";
+        assert_eq!(validate(NotificationKind::Pester, template), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_bad_templates() {
+        let kind = NotificationKind::Reminder;
+        assert!(validate(NotificationKind::Leaderboard, "{user}").is_err());
+        assert!(validate(kind, "  \n").is_err());
+        assert!(validate(kind, &"a".repeat(MAX_TEMPLATE + 1)).is_err());
+        assert!(validate(kind, "hey {user").is_err());
+        assert!(validate(kind, "hey {name}").is_err());
+    }
+
+    #[test]
+    fn render_fills_in_placeholders() {
+        let placeholders = Placeholders::new(42, "Water the plants");
+        assert_eq!(
+            render("{user}: **{task}**", &placeholders),
+            "<@42>: **Water the plants**"
+        );
+    }
+
+    #[test]
+    fn render_drops_lines_without_values() {
+        let placeholders = Placeholders::new(42, "Water the plants");
+        let template = "hey {user}!\n{partner} is watching.\nit's due {due}.\n";
+        assert_eq!(render(template, &placeholders), "hey <@42>!");
+
+        let placeholders = placeholders.partners(&[1, 2]);
+        assert_eq!(
+            render(template, &placeholders),
+            "hey <@42>!\n<@1>, <@2> is watching."
+        );
+    }
+
+    #[test]
+    fn every_preset_is_valid() {
+        let kinds = [
+            NotificationKind::Reminder,
+            NotificationKind::Pester,
+            NotificationKind::Overdue,
+            NotificationKind::Completion,
+        ];
+
+        for locale in Locale::ALL {
+            for kind in kinds {
+                let template = preset(Personality::Default, kind, locale).unwrap();
+                assert_eq!(validate(kind, template), Ok(()));
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// guilds can opt into giving every task its own thread, which keeps busy
+// channels readable. the thread hangs off a short starter message posted
+// in send_to the first time the bot has something to say about the task
+use database::prelude::{Client as DbClient, Guild, NotificationKind, Task};
+use log::{error, info};
+use serenity::{
+    http::Http,
+    model::prelude::{Channel, ChannelId, ChannelType},
+};
+
+use crate::channels::notification_channel;
+use crate::locale::{self, Text};
+
+// one day, which every guild supports regardless of boost level
+const AUTO_ARCHIVE_MINUTES: u16 = 1440;
+
+// resolves where messages about a task should go, creating its thread if
+// needed. without threads (or if creating one fails) the guild's channel for
+// the kind of message is used instead
+pub(crate) async fn task_channel(
+    http: &Http,
+    db_client: &DbClient,
+    guild: &Guild,
+    task: &Task,
+    kind: Option<NotificationKind>,
+) -> ChannelId {
+    if !guild.task_threads {
+        return notification_channel(db_client, guild, kind).await;
+    }
+
+    if let Some(thread_id) = task.thread_id {
+        return ChannelId(thread_id as u64);
+    }
+
+    let channel_id = ChannelId(guild.send_to.unwrap_or_default() as u64);
+    let locale = locale::for_user(db_client, task.user_id, Some(guild)).await;
+    let starter = locale::format(
+        locale,
+        Text::ThreadStarter,
+        &[
+            ("task", &task.title),
+            ("user", &format!("<@{:?}>", task.user_id)),
+        ],
+    );
+
+    let starter = channel_id
+        .send_message(http, |m| m.content(starter))
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    let thread = match starter {
+        Some(starter) => channel_id
+            .create_public_thread(http, starter.id, |t| {
+                t.name(&task.title)
+                    .auto_archive_duration(AUTO_ARCHIVE_MINUTES)
+            })
+            .await
+            .map_err(|e| error!("{:?}", e))
+            .ok(),
+        None => None,
+    };
+
+    match thread {
+        Some(thread) => {
+            Task::set_thread(db_client, task.id, *thread.id.as_u64() as i64)
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .ok();
+
+            info!("created thread {:?} for task {:?}", thread.id, task.id);
+
+            thread.id
+        }
+        None => notification_channel(db_client, guild, kind).await,
+    }
+}
+
+// posting in an archived thread unarchives it, so a task that gets
+// unchecked later on picks up where it left off
+pub(crate) async fn archive(http: &Http, channel_id: ChannelId) {
+    let channel = channel_id
+        .to_channel(http)
+        .await
+        .map_err(|e| error!("{:?}", e))
+        .ok();
+
+    if let Some(Channel::Guild(channel)) = channel {
+        if channel.kind == ChannelType::PublicThread {
+            channel_id
+                .edit_thread(http, |t| t.archived(true))
+                .await
+                .map_err(|e| error!("{:?}", e))
+                .ok();
+        }
+    }
+}
@@ -3,6 +3,38 @@ export type Guild = {
     name: string,
     icon?: string,
     send_to?: number,
+    shame_role?: number,
+    shame_prefix?: string,
+    task_threads: boolean,
+    personality: Personality,
+    locale?: string,
+}
+
+export type Personality =
+    | "default"
+    | "gentle_coach"
+    | "drill_sergeant"
+    | "passive_aggressive"
+
+export type NotificationKind =
+    | "reminder"
+    | "pester"
+    | "overdue"
+    | "completion"
+    | "leaderboard"
+    | "accountability"
+
+export type NotificationChannel = {
+    guild_id: number,
+    kind: NotificationKind,
+    channel_id?: number,
+}
+
+// templates can use {user}, {task}, {due}, {left} and {partner}
+export type MessageTemplate = {
+    guild_id: number,
+    kind: NotificationKind,
+    template?: string,
 }
 
 export type User = {
@@ -10,12 +42,52 @@ export type User = {
     username: string,
     discriminator: string,
     avatar_hash: string,
+    locale?: string,
 }
 
+export type Delivery = "channel" | "dm" | "both"
+
+export type DeliveryPreference = {
+    user_id: number,
+    kind: NotificationKind,
+    delivery?: Delivery,
+}
+
+// deleted lists and tasks are only ever returned by the restore endpoints
 export type List = {
     id: string,
     title: string,
     user_id: number,
+    archived_at?: number,
+    deleted_at?: number,
+}
+
+// due_offset is in seconds after the start date the template is instantiated
+// with, pester falls back to the template's
+export type TemplateTask = {
+    title: string,
+    content?: string,
+    due_offset?: number,
+    pester?: number,
+}
+
+export type ListTemplate = {
+    id: string,
+    title: string,
+    user_id: number,
+    pester?: number,
+    created_at: number,
+    tasks: TemplateTask[],
+}
+
+export type ListRole = "owner" | "editor" | "viewer"
+
+// the list's creator (List.user_id) is always an owner without being listed
+export type ListMember = {
+    list_id: string,
+    user_id: number,
+    role: ListRole,
+    added_at: number,
 }
 
 export type Task = {
@@ -32,6 +104,96 @@ export type Task = {
     pester_job?: string,
     overdue_job?: string,
     reminder_job?: string,
+    thread_id?: number,
+    checked_at?: number,
+    lateness?: number,
+    quorum: Quorum,
+    stake: number,
+    penalty?: string,
+    stake_to_partners: boolean,
+    auto_check: boolean,
+    priority: Priority,
+    // lowercase and sorted, the API normalizes whatever is sent
+    tags: string[],
+    archived_at?: number,
+    deleted_at?: number,
+}
+
+export type Priority = "low" | "normal" | "high" | "urgent"
+
+export type TaskSort = "due" | "priority" | "tag" | "checked"
+
+// unchecked tasks due before now, during the current UTC day or week
+export type DueWindow = "overdue" | "today" | "week"
+
+export type UserSort = "username" | "id"
+
+// sent to /tasks/bulk, applied all or nothing
+export type BulkOperation =
+    | { op: "create", task: Task }
+    | { op: "update", task: Task }
+    | { op: "check", task_id: string, checked: boolean }
+    | { op: "delete", task_id: string }
+
+// when one operation fails, the others are 424 since nothing was applied
+export type OperationResult = {
+    status: number,
+    task?: Task,
+    error?: { message: string },
+}
+
+// task_id can't be worked on before depends_on is done
+export type TaskDependency = {
+    task_id: string,
+    depends_on: string,
+    created_at: number,
+}
+
+export type TaskItem = {
+    id: string,
+    task_id: string,
+    title: string,
+    position: number,
+    checked: boolean,
+    checked_at?: number,
+}
+
+// how many of the accepted partners have to approve proof
+export type Quorum = "any" | "majority" | "all"
+
+export type TaskEventKind =
+    | "created"
+    | "edited"
+    | "checked"
+    | "unchecked"
+    | "due_changed"
+    | "proof_attached"
+    | "proof_approved"
+    | "job_fired"
+    | "assigned"
+    | "archived"
+    | "unarchived"
+    | "deleted"
+    | "restored"
+
+export type TaskEvent = {
+    id: number,
+    task_id: string,
+    kind: TaskEventKind,
+    actor?: number,
+    // changed fields as { field: [old, new] }, { job } for job_fired, or
+    // { list_id } when the task was archived, deleted or restored with its list
+    details?: Record<string, unknown>,
+    created_at: number,
+}
+
+export type HabitStats = {
+    on_time: number,
+    late: number,
+    missed: number,
+    current_streak: number,
+    longest_streak: number,
+    average_lateness?: number,
 }
 
 export type Proof = {
@@ -54,6 +216,33 @@ export type AccountabilityRequest = {
     status: RequestStatus,
 }
 
+// exactly one of list_id and guild_id is set
+export type Partnership = {
+    id: string,
+    user_id: number,
+    partner_id: number,
+    list_id?: string,
+    guild_id?: number,
+    status: RequestStatus,
+    created_at: number,
+}
+
+export type ChangeKind = "update" | "delete" | "unpartner"
+
+// due_at, pester and checked are the values the owner asked for
+export type PendingChange = {
+    task_id: string,
+    requested_by: number,
+    kind: ChangeKind,
+    due_at?: number,
+    pester?: number,
+    quorum: Quorum,
+    stake: number,
+    checked: boolean,
+    partner_id?: number, // dropped by an unpartner change, every partner if missing
+    created_at: number,
+}
+
 export type Token = {
     id: string,
     access_token: string,
@@ -68,3 +257,51 @@ export type ApiKey = {
     discord_token: string,
     key: string,
 }
+
+export type LeaderboardEntry = {
+    user_id: number,
+    completed: number,
+    overdue: number,
+    completion_rate: number,
+    streak: number,
+}
+
+export type PointsReason = "stake_lost" | "stake_won"
+
+// amount is negative for lost stakes
+export type PointsEntry = {
+    id: number,
+    guild_id: number,
+    user_id: number,
+    task_id?: string,
+    reason: PointsReason,
+    amount: number,
+    created_at: number,
+}
+
+export type Balance = {
+    user_id: number,
+    guild_id: number,
+    points: number,
+}
+
+export type SearchKind = "task" | "list" | "proof"
+
+// for proof, task_id and title belong to the task it's attached to. matched
+// words are wrapped in ** in the snippet
+export type SearchHit = {
+    kind: SearchKind,
+    id: string,
+    task_id?: string,
+    title: string,
+    snippet: string,
+    rank: number,
+}
+
+// pass next_cursor back as cursor for the following page, it's missing on
+// the last one
+export type Page<T> = {
+    items: T[],
+    total: number,
+    next_cursor?: string,
+}